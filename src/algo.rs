@@ -0,0 +1,352 @@
+//! Graph algorithms over the nodes and edges of a `Graph`, subgraphs
+//! included.
+//!
+//! Nodes are named like in `Graph::node_names`, so nodes only referenced by
+//! edges count, and results list nodes in that order where it is free. An
+//! edge `a -> {b c}` links `a` to both. The edges of a `Kind::Graph` go both
+//! ways, except for `toposort` which reads them as written.
+//!
+//! ```rust
+//! use dot_graph::{algo, Graph, Kind, Edge};
+//!
+//! let mut graph = Graph::new("build", Kind::Digraph);
+//! graph.add_edge(Edge::chain(&["core", "cli", "docs"], ""));
+//! graph.add_edge(Edge::new("core", "docs", ""));
+//! assert_eq!(algo::toposort(&graph).unwrap(), ["core", "cli", "docs"]);
+//! assert_eq!(algo::shortest_path(&graph, "core", "docs").unwrap(), ["core", "docs"]);
+//!
+//! graph.add_edge(Edge::new("docs", "core", ""));
+//! assert_eq!(algo::find_cycle(&graph).unwrap(), ["core", "cli", "docs", "core"]);
+//! ```
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+use crate::graph::{Graph, Kind};
+
+/// The edges of a graph by node, with nodes as indices in
+/// `Graph::node_names`.
+pub(crate) struct Adjacency {
+    pub(crate) names: Vec<String>,
+    pub(crate) index: HashMap<String, usize>,
+    /// The nodes after each node, with the index of the node pair of the
+    /// edge in the order of `Graph::all_edges`.
+    pub(crate) next: Vec<Vec<(usize, usize)>>,
+    /// The nodes before each node, like `next`.
+    pub(crate) previous: Vec<Vec<(usize, usize)>>,
+}
+
+impl Adjacency {
+    pub(crate) fn new(graph: &Graph, both_ways: bool) -> Self {
+        let names = graph.node_names();
+        let index: HashMap<String, usize> = names.iter().enumerate().map(|(i, n)| (n.clone(), i)).collect();
+        let mut next = vec![vec![]; names.len()];
+        let mut previous = vec![vec![]; names.len()];
+        let pairs = graph.all_edges().into_iter().flat_map(|e| e.node_pairs());
+        for (edge, (from, to)) in pairs.enumerate() {
+            let (from, to) = (index[&from], index[&to]);
+            next[from].push((to, edge));
+            previous[to].push((from, edge));
+            if both_ways && from != to {
+                next[to].push((from, edge));
+                previous[from].push((to, edge));
+            }
+        }
+        Adjacency { names, index, next, previous }
+    }
+
+    /// The graph as its `Kind` reads it.
+    pub(crate) fn of(graph: &Graph) -> Self {
+        Adjacency::new(graph, graph.kind == Kind::Graph)
+    }
+
+    pub(crate) fn names(&self, nodes: impl IntoIterator<Item = usize>) -> Vec<String> {
+        nodes.into_iter().map(|i| self.names[i].clone()).collect()
+    }
+
+    /// Breadth-first search from `start`, giving for each node reached the
+    /// node it was reached from; `start` is reached from itself.
+    pub(crate) fn search(&self, start: usize, forward: bool) -> Vec<Option<usize>> {
+        let edges = if forward { &self.next } else { &self.previous };
+        let mut from = vec![None; self.names.len()];
+        from[start] = Some(start);
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for &(other, _) in edges[node].iter() {
+                if from[other].is_none() {
+                    from[other] = Some(node);
+                    queue.push_back(other);
+                }
+            }
+        }
+        from
+    }
+
+    /// The nodes of a cycle, with the first one again at the end. An
+    /// undirected cycle does not go back over the edge it came from.
+    pub(crate) fn cycle(&self, undirected: bool) -> Option<Vec<usize>> {
+        #[derive(Copy, Clone, PartialEq)]
+        enum Mark {
+            New,
+            Open,
+            Done,
+        }
+        let mut marks = vec![Mark::New; self.names.len()];
+        for start in 0..self.names.len() {
+            if marks[start] != Mark::New {
+                continue;
+            }
+            marks[start] = Mark::Open;
+            // the open nodes, with the edge they were reached by and the
+            // next of their edges to follow
+            let mut path: Vec<(usize, Option<usize>, usize)> = vec![(start, None, 0)];
+            while let Some(top) = path.last_mut() {
+                let (node, by) = (top.0, top.1);
+                let Some(&(to, edge)) = self.next[node].get(top.2) else {
+                    marks[node] = Mark::Done;
+                    path.pop();
+                    continue;
+                };
+                top.2 += 1;
+                if undirected && by == Some(edge) {
+                    continue;
+                }
+                match marks[to] {
+                    Mark::New => {
+                        marks[to] = Mark::Open;
+                        path.push((to, Some(edge), 0));
+                    }
+                    Mark::Open => {
+                        let first = path.iter().position(|p| p.0 == to).unwrap();
+                        let mut cycle: Vec<usize> = path[first..].iter().map(|p| p.0).collect();
+                        cycle.push(to);
+                        return Some(cycle);
+                    }
+                    Mark::Done => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// Strongly connected components by Tarjan's algorithm, each in node
+    /// order, and ordered by their first node.
+    pub(crate) fn components(&self) -> Vec<Vec<usize>> {
+        let count = self.names.len();
+        let mut tarjan = Tarjan { index: vec![usize::MAX; count], low: vec![0; count], stack: vec![], on_stack: vec![false; count], visited: 0 };
+        let mut components = vec![];
+        for start in 0..count {
+            if tarjan.index[start] != usize::MAX {
+                continue;
+            }
+            tarjan.visit(start);
+            let mut calls = vec![(start, 0)];
+            while let Some(call) = calls.last_mut() {
+                let node = call.0;
+                if let Some(&(to, _)) = self.next[node].get(call.1) {
+                    call.1 += 1;
+                    if tarjan.index[to] == usize::MAX {
+                        tarjan.visit(to);
+                        calls.push((to, 0));
+                    } else if tarjan.on_stack[to] {
+                        tarjan.low[node] = tarjan.low[node].min(tarjan.index[to]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    tarjan.low[parent] = tarjan.low[parent].min(tarjan.low[node]);
+                }
+                if tarjan.low[node] == tarjan.index[node] {
+                    let mut component = vec![];
+                    while let Some(member) = tarjan.stack.pop() {
+                        tarjan.on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.sort();
+                    components.push(component);
+                }
+            }
+        }
+        components.sort();
+        components
+    }
+
+    /// Whether each node pair is a bridge of the undirected graph, that is
+    /// on no cycle, by the low links of a depth-first search. Parallel
+    /// edges are told apart by their pair, so they are never bridges.
+    pub(crate) fn bridges(&self) -> Vec<bool> {
+        let count = self.names.len();
+        let pairs = self.next.iter().flatten().map(|&(_, edge)| edge + 1).max().unwrap_or(0);
+        let mut bridges = vec![false; pairs];
+        let (mut index, mut low) = (vec![usize::MAX; count], vec![0; count]);
+        let mut visited = 0;
+        for start in 0..count {
+            if index[start] != usize::MAX {
+                continue;
+            }
+            index[start] = visited;
+            low[start] = visited;
+            visited += 1;
+            // the node, the pair it was reached by and the next one to follow
+            let mut calls = vec![(start, usize::MAX, 0)];
+            while let Some(call) = calls.last_mut() {
+                let (node, by) = (call.0, call.1);
+                if let Some(&(to, edge)) = self.next[node].get(call.2) {
+                    call.2 += 1;
+                    if edge == by {
+                        continue;
+                    }
+                    if index[to] == usize::MAX {
+                        index[to] = visited;
+                        low[to] = visited;
+                        visited += 1;
+                        calls.push((to, edge, 0));
+                    } else {
+                        low[node] = low[node].min(index[to]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some(&(parent, _, _)) = calls.last() {
+                    low[parent] = low[parent].min(low[node]);
+                    bridges[by] = low[node] > index[parent];
+                }
+            }
+        }
+        bridges
+    }
+}
+
+/// The state of Tarjan's algorithm, by node.
+struct Tarjan {
+    index: Vec<usize>,
+    low: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    visited: usize,
+}
+
+impl Tarjan {
+    fn visit(&mut self, node: usize) {
+        self.index[node] = self.visited;
+        self.low[node] = self.visited;
+        self.visited += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+    }
+}
+
+/// The nodes in an order where every edge goes forward, earlier nodes
+/// first when there is a choice; or the nodes of a cycle, see `find_cycle`.
+pub fn toposort(graph: &Graph) -> Result<Vec<String>, Vec<String>> {
+    let adjacency = Adjacency::new(graph, false);
+    let mut incoming: Vec<usize> = adjacency.previous.iter().map(Vec::len).collect();
+    let mut ready: BinaryHeap<Reverse<usize>> =
+        incoming.iter().enumerate().filter(|(_, count)| **count == 0).map(|(i, _)| Reverse(i)).collect();
+    let mut order = vec![];
+    while let Some(Reverse(node)) = ready.pop() {
+        order.push(node);
+        for &(to, _) in adjacency.next[node].iter() {
+            incoming[to] -= 1;
+            if incoming[to] == 0 {
+                ready.push(Reverse(to));
+            }
+        }
+    }
+    if order.len() < adjacency.names.len() {
+        let cycle = adjacency.cycle(false).expect("nodes left by a topological sort are on a cycle");
+        return Err(adjacency.names(cycle));
+    }
+    Ok(adjacency.names(order))
+}
+
+/// The nodes of a cycle, with the first one again at the end, like
+/// `["a", "b", "a"]`. A self loop is `["a", "a"]`; in a `Kind::Graph` a
+/// cycle does not use an edge twice, so a lone `a -- b` is none.
+pub fn find_cycle(graph: &Graph) -> Option<Vec<String>> {
+    let adjacency = Adjacency::of(graph);
+    adjacency.cycle(graph.kind == Kind::Graph).map(|cycle| adjacency.names(cycle))
+}
+
+/// The groups of nodes that can all reach each other, the connected
+/// components for a `Kind::Graph`. Every node is in one group.
+pub fn strongly_connected_components(graph: &Graph) -> Vec<Vec<String>> {
+    let adjacency = Adjacency::of(graph);
+    adjacency.components().into_iter().map(|c| adjacency.names(c)).collect()
+}
+
+/// The groups of nodes linked by edges in either direction, the weakly
+/// connected components for a `Kind::Digraph`.
+pub fn connected_components(graph: &Graph) -> Vec<Vec<String>> {
+    let adjacency = Adjacency::new(graph, true);
+    // one buffer for all the searches, as there may be many small components
+    let mut seen = vec![false; adjacency.names.len()];
+    let mut components = vec![];
+    for start in 0..adjacency.names.len() {
+        if seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut component = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for &(other, _) in adjacency.next[node].iter() {
+                if !seen[other] {
+                    seen[other] = true;
+                    component.push(other);
+                    queue.push_back(other);
+                }
+            }
+        }
+        component.sort();
+        components.push(adjacency.names(component));
+    }
+    components
+}
+
+/// Whether a path leads from `from` to `to`, which is true for the same
+/// node.
+pub fn is_reachable(graph: &Graph, from: &str, to: &str) -> bool {
+    shortest_path(graph, from, to).is_some()
+}
+
+/// The nodes `id` leads to, without `id`.
+pub fn descendants(graph: &Graph, id: &str) -> Vec<String> {
+    reached(graph, id, true)
+}
+
+/// The nodes leading to `id`, without `id`.
+pub fn ancestors(graph: &Graph, id: &str) -> Vec<String> {
+    reached(graph, id, false)
+}
+
+fn reached(graph: &Graph, id: &str, forward: bool) -> Vec<String> {
+    let adjacency = Adjacency::of(graph);
+    let Some(&start) = adjacency.index.get(id) else {
+        return vec![];
+    };
+    let reached = adjacency.search(start, forward);
+    adjacency.names((0..reached.len()).filter(|&i| i != start && reached[i].is_some()))
+}
+
+/// A path with the fewest edges from `from` to `to`, both included, or
+/// `None` if there is none or a node is unknown.
+pub fn shortest_path(graph: &Graph, from: &str, to: &str) -> Option<Vec<String>> {
+    let adjacency = Adjacency::of(graph);
+    let (&start, &end) = (adjacency.index.get(from)?, adjacency.index.get(to)?);
+    let reached = adjacency.search(start, true);
+    let mut path = vec![end];
+    let mut node = end;
+    while node != start {
+        node = reached[node]?;
+        path.push(node);
+    }
+    path.reverse();
+    Some(adjacency.names(path))
+}
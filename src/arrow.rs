@@ -1,6 +1,7 @@
 
 /// This structure holds all information that can describe an arrow connected to
 /// either start or end of an edge.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Hash, PartialEq, Eq)]
 pub struct Arrow {
     pub arrows: Vec<ArrowShape>,
@@ -14,13 +15,6 @@ impl Arrow {
         self.arrows.is_empty()
     }
 
-    /// Arrow constructor which returns a default arrow
-    pub fn default() -> Arrow {
-        Arrow {
-            arrows: vec![],
-        }
-    }
-
     /// Arrow constructor which returns an empty arrow
     pub fn none() -> Arrow {
         Arrow {
@@ -42,6 +36,31 @@ impl Arrow {
         }
     }
 
+    /// Parses an arrow in DOT syntax, like `"lcrow"` or `"invodot"`, as
+    /// produced by `to_dot_string`. Returns `None` if it is not valid.
+    pub fn from_dot_string(s: &str) -> Option<Arrow> {
+        // aliases kept by Graphviz for backward compatibility
+        let s = match s {
+            "ediamond" => "odiamond",
+            "open" => "vee",
+            "halfopen" => "lvee",
+            "empty" => "onormal",
+            "invempty" => "oinv",
+            s => s,
+        };
+        if s.is_empty() {
+            return Some(Arrow::default());
+        }
+        let mut arrows = vec![];
+        let mut rest = s;
+        while !rest.is_empty() {
+            let (shape, tail) = ArrowShape::parse_prefix(rest)?;
+            arrows.push(shape);
+            rest = tail;
+        }
+        Some(Arrow { arrows })
+    }
+
     /// Function which converts given arrow into a renderable form.
     pub fn to_dot_string(&self) -> String {
         let mut cow = String::new();
@@ -53,29 +72,40 @@ impl Arrow {
 }
 
 
-impl Into<Arrow> for [ArrowShape; 2] {
-    fn into(self) -> Arrow {
+impl Default for Arrow {
+    /// Arrow constructor which returns a default arrow
+    fn default() -> Arrow {
         Arrow {
-            arrows: vec![self[0], self[1]],
+            arrows: vec![],
+        }
+    }
+}
+
+impl From<[ArrowShape; 2]> for Arrow {
+    fn from(shapes: [ArrowShape; 2]) -> Arrow {
+        Arrow {
+            arrows: shapes.to_vec(),
         }
     }
 }
-impl Into<Arrow> for [ArrowShape; 3] {
-    fn into(self) -> Arrow {
+impl From<[ArrowShape; 3]> for Arrow {
+    fn from(shapes: [ArrowShape; 3]) -> Arrow {
         Arrow {
-            arrows: vec![self[0], self[1], self[2]],
+            arrows: shapes.to_vec(),
         }
     }
 }
-impl Into<Arrow> for [ArrowShape; 4] {
-    fn into(self) -> Arrow {
+impl From<[ArrowShape; 4]> for Arrow {
+    fn from(shapes: [ArrowShape; 4]) -> Arrow {
         Arrow {
-            arrows: vec![self[0], self[1], self[2], self[3]],
+            arrows: shapes.to_vec(),
         }
     }
 }
 
 /// Arrow modifier that determines if the shape is empty or filled.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
 pub enum Fill {
     Open,
@@ -93,6 +123,8 @@ impl Fill {
 
 /// Arrow modifier that determines if the shape is clipped.
 /// For example `Side::Left` means only left side is visible.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
 pub enum Side {
     Left,
@@ -113,9 +145,12 @@ impl Side {
 
 /// This enumeration represents all possible arrow edge
 /// as defined in [grapviz documentation](http://www.graphviz.org/content/arrow-shapes).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
 pub enum ArrowShape {
     /// No arrow will be displayed
+    #[cfg_attr(feature = "serde", serde(rename = "none"))]
     NoArrow,
     /// Arrow that ends in a triangle. Basically a normal arrow.
     /// NOTE: there is error in official documentation, this supports both fill and side clipping
@@ -195,6 +230,38 @@ impl ArrowShape {
         ArrowShape::Vee(Side::Both)
     }
 
+    /// Parses the first shape of `s`, returning it with the rest of `s`.
+    fn parse_prefix(s: &str) -> Option<(ArrowShape, &str)> {
+        let (fill, s) = match s.strip_prefix('o') {
+            Some(rest) => (Fill::Open, rest),
+            None => (Fill::Filled, s),
+        };
+        let (side, s) = if let Some(rest) = s.strip_prefix('l') {
+            (Side::Left, rest)
+        } else if let Some(rest) = s.strip_prefix('r') {
+            (Side::Right, rest)
+        } else {
+            (Side::Both, s)
+        };
+        let names = ["none", "normal", "box", "crow", "curve", "icurve",
+            "diamond", "dot", "inv", "tee", "vee"];
+        let name = names.iter().find(|n| s.starts_with(*n))?;
+        let shape = match *name {
+            "none" => NoArrow,
+            "normal" => Normal(fill, side),
+            "box" => Box(fill, side),
+            "crow" => Crow(side),
+            "curve" => Curve(side),
+            "icurve" => ICurve(fill, side),
+            "diamond" => Diamond(fill, side),
+            "dot" => Dot(fill),
+            "inv" => Inv(fill, side),
+            "tee" => Tee(side),
+            _ => Vee(side),
+        };
+        Some((shape, &s[name.len()..]))
+    }
+
     /// Function which renders given ArrowShape into a String for displaying.
     pub fn to_dot_string(&self) -> String {
         let mut res = String::new();
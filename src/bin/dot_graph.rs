@@ -0,0 +1,252 @@
+//! The `dot_graph` command line tool, enabled by the `cli` feature.
+//!
+//! Reads DOT (or JSON and GraphML for `convert`) from files or stdin and
+//! writes to stdout. Exits with 0 on success, 1 when `check` finds a
+//! problem or `diff` a difference, and 2 on usage errors or unreadable
+//! input.
+
+use std::{
+    fs,
+    io::{self, Read as _},
+    path::Path,
+    process::ExitCode,
+};
+
+use dot_graph::{dot, mermaid::Direction, Graph};
+
+const USAGE: &str = "Usage: dot_graph <command> [options] [file]
+
+Commands:
+  fmt [--canonical] [FILE]                 rewrite DOT with the dot_graph renderer, sorted
+                                           by name with --canonical
+  check [--strict] [FILE]...               report errors and dropped attributes
+  convert --to FORMAT [--from FORMAT] [FILE]
+                                           convert between dot, json, graphml and mermaid
+  stats [--json] [--top N] [FILE]          count nodes, edges and clusters, and measure
+                                           degrees, density, paths and the N (10) nodes
+                                           with the most fan-in/out
+  diff [--dot] OLD NEW                     list what was added, removed and changed, or
+                                           draw it in one graph with --dot
+
+FILE is read from stdin when missing or \"-\". JSON is the Graphviz json0 schema;
+mermaid is only written. --from defaults to the extension of FILE, then dot.
+check exits with 1 on errors, and on warnings too with --strict; diff exits with
+1 when the graphs differ.
+
+Some errors of check are limits of the dot_graph model, not DOT syntax errors:
+node names must be made of ASCII letters, digits, '_' and '.' and not start with
+a digit, so valid DOT like 1 -> 2, \"a b\" or é is rejected. What the model
+cannot hold otherwise, like attributes or strict, is only a warning.";
+
+/// What stops a command: a usage error or something unreadable.
+struct Failure(String);
+
+impl From<io::Error> for Failure {
+    fn from(e: io::Error) -> Self {
+        Failure(e.to_string())
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, args)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+    let result = match command.as_str() {
+        "fmt" => fmt(args),
+        "check" => check(args),
+        "convert" => convert(args),
+        "stats" => stats(args),
+        "diff" => diff(args),
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            Ok(ExitCode::SUCCESS)
+        }
+        _ => Err(Failure(format!("unknown command {:?}\n\n{}", command, USAGE))),
+    };
+    match result {
+        Ok(code) => code,
+        Err(Failure(message)) => {
+            eprintln!("dot_graph: {}", message);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// The contents of `path`, or of stdin for `None` and `"-"`.
+fn input(path: Option<&str>) -> Result<String, Failure> {
+    match path {
+        None | Some("-") => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            Ok(text)
+        }
+        Some(path) => fs::read_to_string(path).map_err(|e| Failure(format!("{}: {}", path, e))),
+    }
+}
+
+/// The name of the input in messages.
+fn display(path: Option<&str>) -> &str {
+    match path {
+        None | Some("-") => "<stdin>",
+        Some(path) => path,
+    }
+}
+
+/// At most one file argument, and no options.
+fn single(args: &[String]) -> Result<Option<&str>, Failure> {
+    if let Some(option) = args.iter().find(|a| a.starts_with("--")) {
+        return Err(Failure(format!("unknown option {}", option)));
+    }
+    match args {
+        [] => Ok(None),
+        [path] => Ok(Some(path.as_str())),
+        _ => Err(Failure(String::from("expected a single file"))),
+    }
+}
+
+/// Reads a DOT file, printing the warnings to stderr.
+fn read_dot(path: Option<&str>) -> Result<Graph, Failure> {
+    let text = input(path)?;
+    let read = dot::read(&text).map_err(|e| Failure(format!("{}: {}", display(path), e)))?;
+    for warning in read.warnings.iter() {
+        eprintln!("{}:{}: warning: {}", display(path), warning.line, warning.message);
+    }
+    Ok(read.graph)
+}
+
+fn fmt(args: &[String]) -> Result<ExitCode, Failure> {
+    let canonical = args.iter().any(|a| a == "--canonical");
+    let args: Vec<String> = args.iter().filter(|a| *a != "--canonical").cloned().collect();
+    let mut graph = read_dot(single(&args)?)?;
+    if canonical {
+        graph.canonicalize();
+    }
+    print!("{}", graph.to_dot_string()?);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn check(args: &[String]) -> Result<ExitCode, Failure> {
+    let strict = args.iter().any(|a| a == "--strict");
+    let mut paths: Vec<Option<&str>> = vec![];
+    for arg in args.iter().filter(|a| *a != "--strict") {
+        if arg.starts_with("--") {
+            return Err(Failure(format!("unknown option {}", arg)));
+        }
+        paths.push(Some(arg));
+    }
+    if paths.is_empty() {
+        paths.push(None);
+    }
+
+    let mut failed = false;
+    for path in paths {
+        let text = input(path)?;
+        match dot::read(&text) {
+            Ok(read) => {
+                for warning in read.warnings.iter() {
+                    println!("{}:{}: warning: {}", display(path), warning.line, warning.message);
+                }
+                failed |= strict && !read.warnings.is_empty();
+            }
+            Err(e) => {
+                // the message of a syntax error starts with its line
+                let message = e.to_string();
+                let (line, message) = message
+                    .strip_prefix("line ")
+                    .and_then(|m| m.split_once(": "))
+                    .unwrap_or(("1", &message));
+                println!("{}:{}: error: {}", display(path), line, message);
+                failed = true;
+            }
+        }
+    }
+    Ok(if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
+
+fn convert(args: &[String]) -> Result<ExitCode, Failure> {
+    let (mut from, mut to, mut path) = (None, None, None);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => from = Some(args.next().ok_or_else(|| Failure(String::from("--from needs a format")))?.as_str()),
+            "--to" => to = Some(args.next().ok_or_else(|| Failure(String::from("--to needs a format")))?.as_str()),
+            option if option.starts_with("--") => return Err(Failure(format!("unknown option {}", option))),
+            _ if path.is_some() => return Err(Failure(String::from("expected a single file"))),
+            file => path = Some(file),
+        }
+    }
+    let to = to.ok_or_else(|| Failure(String::from("convert needs --to")))?;
+    let from = from.unwrap_or_else(|| {
+        match path.and_then(|p| Path::new(p).extension()).and_then(|e| e.to_str()) {
+            Some("json") => "json",
+            Some("graphml") => "graphml",
+            _ => "dot",
+        }
+    });
+
+    let graph = match from {
+        "dot" | "gv" => read_dot(path)?,
+        "json" => Graph::from_graphviz_json(&input(path)?).map_err(|e| Failure(format!("{}: {}", display(path), e)))?,
+        "graphml" => Graph::from_graphml(&input(path)?).map_err(|e| Failure(format!("{}: {}", display(path), e)))?,
+        format => return Err(Failure(format!("cannot read {}, expected dot, json or graphml", format))),
+    };
+    let output = match to {
+        "dot" | "gv" => graph.to_dot_string()?,
+        "json" => graph.to_graphviz_json(),
+        "graphml" => graph.to_graphml(),
+        "mermaid" => {
+            let flowchart = graph.to_mermaid(Direction::TopDown);
+            for u in flowchart.untranslated.iter() {
+                eprintln!("warning: {} {}={} has no Mermaid equivalent", u.element, u.attribute, u.value);
+            }
+            flowchart.text
+        }
+        format => return Err(Failure(format!("cannot write {}, expected dot, json, graphml or mermaid", format))),
+    };
+    print!("{}", output);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn stats(args: &[String]) -> Result<ExitCode, Failure> {
+    let (mut json, mut top, mut path) = (false, dot_graph::stats::TOP, None);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--top" => {
+                let n = args.next().ok_or_else(|| Failure(String::from("--top needs a number")))?;
+                top = n.parse().map_err(|_| Failure(format!("invalid number {:?} for --top", n)))?;
+            }
+            option if option.starts_with("--") => return Err(Failure(format!("unknown option {}", option))),
+            _ if path.is_some() => return Err(Failure(String::from("expected a single file"))),
+            file => path = Some(file),
+        }
+    }
+    let stats = read_dot(path)?.stats_with(top);
+    if json {
+        print!("{}", stats.to_json());
+    } else {
+        print!("{}", stats);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn diff(args: &[String]) -> Result<ExitCode, Failure> {
+    let dot = args.iter().any(|a| a == "--dot");
+    let files: Vec<&String> = args.iter().filter(|a| *a != "--dot").collect();
+    if let Some(option) = files.iter().find(|a| a.starts_with("--")) {
+        return Err(Failure(format!("unknown option {}", option)));
+    }
+    let [old, new] = files.as_slice() else {
+        return Err(Failure(String::from("diff needs two files")));
+    };
+    let diff = read_dot(Some(old))?.diff(&read_dot(Some(new))?);
+    if dot {
+        print!("{}", diff.to_dot_string()?);
+    } else {
+        print!("{}", diff);
+    }
+    Ok(if diff.is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
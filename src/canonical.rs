@@ -0,0 +1,45 @@
+//! Sorting a graph so that its output does not depend on the order it was
+//! built in.
+
+use crate::{edge::Endpoint, graph::Graph};
+
+impl Graph {
+    /// Sorts subgraphs and nodes by name, edges by the names of their
+    /// endpoints and then by their attributes, and the names of `same_rank`
+    /// groups and of `{a b}` endpoints. Two graphs with the same elements
+    /// then render the same, whatever order they were added in.
+    ///
+    /// The attributes of an element are always rendered in the same order,
+    /// so they need no sorting. Changing the order of edges can change the
+    /// layout Graphviz picks.
+    pub fn canonicalize(&mut self) {
+        let edgeop = self.kind.edgeop();
+        let edges = self.subgraph.iter_mut().map(|s| &mut s.edges).chain(std::iter::once(&mut self.edges));
+        for edges in edges {
+            for e in edges.iter_mut() {
+                for endpoint in e.endpoints.iter_mut() {
+                    if let Endpoint::Subgraph { nodes, .. } = endpoint {
+                        nodes.sort();
+                    }
+                }
+            }
+            edges.sort_by_cached_key(|e| (e.endpoints.iter().map(Endpoint::node_names).collect::<Vec<_>>(), e.to_dot_string(edgeop)));
+        }
+        for s in self.subgraph.iter_mut() {
+            s.nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        self.subgraph.sort_by(|a, b| a.name.cmp(&b.name));
+        self.nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        for names in self.same_rank.iter_mut() {
+            names.sort();
+        }
+        self.same_rank.sort();
+    }
+
+    /// Renders a canonicalized copy, see `Graph::canonicalize`.
+    pub fn to_canonical_dot_string(&self) -> std::io::Result<String> {
+        let mut graph = self.clone();
+        graph.canonicalize();
+        graph.to_dot_string()
+    }
+}
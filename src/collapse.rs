@@ -0,0 +1,192 @@
+//! Drawing a cluster as a single node, and back.
+
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    edge::Edge,
+    graph::{Graph, Kind},
+    node::Node,
+    subgraph::Subgraph,
+};
+
+/// Why `Graph::collapse_subgraph` or `Graph::expand` failed; the graph is
+/// left unchanged.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CollapseError {
+    /// No subgraph has this name.
+    UnknownSubgraph(String),
+    /// A node already has the name of the subgraph to collapse.
+    NameTaken(String),
+    /// No subgraph of this name is collapsed.
+    NotCollapsed(String),
+}
+
+impl fmt::Display for CollapseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CollapseError::UnknownSubgraph(name) => write!(f, "no subgraph {}", name),
+            CollapseError::NameTaken(name) => write!(f, "a node is already called {}", name),
+            CollapseError::NotCollapsed(name) => write!(f, "no collapsed subgraph {}", name),
+        }
+    }
+}
+
+impl std::error::Error for CollapseError {}
+
+/// What `Graph::collapse_subgraph` changed, for `Graph::expand`.
+#[derive(Clone)]
+pub(crate) struct Collapsed {
+    /// The subgraph as it was, with its nodes and edges.
+    subgraph: Subgraph,
+    index: usize,
+    /// The edges of other subgraphs (by name) or of the graph using its
+    /// nodes, with their index there.
+    edges: Vec<(Option<String>, usize, Edge)>,
+    /// The edges to the summary node that replaced them.
+    added: Vec<Edge>,
+    /// The `same_rank` groups that had its nodes, by index.
+    same_rank: Vec<(usize, Vec<String>)>,
+    /// Whether the graph was `compound` before.
+    compound: bool,
+}
+
+impl Graph {
+    /// Replaces the subgraph `name` by a node of the same name, labelled
+    /// with the subgraph label (or its name without `cluster_`) and the
+    /// number of its nodes, like `"net (3)"`.
+    ///
+    /// Edges between its nodes are hidden; edges from or to them go to the
+    /// new node instead, at the top level, once per pair of nodes. An edge
+    /// with more node pairs is split. `lhead`/`ltail` naming the subgraph
+    /// are dropped, and `compound=true` stays only while an edge still has
+    /// one. `Graph::expand` undoes it.
+    ///
+    /// Fails with `CollapseError::UnknownSubgraph` for an unknown subgraph,
+    /// and with `CollapseError::NameTaken` when a node already has its name.
+    pub fn collapse_subgraph(&mut self, name: &str) -> Result<(), CollapseError> {
+        let index = self
+            .subgraph
+            .iter()
+            .position(|s| s.name == name)
+            .ok_or_else(|| CollapseError::UnknownSubgraph(String::from(name)))?;
+        if self.node_names().iter().any(|n| n == name) {
+            return Err(CollapseError::NameTaken(String::from(name)));
+        }
+        let subgraph = self.subgraph.remove(index);
+        let members: HashSet<&str> = subgraph.nodes.iter().map(|n| n.name.as_str()).collect();
+        let touches = |e: &Edge| e.node_pairs().iter().any(|(from, to)| members.contains(from.as_str()) || members.contains(to.as_str()));
+
+        let mut edges = vec![];
+        for s in self.subgraph.iter_mut() {
+            take_edges(&mut s.edges, Some(&s.name), touches, &mut edges);
+        }
+        take_edges(&mut self.edges, None, touches, &mut edges);
+
+        let rename = |n: String| if members.contains(n.as_str()) { String::from(name) } else { n };
+        let undirected = self.kind == Kind::Graph;
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        let mut added = vec![];
+        for edge in edges.iter().map(|(_, _, e)| e).chain(subgraph.edges.iter()) {
+            for (from, to) in edge.node_pairs() {
+                let (from, to) = (rename(from), rename(to));
+                let key = if undirected && to < from { (to.clone(), from.clone()) } else { (from.clone(), to.clone()) };
+                if (from == name && to == name) || !seen.insert(key) {
+                    continue;
+                }
+                let mut rewired = edge.with_endpoints(&from, &to);
+                rewired.lhead = rewired.lhead.filter(|c| c != name);
+                rewired.ltail = rewired.ltail.filter(|c| c != name);
+                added.push(rewired);
+            }
+        }
+        self.edges.extend(added.iter().cloned());
+        let compound = self.compound;
+        self.compound = self.all_edges().iter().any(|e| e.is_compound());
+
+        let title = match (subgraph.label.as_str(), name.trim_start_matches("cluster_")) {
+            ("", "") => name,
+            ("", short) => short,
+            (label, _) => label,
+        };
+        let mut summary = Node::new(name).label(&format!("{} ({})", title, subgraph.nodes.len())).shape(Some("box3d"));
+        summary.color = subgraph.color.clone();
+        self.nodes.push(summary);
+
+        let mut same_rank = vec![];
+        for (i, names) in self.same_rank.iter_mut().enumerate() {
+            if names.iter().any(|n| members.contains(n.as_str())) {
+                same_rank.push((i, names.clone()));
+                let mut renamed: Vec<String> = vec![];
+                for n in names.drain(..).map(rename) {
+                    if !renamed.contains(&n) {
+                        renamed.push(n);
+                    }
+                }
+                *names = renamed;
+            }
+        }
+
+        self.collapsed.push(Collapsed { subgraph, index, edges, added, same_rank, compound });
+        Ok(())
+    }
+
+    /// Brings back a subgraph collapsed by `Graph::collapse_subgraph`, with
+    /// its edges as they were then. Edges added to the summary node since
+    /// are dropped; subgraphs collapsed later are best expanded first.
+    ///
+    /// Fails with `CollapseError::NotCollapsed` when `name` is not
+    /// collapsed.
+    pub fn expand(&mut self, name: &str) -> Result<(), CollapseError> {
+        let i = self
+            .collapsed
+            .iter()
+            .position(|c| c.subgraph.name == name)
+            .ok_or_else(|| CollapseError::NotCollapsed(String::from(name)))?;
+        let collapsed = self.collapsed.remove(i);
+
+        for nodes in self.subgraph.iter_mut().map(|s| &mut s.nodes).chain(std::iter::once(&mut self.nodes)) {
+            nodes.retain(|n| n.name != name);
+        }
+        let edgeop = self.kind.edgeop();
+        for added in collapsed.added.iter() {
+            let dot = added.to_dot_string(edgeop);
+            if let Some(j) = self.edges.iter().position(|e| e.to_dot_string(edgeop) == dot) {
+                self.edges.remove(j);
+            }
+        }
+        for edges in self.subgraph.iter_mut().map(|s| &mut s.edges).chain(std::iter::once(&mut self.edges)) {
+            edges.retain(|e| e.node_pairs().iter().all(|(from, to)| from != name && to != name));
+        }
+
+        let index = collapsed.index.min(self.subgraph.len());
+        self.subgraph.insert(index, collapsed.subgraph);
+        for (cluster, index, edge) in collapsed.edges {
+            let edges = match cluster.and_then(|c| self.subgraph.iter().position(|s| s.name == c)) {
+                Some(s) => &mut self.subgraph[s].edges,
+                None => &mut self.edges,
+            };
+            edges.insert(index.min(edges.len()), edge);
+        }
+        for (index, names) in collapsed.same_rank {
+            if let Some(group) = self.same_rank.get_mut(index) {
+                *group = names;
+            }
+        }
+        self.compound = collapsed.compound || self.all_edges().iter().any(|e| e.is_compound());
+        Ok(())
+    }
+}
+
+/// Moves the edges `touches` accepts out of `edges`, with `cluster` and
+/// their index.
+fn take_edges(edges: &mut Vec<Edge>, cluster: Option<&String>, touches: impl Fn(&Edge) -> bool, taken: &mut Vec<(Option<String>, usize, Edge)>) {
+    let mut kept = vec![];
+    for (i, e) in edges.drain(..).enumerate() {
+        if touches(&e) {
+            taken.push((cluster.cloned(), i, e));
+        } else {
+            kept.push(e);
+        }
+    }
+    *edges = kept;
+}
@@ -0,0 +1,185 @@
+//! Export to the elements JSON of [Cytoscape.js](https://js.cytoscape.org/).
+
+use crate::{
+    arrow::{Arrow, ArrowShape, Fill},
+    edge::{Edge, Endpoint},
+    graph::{Graph, Kind},
+    json::Json,
+    node::Node,
+    style::Style,
+    utils::unescape_label,
+};
+
+impl Graph {
+    /// Renders the graph as a Cytoscape.js elements array, ready for
+    /// `cy.add()` or the `elements` option.
+    ///
+    /// Clusters become compound nodes, their members refer to them with
+    /// `data.parent`, and edges to a subgraph connect to its compound node.
+    /// Labels go, as plain text without the escapes of DOT like `\n`, to
+    /// `data.label` and, together with colors, node shapes, edge styles and
+    /// arrows, to the `style` of each element.
+    pub fn to_cytoscape_json(&self) -> String {
+        let mut elements = vec![];
+
+        let mut declared: Vec<&str> = vec![];
+        for s in self.subgraph.iter() {
+            let label = unescape_label(&s.label);
+            let mut css = vec![(String::from("label"), Json::str(&label))];
+            push_color(&mut css, s.style, &s.color);
+            if s.style == Style::Dashed || s.style == Style::Dotted {
+                css.push((String::from("border-style"), Json::str(s.style.as_slice())));
+            }
+            elements.push(element("nodes", vec![
+                (String::from("id"), Json::str(&s.name)),
+                (String::from("label"), Json::str(&label)),
+            ], css));
+            for n in s.nodes.iter() {
+                elements.push(node(n, Some(&s.name)));
+                declared.push(&n.name);
+            }
+        }
+        for n in self.nodes.iter() {
+            elements.push(node(n, None));
+            declared.push(&n.name);
+        }
+        for name in self.node_names() {
+            if !declared.contains(&name.as_str()) {
+                elements.push(node(&Node::implicit(&name), None));
+            }
+        }
+
+        let mut count = 0;
+        for e in self.all_edges() {
+            for segment in e.endpoints.windows(2) {
+                elements.push(edge(self.kind, e, &segment[0], &segment[1], count));
+                count += 1;
+            }
+        }
+
+        Json::Array(elements).to_pretty_string()
+    }
+}
+
+fn element(group: &str, data: Vec<(String, Json)>, css: Vec<(String, Json)>) -> Json {
+    let mut members = vec![
+        (String::from("group"), Json::str(group)),
+        (String::from("data"), Json::Object(data)),
+    ];
+    if !css.is_empty() {
+        members.push((String::from("style"), Json::Object(css)));
+    }
+    Json::Object(members)
+}
+
+/// Graphviz colors the outline, and the background of filled elements.
+fn push_color(css: &mut Vec<(String, Json)>, style: Style, color: &Option<String>) {
+    if let Some(c) = color {
+        css.push((String::from("border-color"), Json::str(c)));
+        if style == Style::Filled {
+            css.push((String::from("background-color"), Json::str(c)));
+        }
+    }
+}
+
+fn node(n: &Node, parent: Option<&str>) -> Json {
+    let label = unescape_label(&n.label);
+    let mut data = vec![
+        (String::from("id"), Json::str(&n.name)),
+        (String::from("label"), Json::str(&label)),
+    ];
+    if let Some(p) = parent {
+        data.push((String::from("parent"), Json::str(p)));
+    }
+
+    let mut css = vec![(String::from("label"), Json::str(&label))];
+    push_color(&mut css, n.style, &n.color);
+    match n.style {
+        Style::Dashed | Style::Dotted => css.push((String::from("border-style"), Json::str(n.style.as_slice()))),
+        Style::Invisible => css.push((String::from("visibility"), Json::str("hidden"))),
+        _ => {}
+    }
+    if let Some(shape) = n.shape.as_deref().and_then(node_shape) {
+        css.push((String::from("shape"), Json::str(shape)));
+    }
+    element("nodes", data, css)
+}
+
+fn edge(kind: Kind, e: &Edge, from: &Endpoint, to: &Endpoint, count: usize) -> Json {
+    let id = |endpoint: &Endpoint| match endpoint {
+        Endpoint::Node(name) => Json::str(name),
+        Endpoint::Subgraph { name, .. } => Json::str(name),
+    };
+    let label = unescape_label(&e.label);
+    let data = vec![
+        (String::from("id"), Json::String(format!("e{}", count))),
+        (String::from("source"), id(from)),
+        (String::from("target"), id(to)),
+        (String::from("label"), Json::str(&label)),
+    ];
+
+    let mut css = vec![(String::from("label"), Json::str(&label))];
+    if let Some(c) = &e.color {
+        css.push((String::from("line-color"), Json::str(c)));
+        css.push((String::from("source-arrow-color"), Json::str(c)));
+        css.push((String::from("target-arrow-color"), Json::str(c)));
+    }
+    match e.style {
+        Style::Dashed | Style::Dotted => css.push((String::from("line-style"), Json::str(e.style.as_slice()))),
+        Style::Bold => css.push((String::from("width"), Json::Number(3.0))),
+        Style::Invisible => css.push((String::from("visibility"), Json::str("hidden"))),
+        _ => {}
+    }
+    let head = if kind == Kind::Digraph { "triangle" } else { "none" };
+    push_arrow(&mut css, "source", &e.start_arrow, "none");
+    push_arrow(&mut css, "target", &e.end_arrow, head);
+    element("edges", data, css)
+}
+
+/// Adds the arrow shape and fill for the `end` (`source` or `target`) of
+/// an edge; multiple Graphviz arrows are reduced to the first one.
+/// Cytoscape.js has no `icurve` nor `crow`, both become `triangle-cross`.
+fn push_arrow(css: &mut Vec<(String, Json)>, end: &str, arrow: &Arrow, default: &str) {
+    let (shape, fill) = match arrow.arrows.first() {
+        None => (default, Fill::Filled),
+        Some(ArrowShape::NoArrow) => ("none", Fill::Filled),
+        Some(ArrowShape::Normal(f, _)) => ("triangle", *f),
+        Some(ArrowShape::Inv(f, _)) => ("triangle-backcurve", *f),
+        Some(ArrowShape::Box(f, _)) => ("square", *f),
+        Some(ArrowShape::Diamond(f, _)) => ("diamond", *f),
+        Some(ArrowShape::Dot(f)) => ("circle", *f),
+        Some(ArrowShape::ICurve(f, _)) => ("triangle-cross", *f),
+        Some(ArrowShape::Crow(_)) => ("triangle-cross", Fill::Filled),
+        Some(ArrowShape::Curve(_)) => ("chevron", Fill::Filled),
+        Some(ArrowShape::Tee(_)) => ("tee", Fill::Filled),
+        Some(ArrowShape::Vee(_)) => ("vee", Fill::Filled),
+    };
+    if shape == "none" && default == "none" {
+        return;
+    }
+    css.push((format!("{}-arrow-shape", end), Json::str(shape)));
+    if fill == Fill::Open {
+        css.push((format!("{}-arrow-fill", end), Json::str("hollow")));
+    }
+}
+
+/// The Cytoscape.js shape closest to a Graphviz one.
+fn node_shape(shape: &str) -> Option<&'static str> {
+    let shape = match shape {
+        "box" | "rect" | "rectangle" | "square" | "plaintext" | "plain" | "none" | "note" | "tab" | "folder" | "component" => "rectangle",
+        "ellipse" | "oval" | "circle" | "doublecircle" | "point" | "egg" => "ellipse",
+        "triangle" => "triangle",
+        "diamond" | "Mdiamond" => "diamond",
+        "pentagon" => "pentagon",
+        "hexagon" => "hexagon",
+        "septagon" => "heptagon",
+        "octagon" | "doubleoctagon" | "tripleoctagon" => "octagon",
+        "star" => "star",
+        "parallelogram" => "rhomboid",
+        "cylinder" => "barrel",
+        "Mrecord" => "round-rectangle",
+        "cds" | "rarrow" => "tag",
+        _ => return None,
+    };
+    Some(shape)
+}
@@ -0,0 +1,404 @@
+//! Structural differences between two versions of a graph.
+//!
+//! ```rust
+//! use dot_graph::{Graph, Kind, Node, Edge, diff::{Change, Element}};
+//!
+//! let mut old = Graph::new("G", Kind::Digraph);
+//! old.add_node(Node::new("a").color(Some("red")));
+//! old.add_edge(Edge::new("a", "b", ""));
+//!
+//! let mut new = Graph::new("G", Kind::Digraph);
+//! new.add_node(Node::new("a").color(Some("blue")));
+//! new.add_edge(Edge::new("a", "c", ""));
+//!
+//! let diff = old.diff(&new);
+//! assert_eq!(diff.to_string(),
+//! r#"- node b
+//! + node c
+//! ~ node a color: red -> blue
+//! - edge a -> b
+//! + edge a -> c
+//! "#);
+//! assert_eq!(diff.changes[1], Change::Added(Element::Node(String::from("c"))));
+//! // the merged graph draws removals in red, additions in green and
+//! // changes in orange
+//! let dot = diff.to_dot_string().unwrap();
+//! ```
+
+use std::{collections::HashMap, fmt, io};
+
+use crate::{
+    edge::Edge,
+    graph::{Graph, Kind},
+    node::Node,
+    style::Style,
+    subgraph::Subgraph,
+    utils::number,
+};
+
+const ADDED: &str = "green";
+const REMOVED: &str = "red";
+const CHANGED: &str = "orange";
+
+/// What changed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Element {
+    Graph,
+    Subgraph(String),
+    Node(String),
+    /// An edge between two nodes; edge chains and edges to subgraphs are
+    /// compared by node pair.
+    Edge(String, String),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Change {
+    Added(Element),
+    Removed(Element),
+    /// An attribute, by its DOT name, with `None` when it is not set. The
+    /// cluster a node is declared in is the attribute `subgraph`.
+    Changed {
+        element: Element,
+        attribute: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+}
+
+/// The result of `Graph::diff`.
+#[derive(Clone)]
+pub struct GraphDiff {
+    /// Graph attributes first, then subgraphs, nodes and edges, each with
+    /// the removals, additions and changes in the order of the graphs.
+    pub changes: Vec<Change>,
+    kind: Kind,
+    merged: Graph,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The new graph with what was removed put back: removed elements are
+    /// colored red, added ones green and changed ones orange. Edge chains
+    /// with a changed segment are split into single edges.
+    pub fn merged(&self) -> &Graph {
+        &self.merged
+    }
+
+    /// The DOT of `merged`.
+    pub fn to_dot_string(&self) -> io::Result<String> {
+        self.merged.to_dot_string()
+    }
+}
+
+impl fmt::Display for GraphDiff {
+    /// One line per change: `+` for additions, `-` for removals and `~`
+    /// for changed attributes.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let element = |e: &Element| match e {
+            Element::Graph => String::from("graph"),
+            Element::Subgraph(name) => format!("subgraph {}", name),
+            Element::Node(name) => format!("node {}", name),
+            Element::Edge(from, to) => format!("edge {} {} {}", from, self.kind.edgeop(), to),
+        };
+        for change in self.changes.iter() {
+            match change {
+                Change::Added(e) => writeln!(f, "+ {}", element(e))?,
+                Change::Removed(e) => writeln!(f, "- {}", element(e))?,
+                Change::Changed { element: e, attribute, old, new } => {
+                    let value = |v: &Option<String>| v.clone().unwrap_or_else(|| String::from("(none)"));
+                    writeln!(f, "~ {} {}: {} -> {}", element(e), attribute, value(old), value(new))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Status {
+    Same,
+    Added,
+    Changed,
+}
+
+impl Status {
+    fn color(self) -> Option<&'static str> {
+        match self {
+            Status::Same => None,
+            Status::Added => Some(ADDED),
+            Status::Changed => Some(CHANGED),
+        }
+    }
+}
+
+impl Graph {
+    /// Compares this graph with a newer version of it.
+    ///
+    /// Nodes and subgraphs are matched by name, edges by their node pair
+    /// (in either order for `Kind::Graph`), parallel edges in order.
+    /// Layout positions (`pos`, `bb`) are not compared. A node only
+    /// referenced by edges compares equal to a declared node without
+    /// attributes.
+    pub fn diff(&self, other: &Graph) -> GraphDiff {
+        let mut changes = vec![];
+        let kind = |g: &Graph| Some(String::from(g.kind.keyword()));
+        compare(Element::Graph, graph_attributes(self, kind(self)), graph_attributes(other, kind(other)), &mut changes);
+
+        // every kind of element lists its removals, additions and changes
+        let (mut removed, mut added, mut modified) = (vec![], vec![], vec![]);
+        let mut flush = |removed: &mut Vec<Change>, added: &mut Vec<Change>, modified: &mut Vec<Change>| {
+            changes.append(removed);
+            changes.append(added);
+            changes.append(modified);
+        };
+
+        let old_subgraphs: HashMap<&str, &Subgraph> = self.subgraph.iter().map(|s| (s.name.as_str(), s)).collect();
+        let removed_subgraphs: Vec<&Subgraph> = self.subgraph.iter().filter(|s| !other.subgraph.iter().any(|n| n.name == s.name)).collect();
+        removed.extend(removed_subgraphs.iter().map(|s| Change::Removed(Element::Subgraph(s.name.clone()))));
+        let mut subgraph_status = HashMap::new();
+        for s in other.subgraph.iter() {
+            let status = match old_subgraphs.get(s.name.as_str()) {
+                Some(old) => compare(Element::Subgraph(s.name.clone()), subgraph_attributes(old), subgraph_attributes(s), &mut modified),
+                None => {
+                    added.push(Change::Added(Element::Subgraph(s.name.clone())));
+                    Status::Added
+                }
+            };
+            subgraph_status.insert(s.name.clone(), status);
+        }
+        flush(&mut removed, &mut added, &mut modified);
+
+        let (old_nodes, new_nodes) = (Nodes::new(self), Nodes::new(other));
+        let removed_nodes: Vec<&String> = old_nodes.names.iter().filter(|n| !new_nodes.clusters.contains_key(*n)).collect();
+        removed.extend(removed_nodes.iter().map(|n| Change::Removed(Element::Node((*n).clone()))));
+        let mut node_status = HashMap::new();
+        for name in new_nodes.names.iter() {
+            let status = if old_nodes.clusters.contains_key(name) {
+                let (mut old, mut new) = (old_nodes.attributes(name), new_nodes.attributes(name));
+                old.push(("subgraph", old_nodes.clusters[name].clone()));
+                new.push(("subgraph", new_nodes.clusters[name].clone()));
+                compare(Element::Node(name.clone()), old, new, &mut modified)
+            } else {
+                added.push(Change::Added(Element::Node(name.clone())));
+                Status::Added
+            };
+            node_status.insert(name.clone(), status);
+        }
+        flush(&mut removed, &mut added, &mut modified);
+
+        let key = |from: &str, to: &str| -> (String, String) {
+            if other.kind == Kind::Graph && to < from {
+                (String::from(to), String::from(from))
+            } else {
+                (String::from(from), String::from(to))
+            }
+        };
+        let mut old_pairs: HashMap<(String, String), Vec<Edge>> = HashMap::new();
+        for e in self.all_edges() {
+            for (from, to) in e.node_pairs() {
+                old_pairs.entry(key(&from, &to)).or_default().push(e.with_endpoints(&from, &to));
+            }
+        }
+        // parallel edges are matched in order
+        let mut seen: HashMap<(String, String), usize> = HashMap::new();
+        // the status of every pair of every edge of `other`
+        let mut edge_status: Vec<Vec<Status>> = vec![];
+        for e in other.all_edges() {
+            let mut statuses = vec![];
+            for (from, to) in e.node_pairs() {
+                let k = key(&from, &to);
+                let count = seen.entry(k.clone()).or_default();
+                let element = Element::Edge(from.clone(), to.clone());
+                statuses.push(match old_pairs.get(&k).and_then(|edges| edges.get(*count)) {
+                    Some(old) => compare(element, edge_attributes(old), edge_attributes(&e.with_endpoints(&from, &to)), &mut modified),
+                    None => {
+                        added.push(Change::Added(element));
+                        Status::Added
+                    }
+                });
+                *count += 1;
+            }
+            edge_status.push(statuses);
+        }
+        let mut removed_edges = vec![];
+        let mut used: HashMap<(String, String), usize> = HashMap::new();
+        for e in self.all_edges() {
+            for (from, to) in e.node_pairs() {
+                let k = key(&from, &to);
+                let count = used.entry(k.clone()).or_default();
+                if *count >= seen.get(&k).copied().unwrap_or(0) {
+                    removed.push(Change::Removed(Element::Edge(from.clone(), to.clone())));
+                    removed_edges.push(e.with_endpoints(&from, &to));
+                }
+                *count += 1;
+            }
+        }
+        flush(&mut removed, &mut added, &mut modified);
+
+        // the merged graph
+        let mut merged = Graph::new(&other.name, other.kind);
+        merged.url = other.url.clone();
+        merged.compound = other.compound;
+        merged.same_rank = other.same_rank.clone();
+        merged.bb = other.bb.clone();
+        let mut edges = edge_status.into_iter();
+        let mut colored_edges = |list: &[Edge]| -> Vec<Edge> {
+            let mut result = vec![];
+            for e in list {
+                let statuses = edges.next().unwrap_or_default();
+                if statuses.iter().all(|s| *s == Status::Same) {
+                    result.push(e.clone());
+                    continue;
+                }
+                for ((from, to), status) in e.node_pairs().into_iter().zip(statuses) {
+                    result.push(paint_edge(e.with_endpoints(&from, &to), status.color()));
+                }
+            }
+            result
+        };
+        let paint_node = |n: &Node| match node_status.get(&n.name).and_then(|s| s.color()) {
+            Some(color) => n.color(Some(color)),
+            None => n.clone(),
+        };
+
+        let mut subgraphs = vec![];
+        for s in other.subgraph.iter() {
+            let mut merged_subgraph = s.clone();
+            if let Some(color) = subgraph_status.get(&s.name).and_then(|s| s.color()) {
+                merged_subgraph.color = Some(String::from(color));
+            }
+            merged_subgraph.nodes = s.nodes.iter().map(paint_node).collect();
+            merged_subgraph.edges = colored_edges(&s.edges);
+            subgraphs.push(merged_subgraph);
+        }
+        for s in removed_subgraphs {
+            let mut merged_subgraph = Subgraph::new(&s.name);
+            merged_subgraph.label = s.label.clone();
+            merged_subgraph.style = s.style;
+            merged_subgraph.color = Some(String::from(REMOVED));
+            subgraphs.push(merged_subgraph);
+        }
+        let mut nodes: Vec<Node> = other.nodes.iter().map(paint_node).collect();
+        // changed or added nodes need a declaration to be colored
+        for name in new_nodes.names.iter() {
+            if !new_nodes.declared.contains_key(name) && node_status[name] != Status::Same {
+                nodes.push(paint_node(&Node::implicit(name)));
+            }
+        }
+        for name in removed_nodes {
+            let node = old_nodes.declared.get(name).map_or_else(|| Node::implicit(name), |n| (*n).clone()).color(Some(REMOVED));
+            match old_nodes.clusters[name].as_ref().and_then(|c| subgraphs.iter_mut().find(|s| &s.name == c)) {
+                Some(s) => s.nodes.push(node),
+                None => nodes.push(node),
+            }
+        }
+        let mut top_edges = colored_edges(&other.edges);
+        top_edges.extend(removed_edges.into_iter().map(|e| paint_edge(e, Some(REMOVED))));
+
+        for s in subgraphs {
+            merged.add_subgraph(s);
+        }
+        for n in nodes {
+            merged.add_node(n);
+        }
+        for e in top_edges {
+            merged.add_edge(e);
+        }
+        GraphDiff { changes, kind: other.kind, merged }
+    }
+}
+
+/// Adds a `Change` for every attribute that differs, telling whether any
+/// did.
+fn compare(element: Element, old: Vec<(&'static str, Option<String>)>, new: Vec<(&'static str, Option<String>)>, changes: &mut Vec<Change>) -> Status {
+    let mut status = Status::Same;
+    for ((attribute, old), (_, new)) in old.into_iter().zip(new) {
+        if old != new {
+            changes.push(Change::Changed { element: element.clone(), attribute: String::from(attribute), old, new });
+            status = Status::Changed;
+        }
+    }
+    status
+}
+
+fn paint_edge(mut edge: Edge, color: Option<&str>) -> Edge {
+    match color {
+        Some(c) => edge.color(Some(c)),
+        None => edge,
+    }
+}
+
+/// The nodes of a graph with their cluster.
+struct Nodes<'a> {
+    names: Vec<String>,
+    declared: HashMap<String, &'a Node>,
+    clusters: HashMap<String, Option<String>>,
+}
+
+impl<'a> Nodes<'a> {
+    fn new(graph: &'a Graph) -> Nodes<'a> {
+        let mut clusters: HashMap<String, Option<String>> = graph.node_names().into_iter().map(|n| (n, None)).collect();
+        let mut declared = HashMap::new();
+        for s in graph.subgraph.iter() {
+            for n in s.nodes.iter() {
+                clusters.insert(n.name.clone(), Some(s.name.clone()));
+                declared.entry(n.name.clone()).or_insert(n);
+            }
+        }
+        for n in graph.nodes.iter() {
+            declared.entry(n.name.clone()).or_insert(n);
+        }
+        Nodes { names: graph.node_names(), declared, clusters }
+    }
+
+    fn attributes(&self, name: &str) -> Vec<(&'static str, Option<String>)> {
+        match self.declared.get(name) {
+            Some(node) => node_attributes(node),
+            None => node_attributes(&Node::implicit(name)),
+        }
+    }
+}
+
+fn set(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| String::from(value))
+}
+
+fn style(style: Style) -> Option<String> {
+    set(style.as_slice())
+}
+
+fn graph_attributes(graph: &Graph, kind: Option<String>) -> Vec<(&'static str, Option<String>)> {
+    vec![("kind", kind), ("URL", set(&graph.url)), ("compound", graph.compound.then(|| String::from("true")))]
+}
+
+fn subgraph_attributes(s: &Subgraph) -> Vec<(&'static str, Option<String>)> {
+    vec![("label", set(&s.label)), ("style", style(s.style)), ("color", s.color.clone()), ("URL", set(&s.url))]
+}
+
+fn node_attributes(n: &Node) -> Vec<(&'static str, Option<String>)> {
+    vec![
+        ("label", Some(n.label.clone())),
+        ("style", style(n.style)),
+        ("color", n.color.clone()),
+        ("shape", n.shape.clone()),
+        ("URL", set(&n.url)),
+    ]
+}
+
+fn edge_attributes(e: &Edge) -> Vec<(&'static str, Option<String>)> {
+    vec![
+        ("label", set(&e.label)),
+        ("labelURL", set(&e.label_url)),
+        ("URL", set(&e.url)),
+        ("style", style(e.style)),
+        ("color", e.color.clone()),
+        ("penwidth", e.penwidth.map(number)),
+        ("arrowhead", set(&e.end_arrow.to_dot_string())),
+        ("arrowtail", set(&e.start_arrow.to_dot_string())),
+        ("lhead", e.lhead.clone()),
+        ("ltail", e.ltail.clone()),
+    ]
+}
@@ -0,0 +1,881 @@
+//! Reading DOT documents back into a `Graph`.
+//!
+//! The whole DOT language is read, but the graph model only keeps what it
+//! can render: everything else is dropped with a `Warning`, like the
+//! attributes without a field in `Node` or `Edge`.
+//!
+//! ```rust
+//! use dot_graph::{Graph, dot};
+//!
+//! let read = dot::read(r#"digraph G {
+//!     node [shape=box];
+//!     a [label="A", fontname="Helvetica"];
+//!     a -> b;
+//! }"#).unwrap();
+//! assert_eq!(read.graph.to_dot_string().unwrap(),
+//! r#"digraph G {
+//!     "a"[label="A"][shape="box"];
+//!     "b"[label="b"][shape="box"];
+//!     "a" -> "b"[label=""];
+//! }
+//! "#);
+//! assert_eq!(read.warnings[0].to_string(), "line 3: attribute fontname of node a is not supported");
+//!
+//! let graph = Graph::from_dot_string("graph { a -- b }").unwrap();
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, io,
+};
+
+use crate::{
+    arrow::Arrow,
+    edge::{Edge, Endpoint},
+    graph::{Graph, Kind},
+    node::{self, Node},
+    style::Style,
+    subgraph::{self, Subgraph},
+    utils::{escape_quotes, quote_string},
+};
+
+/// Something in the document that was not read into the graph.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Warning {
+    /// The line it was found on, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// The result of `read`.
+#[derive(Clone)]
+pub struct Read {
+    pub graph: Graph,
+    pub warnings: Vec<Warning>,
+}
+
+/// Reads a DOT document.
+///
+/// Syntax errors and node names `Node::new` would not accept fail with
+/// `ErrorKind::InvalidData`, the message starting with the line number.
+///
+/// The attributes kept are `label`, `style`, `color`, `shape`, `URL` and
+/// `pos` of nodes; `label`, `labelURL`, `URL`, `style`, `color`,
+/// `arrowhead`, `arrowtail`, `lhead`, `ltail` and `pos` of edges; `label`,
+/// `style`, `color`, `URL` and `bb` of clusters and `URL`, `compound` and
+/// `bb` of the graph. Defaults from `node [...]` and `edge [...]` are
+/// applied to the nodes and edges that follow them. Nodes only referenced
+/// by edges are declared where needed: inside clusters, or to keep
+/// default attributes.
+///
+/// The model has no strict graphs: `strict` is dropped with a warning and
+/// the edges it would merge are all kept.
+///
+/// Subgraphs named `cluster_...` become `Subgraph`s, nested ones next to
+/// their parent as the model has no nesting. `{rank=same; ...}` becomes
+/// `Graph::same_rank`, and the statements of other subgraphs are read into
+/// the enclosing graph or cluster. A node belongs to the innermost cluster
+/// it first appears in.
+pub fn read(dot: &str) -> io::Result<Read> {
+    let document = parse(dot)?;
+    let mut reader = Reader {
+        kind: document.kind,
+        graph: Graph::new(&document.name.map(graph_name).unwrap_or_default(), document.kind),
+        nodes: vec![],
+        index: HashMap::new(),
+        mentioned: HashSet::new(),
+        edges: vec![],
+        clusters: vec![],
+        warnings: vec![],
+    };
+    if let Some(line) = document.strict {
+        reader.warn(line, String::from("strict is not supported, duplicate edges are kept"));
+    }
+    let scope = Scope { cluster: None, top: true, rank: false, node: vec![], edge: vec![] };
+    reader.block(&document.statements, &scope)?;
+    Ok(reader.finish())
+}
+
+impl Graph {
+    /// Reads a DOT document, dropping what the model cannot hold; see
+    /// `dot::read` for the details and the warnings.
+    pub fn from_dot_string(dot: &str) -> io::Result<Graph> {
+        read(dot).map(|read| read.graph)
+    }
+}
+
+/// A graph name which does not need quotes is kept as it is, others are
+/// quoted with the quotes they contain escaped, like the ones of HTML
+/// names.
+fn graph_name(name: String) -> String {
+    let plain = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        return name;
+    }
+    quote_string(escape_quotes(&name))
+}
+
+/// The defaults in effect in a block.
+#[derive(Clone)]
+struct Scope {
+    cluster: Option<usize>,
+    /// Whether the block is the body of the graph itself.
+    top: bool,
+    /// Whether the block is a `{rank=same; ...}`, whose nodes are not
+    /// declarations.
+    rank: bool,
+    node: Vec<Attribute>,
+    edge: Vec<Attribute>,
+}
+
+struct Reader {
+    kind: Kind,
+    graph: Graph,
+    /// Declared nodes, with their cluster.
+    nodes: Vec<(Node, Option<usize>)>,
+    index: HashMap<String, usize>,
+    /// All nodes seen so far, declared or not.
+    mentioned: HashSet<String>,
+    edges: Vec<(Edge, Option<usize>, usize)>,
+    clusters: Vec<Subgraph>,
+    warnings: Vec<Warning>,
+}
+
+impl Reader {
+    fn warn(&mut self, line: usize, message: String) {
+        self.warnings.push(Warning { line, message });
+    }
+
+    /// Reads the statements of a block, returning the nodes they mention.
+    fn block(&mut self, statements: &[Statement], scope: &Scope) -> io::Result<Vec<String>> {
+        let mut scope = scope.clone();
+        let mut names = vec![];
+        for statement in statements {
+            match statement {
+                Statement::Defaults(Target::Graph, attributes) => {
+                    for a in attributes {
+                        self.graph_attribute(a, &scope);
+                    }
+                }
+                Statement::Assign(a) => self.graph_attribute(a, &scope),
+                Statement::Defaults(Target::Node, attributes) => scope.node.extend(attributes.iter().cloned()),
+                Statement::Defaults(Target::Edge, attributes) => scope.edge.extend(attributes.iter().cloned()),
+                // naming a node again does not declare it
+                Statement::Node(id, attributes) if attributes.is_empty() && (scope.rank || self.mentioned.contains(&id.name)) => {
+                    self.mention(id, &scope)?;
+                    names.push(id.name.clone());
+                }
+                Statement::Node(id, attributes) => {
+                    let i = self.declare(id, &scope)?;
+                    for a in attributes {
+                        self.node_attribute(i, a);
+                    }
+                    names.push(id.name.clone());
+                }
+                Statement::Edge(operands, attributes) => {
+                    let mut endpoints = vec![];
+                    for operand in operands {
+                        endpoints.push(match operand {
+                            Operand::Node(id) => {
+                                self.mention(id, &scope)?;
+                                names.push(id.name.clone());
+                                Endpoint::Node(id.name.clone())
+                            }
+                            Operand::Subgraph(block) => {
+                                let nodes = self.subgraph(block, &scope)?;
+                                names.extend(nodes.iter().cloned());
+                                Endpoint::Subgraph { name: block.name.clone().unwrap_or_default(), nodes }
+                            }
+                        });
+                    }
+                    let mut edge = Edge::new(endpoints[0].clone(), endpoints[1].clone(), "");
+                    edge.endpoints = endpoints;
+                    let name = format!("edge {}", edge_name(&edge, self.kind));
+                    let mut dir = None;
+                    for a in scope.edge.iter().chain(attributes.iter()) {
+                        if a.key == "dir" {
+                            dir = Some(a);
+                        } else if let Some(ignored) = edge_attribute(&mut edge, a) {
+                            self.warn(a.line, ignored.message(&name));
+                        }
+                    }
+                    // dir is only written by the renderer with both arrows set
+                    let both = !edge.start_arrow.is_default() && !edge.end_arrow.is_default();
+                    let default = if self.kind == Kind::Digraph { "forward" } else { "none" };
+                    if let Some(a) = dir.filter(|a| a.value != default && !(a.value == "both" && both)) {
+                        self.warn(a.line, Ignored::Unsupported(a.key.clone()).message(&name));
+                    }
+                    let line = operands.first().map_or(0, Operand::line);
+                    self.edges.push((edge, scope.cluster, line));
+                }
+                Statement::Subgraph(block) => names.extend(self.subgraph(block, &scope)?),
+            }
+        }
+        Ok(names)
+    }
+
+    /// Reads a subgraph, returning the nodes it mentions.
+    fn subgraph(&mut self, block: &Block, scope: &Scope) -> io::Result<Vec<String>> {
+        let rank = block.statements.iter().find_map(|s| match s {
+            Statement::Assign(a) if a.key == "rank" => Some(a),
+            _ => None,
+        });
+        let mut inner = Scope { top: false, rank: rank.is_some(), ..scope.clone() };
+        match &block.name {
+            Some(name) if subgraph::is_valid_name(name) => {
+                let i = match self.clusters.iter().position(|c| &c.name == name) {
+                    Some(i) => i,
+                    None => {
+                        if scope.cluster.is_some() {
+                            self.warn(block.line, format!("cluster {} is nested, it is read next to its parent", name));
+                        }
+                        self.clusters.push(Subgraph::new(name));
+                        self.clusters.len() - 1
+                    }
+                };
+                inner.cluster = Some(i);
+            }
+            Some(name) if rank.is_none() => {
+                self.warn(block.line, format!("subgraph {} is not a cluster, its statements are read into the enclosing graph", name));
+            }
+            _ => {}
+        }
+        let names = self.block(&block.statements, &inner)?;
+        if let Some(rank) = rank {
+            if rank.value == "same" {
+                self.graph.same_rank.push(names.clone());
+            } else {
+                self.warn(rank.line, format!("rank={} is not supported", rank.value));
+            }
+        }
+        Ok(names)
+    }
+
+    /// Declares a node, or moves it into the cluster of `scope` if it was
+    /// declared outside of any cluster.
+    fn declare(&mut self, id: &NodeId, scope: &Scope) -> io::Result<usize> {
+        self.check(id)?;
+        if let Some(&i) = self.index.get(&id.name) {
+            if self.nodes[i].1.is_none() {
+                self.nodes[i].1 = scope.cluster;
+            }
+            return Ok(i);
+        }
+        let i = self.nodes.len();
+        self.nodes.push((Node::new(&id.name), scope.cluster));
+        self.index.insert(id.name.clone(), i);
+        // defaults only apply to new nodes
+        if self.mentioned.insert(id.name.clone()) {
+            for a in scope.node.clone() {
+                self.node_attribute(i, &a);
+            }
+        }
+        Ok(i)
+    }
+
+    /// A node referenced by an edge, declared only when it matters.
+    fn mention(&mut self, id: &NodeId, scope: &Scope) -> io::Result<()> {
+        let new = !self.mentioned.contains(&id.name);
+        if self.index.contains_key(&id.name) || scope.cluster.is_some() || new && !scope.node.is_empty() {
+            self.declare(id, scope)?;
+        } else {
+            self.check(id)?;
+            self.mentioned.insert(id.name.clone());
+        }
+        Ok(())
+    }
+
+    fn check(&mut self, id: &NodeId) -> io::Result<()> {
+        if !node::is_valid_name(&id.name) {
+            let message = format!("node name {:?} is not supported, names are made of letters, digits, '_' and '.' and do not start with a digit", id.name);
+            return Err(invalid(id.line, &message));
+        }
+        if id.port {
+            self.warn(id.line, format!("the port of node {} is not supported", id.name));
+        }
+        Ok(())
+    }
+
+    fn node_attribute(&mut self, i: usize, a: &Attribute) {
+        let node = &mut self.nodes[i].0;
+        let value = a.value.clone();
+        let ignored = match a.key.as_str() {
+            "label" => {
+                node.label = value;
+                a.html.then_some(Ignored::Html)
+            }
+            "style" => set_style(&mut node.style, &value),
+            "color" => {
+                node.color = Some(value);
+                None
+            }
+            "shape" => {
+                node.shape = Some(value);
+                None
+            }
+            "URL" | "href" => {
+                node.url = value;
+                None
+            }
+            "pos" => {
+                node.pos = Some(value);
+                None
+            }
+            key => Some(Ignored::Unsupported(String::from(key))),
+        };
+        if let Some(ignored) = ignored {
+            let message = ignored.message(&format!("node {}", node.name));
+            self.warn(a.line, message);
+        }
+    }
+
+    fn graph_attribute(&mut self, a: &Attribute, scope: &Scope) {
+        let value = a.value.clone();
+        let (element, ignored) = if let Some(i) = scope.cluster {
+            let cluster = &mut self.clusters[i];
+            let ignored = match a.key.as_str() {
+                "label" => {
+                    cluster.label = value;
+                    a.html.then_some(Ignored::Html)
+                }
+                "style" => set_style(&mut cluster.style, &value),
+                "color" => {
+                    cluster.color = Some(value);
+                    None
+                }
+                "URL" | "href" => {
+                    cluster.url = value;
+                    None
+                }
+                "bb" => {
+                    cluster.bb = Some(value);
+                    None
+                }
+                // read by `subgraph`
+                "rank" => None,
+                key => Some(Ignored::Unsupported(String::from(key))),
+            };
+            (cluster.name.clone(), ignored)
+        } else if scope.top {
+            let ignored = match a.key.as_str() {
+                "URL" | "href" => {
+                    self.graph.url = value;
+                    None
+                }
+                "compound" => {
+                    self.graph.compound = value == "true";
+                    None
+                }
+                "bb" => {
+                    self.graph.bb = Some(value);
+                    None
+                }
+                key => Some(Ignored::Unsupported(String::from(key))),
+            };
+            (String::from("the graph"), ignored)
+        } else {
+            let ignored = (a.key != "rank").then(|| Ignored::Unsupported(a.key.clone()));
+            (String::from("a subgraph"), ignored)
+        };
+        if let Some(ignored) = ignored {
+            self.warn(a.line, ignored.message(&element));
+        }
+    }
+
+    fn finish(mut self) -> Read {
+        let known: HashSet<String> = self.clusters.iter().map(|c| c.name.clone()).collect();
+        for (edge, _, line) in self.edges.iter() {
+            for cluster in [&edge.lhead, &edge.ltail].into_iter().flatten() {
+                if !known.contains(cluster) {
+                    let message = format!("edge {} is clipped at {}, which is not a cluster of the graph", edge_name(edge, self.kind), cluster);
+                    self.warnings.push(Warning { line: *line, message });
+                }
+            }
+        }
+
+        let mut graph = self.graph;
+        let mut clusters = self.clusters;
+        let mut nodes = vec![];
+        for (node, cluster) in self.nodes {
+            match cluster {
+                Some(i) => clusters[i].add_node(node),
+                None => nodes.push(node),
+            }
+        }
+        let mut edges = vec![];
+        for (edge, cluster, _) in self.edges {
+            match cluster {
+                Some(i) => clusters[i].add_edge(edge),
+                None => edges.push(edge),
+            }
+        }
+        for cluster in clusters {
+            graph.add_subgraph(cluster);
+        }
+        for node in nodes {
+            graph.add_node(node);
+        }
+        for edge in edges {
+            graph.add_edge(edge);
+        }
+        Read { graph, warnings: self.warnings }
+    }
+}
+
+/// Why an attribute was not read.
+enum Ignored {
+    Unsupported(String),
+    Style(String),
+    Arrow(String, String),
+    Html,
+}
+
+impl Ignored {
+    fn message(&self, element: &str) -> String {
+        match self {
+            Ignored::Unsupported(key) => format!("attribute {} of {} is not supported", key, element),
+            Ignored::Style(style) => format!("style {:?} of {} is not supported", style, element),
+            Ignored::Arrow(key, arrow) => format!("{} {:?} of {} is not a valid arrow", key, arrow, element),
+            Ignored::Html => format!("the HTML label of {} is read as text", element),
+        }
+    }
+}
+
+/// Sets an edge attribute other than `dir`.
+fn edge_attribute(edge: &mut Edge, a: &Attribute) -> Option<Ignored> {
+    let value = a.value.clone();
+    match a.key.as_str() {
+        "label" => {
+            edge.label = value;
+            return a.html.then_some(Ignored::Html);
+        }
+        "labelURL" | "labelhref" => edge.label_url = value,
+        "URL" | "href" => edge.url = value,
+        "style" => return set_style(&mut edge.style, &value),
+        "color" => edge.color = Some(value),
+        "penwidth" => match value.parse() {
+            Ok(width) => edge.penwidth = Some(width),
+            Err(_) => return Some(Ignored::Unsupported(String::from("penwidth"))),
+        },
+        "lhead" => edge.lhead = Some(value),
+        "ltail" => edge.ltail = Some(value),
+        "pos" => edge.pos = Some(value),
+        "arrowhead" | "arrowtail" => match Arrow::from_dot_string(&value) {
+            Some(arrow) if a.key == "arrowhead" => edge.end_arrow = arrow,
+            Some(arrow) => edge.start_arrow = arrow,
+            None => return Some(Ignored::Arrow(a.key.clone(), value)),
+        },
+        key => return Some(Ignored::Unsupported(String::from(key))),
+    }
+    None
+}
+
+fn set_style(style: &mut Style, value: &str) -> Option<Ignored> {
+    match Style::from_slice(value) {
+        Some(s) => {
+            *style = s;
+            None
+        }
+        None => Some(Ignored::Style(String::from(value))),
+    }
+}
+
+fn edge_name(edge: &Edge, kind: Kind) -> String {
+    let ends: Vec<String> = edge
+        .endpoints
+        .iter()
+        .map(|e| match e {
+            Endpoint::Node(name) => name.clone(),
+            Endpoint::Subgraph { nodes, .. } => format!("{{{}}}", nodes.join(" ")),
+        })
+        .collect();
+    ends.join(&format!(" {} ", kind.edgeop()))
+}
+
+fn invalid(line: usize, msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line, msg))
+}
+
+/// The syntax tree of a DOT document.
+pub(crate) struct Document {
+    pub(crate) kind: Kind,
+    /// The line of `strict`, for a strict graph.
+    pub(crate) strict: Option<usize>,
+    pub(crate) name: Option<String>,
+    pub(crate) statements: Vec<Statement>,
+}
+
+/// An attribute as written, `key=value`.
+#[derive(Clone)]
+pub(crate) struct Attribute {
+    pub(crate) key: String,
+    pub(crate) value: String,
+    /// Whether the value was an HTML string `<...>`.
+    pub(crate) html: bool,
+    pub(crate) line: usize,
+}
+
+pub(crate) enum Target {
+    Graph,
+    Node,
+    Edge,
+}
+
+pub(crate) struct NodeId {
+    pub(crate) name: String,
+    /// Whether a `:port` followed the name.
+    pub(crate) port: bool,
+    pub(crate) line: usize,
+}
+
+pub(crate) struct Block {
+    pub(crate) name: Option<String>,
+    pub(crate) statements: Vec<Statement>,
+    pub(crate) line: usize,
+}
+
+pub(crate) enum Operand {
+    Node(NodeId),
+    Subgraph(Block),
+}
+
+impl Operand {
+    fn line(&self) -> usize {
+        match self {
+            Operand::Node(id) => id.line,
+            Operand::Subgraph(block) => block.line,
+        }
+    }
+}
+
+pub(crate) enum Statement {
+    /// `graph [...]`, `node [...]` or `edge [...]`.
+    Defaults(Target, Vec<Attribute>),
+    /// `key=value` in a graph body.
+    Assign(Attribute),
+    Node(NodeId, Vec<Attribute>),
+    /// An edge or edge chain.
+    Edge(Vec<Operand>, Vec<Attribute>),
+    Subgraph(Block),
+}
+
+/// Parses a DOT document. Quoted strings keep their escapes, except for
+/// the line continuations, so they can be written back as they were.
+pub(crate) fn parse(text: &str) -> io::Result<Document> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let document = parser.document()?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(invalid(token.line, "unexpected text after the graph"));
+    }
+    Ok(document)
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Tok {
+    Id { text: String, quoted: bool, html: bool },
+    Punct(char),
+    EdgeOp,
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    tok: Tok,
+    line: usize,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn line(&self) -> usize {
+        self.tokens.get(self.pos).or(self.tokens.last()).map_or(1, |t| t.line)
+    }
+
+    fn error(&self, msg: &str) -> io::Error {
+        match self.tokens.get(self.pos) {
+            Some(_) => invalid(self.line(), msg),
+            None => invalid(self.line(), &format!("{} at the end of the document", msg)),
+        }
+    }
+
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos).map(|t| &t.tok)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Tok::Punct(c)) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, c: char) -> io::Result<()> {
+        if self.eat(c) { Ok(()) } else { Err(self.error(&format!("expected '{}'", c))) }
+    }
+
+    /// Whether the next token is the unquoted keyword.
+    fn keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Tok::Id { text, quoted: false, html: false }) if text.eq_ignore_ascii_case(keyword))
+    }
+
+    fn is_id(&self) -> bool {
+        matches!(self.peek(), Some(Tok::Id { .. }))
+    }
+
+    /// An id, with quoted strings joined by `+`. Returns the text and
+    /// whether it was HTML.
+    fn id(&mut self) -> io::Result<(String, bool)> {
+        let Some(Tok::Id { text, quoted, html }) = self.peek().cloned() else {
+            return Err(self.error("expected an id"));
+        };
+        self.pos += 1;
+        let mut text = text;
+        if quoted {
+            while self.peek() == Some(&Tok::Punct('+')) {
+                self.pos += 1;
+                match self.peek().cloned() {
+                    Some(Tok::Id { text: more, quoted: true, .. }) => text.push_str(&more),
+                    _ => return Err(self.error("expected a quoted string after '+'")),
+                }
+                self.pos += 1;
+            }
+        }
+        Ok((text, html))
+    }
+
+    fn document(&mut self) -> io::Result<Document> {
+        let strict = self.keyword("strict").then(|| self.line());
+        if strict.is_some() {
+            self.pos += 1;
+        }
+        let kind = if self.keyword("digraph") {
+            Kind::Digraph
+        } else if self.keyword("graph") {
+            Kind::Graph
+        } else {
+            return Err(self.error("expected graph or digraph"));
+        };
+        self.pos += 1;
+        let name = if self.is_id() { Some(self.id()?.0) } else { None };
+        let statements = self.statements()?;
+        Ok(Document { kind, strict, name, statements })
+    }
+
+    fn statements(&mut self) -> io::Result<Vec<Statement>> {
+        self.expect('{')?;
+        let mut statements = vec![];
+        loop {
+            if self.eat('}') {
+                return Ok(statements);
+            }
+            if self.eat(';') {
+                continue;
+            }
+            if self.peek().is_none() {
+                return Err(self.error("expected '}'"));
+            }
+            let target = [("graph", Target::Graph), ("node", Target::Node), ("edge", Target::Edge)]
+                .into_iter()
+                .find(|(keyword, _)| self.keyword(keyword));
+            if let Some((_, target)) = target {
+                self.pos += 1;
+                let attributes = self.attributes()?;
+                statements.push(Statement::Defaults(target, attributes));
+                continue;
+            }
+            let line = self.line();
+            let first = if self.keyword("subgraph") || self.peek() == Some(&Tok::Punct('{')) {
+                Operand::Subgraph(self.block()?)
+            } else {
+                let (key, html) = self.id()?;
+                if self.eat('=') {
+                    let (value, value_html) = self.id()?;
+                    statements.push(Statement::Assign(Attribute { key, value, html: value_html, line }));
+                    continue;
+                }
+                if html {
+                    return Err(invalid(line, "an HTML string is not a node name"));
+                }
+                Operand::Node(self.node_id(key, line)?)
+            };
+            if self.peek() != Some(&Tok::EdgeOp) {
+                match first {
+                    Operand::Subgraph(block) => statements.push(Statement::Subgraph(block)),
+                    Operand::Node(id) => statements.push(Statement::Node(id, self.attributes()?)),
+                }
+                continue;
+            }
+            let mut operands = vec![first];
+            while self.peek() == Some(&Tok::EdgeOp) {
+                self.pos += 1;
+                let line = self.line();
+                operands.push(if self.keyword("subgraph") || self.peek() == Some(&Tok::Punct('{')) {
+                    Operand::Subgraph(self.block()?)
+                } else {
+                    let (name, _) = self.id()?;
+                    Operand::Node(self.node_id(name, line)?)
+                });
+            }
+            statements.push(Statement::Edge(operands, self.attributes()?));
+        }
+    }
+
+    /// `subgraph name { ... }`, `subgraph { ... }` or `{ ... }`.
+    fn block(&mut self) -> io::Result<Block> {
+        let line = self.line();
+        let mut name = None;
+        if self.keyword("subgraph") {
+            self.pos += 1;
+            if self.is_id() {
+                name = Some(self.id()?.0);
+            }
+        }
+        let statements = self.statements()?;
+        Ok(Block { name, statements, line })
+    }
+
+    /// The rest of a node id after its name: `:port` or `:port:compass`.
+    fn node_id(&mut self, name: String, line: usize) -> io::Result<NodeId> {
+        let mut port = false;
+        while self.eat(':') {
+            self.id()?;
+            port = true;
+        }
+        Ok(NodeId { name, port, line })
+    }
+
+    /// Any number of `[key=value, ...]` lists.
+    fn attributes(&mut self) -> io::Result<Vec<Attribute>> {
+        let mut attributes = vec![];
+        while self.eat('[') {
+            while !self.eat(']') {
+                let line = self.line();
+                let (key, _) = self.id()?;
+                self.expect('=')?;
+                let (value, html) = self.id()?;
+                attributes.push(Attribute { key, value, html, line });
+                if !self.eat(',') {
+                    self.eat(';');
+                }
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+fn tokenize(text: &str) -> io::Result<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = vec![];
+    let mut line = 1;
+    let mut i = 0;
+    let id = |text: String, quoted: bool, html: bool| Tok::Id { text, quoted, html };
+    while i < chars.len() {
+        let c = chars[i];
+        let start_line = line;
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') || c == '#' && (i == 0 || chars[i - 1] == '\n') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(invalid(start_line, "unterminated comment"));
+            }
+            i += 2;
+        } else if c == '-' && matches!(chars.get(i + 1), Some('>' | '-')) {
+            tokens.push(Token { tok: Tok::EdgeOp, line });
+            i += 2;
+        } else if "{}[]=;,:+".contains(c) {
+            tokens.push(Token { tok: Tok::Punct(c), line });
+            i += 1;
+        } else if c == '"' {
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(invalid(start_line, "unterminated string")),
+                    Some('"') => break,
+                    // long lines are continued with a backslash
+                    Some('\\') if chars.get(i + 1) == Some(&'\n') => {
+                        line += 1;
+                        i += 1;
+                    }
+                    Some('\\') if chars.get(i + 1) == Some(&'\r') && chars.get(i + 2) == Some(&'\n') => {
+                        line += 1;
+                        i += 2;
+                    }
+                    Some('\\') if chars.get(i + 1).is_some() => {
+                        text.push('\\');
+                        text.push(chars[i + 1]);
+                        i += 1;
+                    }
+                    Some(&c) => {
+                        if c == '\n' {
+                            line += 1;
+                        }
+                        text.push(c);
+                    }
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token { tok: id(text, true, false), line: start_line });
+        } else if c == '<' {
+            // an HTML string, kept without its outer brackets
+            let start = i + 1;
+            let mut depth = 0;
+            loop {
+                match chars.get(i) {
+                    None => return Err(invalid(start_line, "unterminated HTML string")),
+                    Some('<') => depth += 1,
+                    Some('>') => depth -= 1,
+                    Some('\n') => line += 1,
+                    _ => {}
+                }
+                i += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            tokens.push(Token { tok: id(chars[start..i - 1].iter().collect(), false, true), line: start_line });
+        } else if c.is_ascii_digit() || (c == '-' || c == '.') && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit() || *d == '.') {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push(Token { tok: id(chars[start..i].iter().collect(), false, false), line });
+        } else if c.is_alphabetic() || c == '_' || !c.is_ascii() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.' || !chars[i].is_ascii()) {
+                i += 1;
+            }
+            tokens.push(Token { tok: id(chars[start..i].iter().collect(), false, false), line });
+        } else {
+            return Err(invalid(line, &format!("unexpected {:?}", c)));
+        }
+    }
+    Ok(tokens)
+}
@@ -1,34 +1,120 @@
 use crate::{
     arrow::{Arrow},
     style::{Style},
-    utils::{quote_string},
+    subgraph::{Subgraph},
+    utils::{quote_string, number},
 };
 
+/// One end of an `Edge`, either a single node or all the nodes of a
+/// `Subgraph`, which is rendered as `{"N1" "N2"}`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[derive(Clone)]
+pub enum Endpoint {
+    Node(String),
+    Subgraph { name: String, nodes: Vec<String> },
+}
+
+impl Endpoint {
+    pub fn to_dot_string(&self) -> String {
+        match self {
+            Endpoint::Node(name) => quote_string(name.clone()),
+            Endpoint::Subgraph { nodes, .. } => {
+                let names = nodes
+                    .iter()
+                    .map(|n| quote_string(n.clone()))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                format!("{{{}}}", names)
+            }
+        }
+    }
+
+    /// Names of all nodes this endpoint stands for.
+    pub fn node_names(&self) -> Vec<String> {
+        match self {
+            Endpoint::Node(name) => vec![name.clone()],
+            Endpoint::Subgraph { nodes, .. } => nodes.clone(),
+        }
+    }
+}
+
+impl From<&str> for Endpoint {
+    fn from(name: &str) -> Self {
+        Endpoint::Node(String::from(name))
+    }
+}
+
+impl From<&String> for Endpoint {
+    fn from(name: &String) -> Self {
+        Endpoint::Node(name.clone())
+    }
+}
+
+/// The subgraph as it is now: its nodes are copied, so nodes added to it
+/// later are not part of the endpoint. Add the nodes first, then the edge.
+impl From<&Subgraph> for Endpoint {
+    fn from(subgraph: &Subgraph) -> Self {
+        Endpoint::Subgraph { name: subgraph.name.clone(), nodes: subgraph.node_names() }
+    }
+}
+
 /// `Graph`'s edge.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone)]
 pub struct Edge {
-    from: String,
-    to: String,
-    label: String,
-    label_url: String,
-    url: String,
-    style: Style,
-    start_arrow: Arrow,
-    end_arrow: Arrow,
-    color: Option<String>,
+    pub(crate) endpoints: Vec<Endpoint>,
+    pub(crate) label: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) label_url: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) url: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) style: Style,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) start_arrow: Arrow,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) end_arrow: Arrow,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) color: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) penwidth: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) lhead: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) ltail: Option<String>,
+    /// The spline computed by a layout, see `Graph::apply_layout`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) pos: Option<String>,
 }
 
 impl Edge {
-    pub fn new(from: &str, to: &str, label: &str) -> Self {
+    /// Creates an edge between two endpoints, each being either a node name
+    /// or a `&Subgraph`. A subgraph endpoint keeps the nodes the subgraph
+    /// has at this point, not the ones added to it afterwards.
+    pub fn new<F: Into<Endpoint>, T: Into<Endpoint>>(from: F, to: T, label: &str) -> Self {
         Edge { 
-            from: String::from(from), to: String::from(to), 
+            endpoints: vec![from.into(), to.into()],
             label: String::from(label), label_url: Default::default(),
-            color: None, style: Style::None, 
+            color: None, penwidth: None, style: Style::None, 
             start_arrow: Arrow::default(), end_arrow: Arrow::default(),
-            url: Default::default() 
+            url: Default::default(), lhead: None, ltail: None, pos: None
         }
     }
 
+    /// Creates an edge chain like `a -> b -> c`, all segments sharing the
+    /// same attributes.
+    ///
+    /// Passing fewer than two endpoints will cause panic.
+    pub fn chain<T: Clone + Into<Endpoint>>(endpoints: &[T], label: &str) -> Self {
+        if endpoints.len() < 2 {
+            panic!("An edge chain should have at least two endpoints")
+        }
+        let mut edge = Edge::new(endpoints[0].clone(), endpoints[1].clone(), label);
+        edge.endpoints = endpoints.iter().cloned().map(Into::into).collect();
+        edge
+    }
+
     pub fn label(&mut self, label: &str) -> Self {
         let mut edge = self.clone();
         edge.label = String::from(label);
@@ -43,13 +129,52 @@ impl Edge {
 
     pub fn color(&mut self, color: Option<&str>) -> Self {
         let mut edge = self.clone();
-        edge.color = match color {
-            Some(c) => Some(String::from(c)),
-            None => None
-        };
+        edge.color = color.map(String::from);
         edge
     }
 
+    /// Clips the edge at the boundary of the `subgraph` cluster on its head
+    /// side. The `Graph` owning this edge will be rendered with `compound=true`.
+    pub fn lhead(&mut self, subgraph: &Subgraph) -> Self {
+        let mut edge = self.clone();
+        edge.lhead = Some(subgraph.name.clone());
+        edge
+    }
+
+    /// Clips the edge at the boundary of the `subgraph` cluster on its tail
+    /// side. The `Graph` owning this edge will be rendered with `compound=true`.
+    pub fn ltail(&mut self, subgraph: &Subgraph) -> Self {
+        let mut edge = self.clone();
+        edge.ltail = Some(subgraph.name.clone());
+        edge
+    }
+
+    /// Every `(from, to)` node pair this edge connects, with chains split
+    /// into their segments and subgraph endpoints expanded to their nodes.
+    pub fn node_pairs(&self) -> Vec<(String, String)> {
+        let mut pairs = vec![];
+        for segment in self.endpoints.windows(2) {
+            for from in segment[0].node_names() {
+                for to in segment[1].node_names() {
+                    pairs.push((from.clone(), to.clone()));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// A copy of this edge with the same attributes between `from` and `to`.
+    pub fn with_endpoints(&self, from: &str, to: &str) -> Edge {
+        let mut edge = self.clone();
+        edge.endpoints = vec![from.into(), to.into()];
+        edge
+    }
+
+    /// Whether this edge needs `compound=true` on the graph.
+    pub(crate) fn is_compound(&self) -> bool {
+        self.lhead.is_some() || self.ltail.is_some()
+    }
+
     pub fn start_arrow(&mut self, arrow: Arrow) -> Self {
         let mut edge = self.clone();
         edge.start_arrow = arrow;
@@ -62,6 +187,13 @@ impl Edge {
         edge
     }
 
+    /// The width of the line, 1 by default in Graphviz.
+    pub fn penwidth(&mut self, penwidth: Option<f64>) -> Self {
+        let mut edge = self.clone();
+        edge.penwidth = penwidth;
+        edge
+    }
+
     pub fn label_url(&mut self, url: String) -> Self {
         let mut edge = self.clone();
         edge.label_url = url;
@@ -82,9 +214,14 @@ impl Edge {
         let escaped_label_url: &String = &quote_string(self.label_url.clone());
         let escaped_url: &String = &quote_string(self.url.clone());
         
-        let mut text = vec!["\"", self.from.as_str(), "\" ",
-        edge_symbol, " ",
-        "\"", self.to.as_str(), "\"",];
+        let separator = format!(" {} ", edge_symbol);
+        let endpoints: String = self.endpoints
+            .iter()
+            .map(|e| e.to_dot_string())
+            .collect::<Vec<String>>()
+            .join(&separator);
+
+        let mut text = vec![endpoints.as_str()];
         
         text.push("[label=");
         text.push(escaped_label.as_str());
@@ -108,19 +245,45 @@ impl Edge {
             text.push("\"]");
         }
 
-        let color: Option<String> = match &self.color {
-            Some(l) => {
-                Some((*l).clone())
-            },
-            None => None,
-        };
-        if let Some(c) = color {
+        if let Some(c) = self.color.clone() {
             colorstring = quote_string(c);
             text.push("[color=");
             text.push(&colorstring);
             text.push("]");
         }
 
+        let penwidth_string: String;
+        if let Some(p) = self.penwidth {
+            penwidth_string = number(p);
+            text.push("[penwidth=");
+            text.push(&penwidth_string);
+            text.push("]");
+        }
+
+        let lhead_string: String;
+        if let Some(l) = self.lhead.clone() {
+            lhead_string = quote_string(l);
+            text.push("[lhead=");
+            text.push(&lhead_string);
+            text.push("]");
+        }
+
+        let ltail_string: String;
+        if let Some(l) = self.ltail.clone() {
+            ltail_string = quote_string(l);
+            text.push("[ltail=");
+            text.push(&ltail_string);
+            text.push("]");
+        }
+
+        let pos_string: String;
+        if let Some(p) = self.pos.clone() {
+            pos_string = quote_string(p);
+            text.push("[pos=");
+            text.push(&pos_string);
+            text.push("]");
+        }
+
         let mut arrow_text: Vec<String> = vec![];
         let mut arrow_str: String = String::new();
         if !self.start_arrow.is_default() || !self.end_arrow.is_default() {
@@ -134,14 +297,14 @@ impl Edge {
                 arrow_text.push(String::from("dir=\"both\""));
             }
         }
-        if arrow_text.len() > 0 {
+        if !arrow_text.is_empty() {
             arrow_str.push_str(&arrow_text.join(" "));
             arrow_str.insert(0, '[');
-            arrow_str.push_str("]");
+            arrow_str.push(']');
             text.push(arrow_str.as_str());
         }
 
         text.push(";");
-        return text.into_iter().collect();
+        text.into_iter().collect()
     }
 }
\ No newline at end of file
@@ -0,0 +1,131 @@
+//! Smaller views of a graph: neighborhoods, filters and induced subgraphs.
+//!
+//! The views are new graphs keeping the attributes of what they keep, and
+//! the clusters that still have nodes or edges.
+//!
+//! ```rust
+//! use dot_graph::{extract::Direction, Graph, Kind, Edge};
+//!
+//! let mut graph = Graph::new("deps", Kind::Digraph);
+//! graph.add_edge(Edge::chain(&["app", "http", "serde", "core"], ""));
+//! graph.add_edge(Edge::new("cli", "serde", ""));
+//! assert_eq!(graph.neighborhood("serde", 1, Direction::Incoming).to_dot_string().unwrap(),
+//! r#"digraph deps {
+//!     "http" -> "serde"[label=""];
+//!     "cli" -> "serde"[label=""];
+//! }
+//! "#);
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    algo::Adjacency,
+    edge::Edge,
+    graph::{Graph, Kind},
+    node::Node,
+};
+
+/// Which edges `Graph::neighborhood` follows.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Direction {
+    /// From a node to the nodes it leads to.
+    Outgoing,
+    /// From a node to the nodes leading to it.
+    Incoming,
+    /// Both, like all edges of a `Kind::Graph`.
+    Both,
+}
+
+impl Graph {
+    /// The nodes at most `depth` edges away from `id`, following
+    /// `direction`, with the edges between them. `usize::MAX` gives all the
+    /// descendants or ancestors. An unknown `id` gives an empty graph.
+    pub fn neighborhood(&self, id: &str, depth: usize, direction: Direction) -> Graph {
+        let adjacency = Adjacency::new(self, self.kind == Kind::Graph || direction == Direction::Both);
+        let Some(&start) = adjacency.index.get(id) else {
+            return self.induced::<&str>(&[]);
+        };
+        let edges = if direction == Direction::Incoming { &adjacency.previous } else { &adjacency.next };
+        let mut distance: Vec<Option<usize>> = vec![None; adjacency.names.len()];
+        distance[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            let d = distance[node].unwrap_or_default();
+            if d == depth {
+                continue;
+            }
+            for &(other, _) in edges[node].iter() {
+                if distance[other].is_none() {
+                    distance[other] = Some(d + 1);
+                    queue.push_back(other);
+                }
+            }
+        }
+        let names: Vec<&str> = (0..distance.len())
+            .filter(|&i| distance[i].is_some())
+            .map(|i| adjacency.names[i].as_str())
+            .collect();
+        self.induced(&names)
+    }
+
+    /// The nodes of `ids` with the edges between them.
+    pub fn induced<S: AsRef<str>>(&self, ids: &[S]) -> Graph {
+        let ids: HashSet<&str> = ids.iter().map(AsRef::as_ref).collect();
+        self.filter(|n| ids.contains(n.name.as_str()), |_| true)
+    }
+
+    /// The nodes `node` accepts, with the edges `edge` accepts between them.
+    /// Nodes only referenced by edges are given to `node` with the defaults
+    /// of `Node::new`, whatever their name; when they lose all their edges
+    /// they are declared. An edge with more node pairs, like `a -> b -> c`,
+    /// is split when only some of them are kept.
+    pub fn filter<N: Fn(&Node) -> bool, E: Fn(&Edge) -> bool>(&self, node: N, edge: E) -> Graph {
+        let declared: HashMap<&str, &Node> = self.all_nodes().into_iter().map(|n| (n.name.as_str(), n)).collect();
+        let kept: Vec<String> = self
+            .node_names()
+            .into_iter()
+            .filter(|name| match declared.get(name.as_str()) {
+                Some(n) => node(n),
+                None => node(&Node::implicit(name)),
+            })
+            .collect();
+        let kept_names: HashSet<&str> = kept.iter().map(String::as_str).collect();
+        let is_kept = |name: &str| kept_names.contains(name);
+
+        let mut graph = self.clone();
+        let keep: Vec<bool> = self
+            .all_edges()
+            .into_iter()
+            .flat_map(|e| {
+                let accepted = edge(e);
+                e.node_pairs().into_iter().map(move |(from, to)| (accepted, from, to))
+            })
+            .map(|(accepted, from, to)| accepted && is_kept(&from) && is_kept(&to))
+            .collect();
+        graph.rewrite_pairs(&keep, &vec![false; keep.len()]);
+        for s in graph.subgraph.iter_mut() {
+            s.nodes.retain(|n| is_kept(&n.name));
+        }
+        graph.nodes.retain(|n| is_kept(&n.name));
+        graph.subgraph.retain(|s| !s.nodes.is_empty() || !s.edges.is_empty());
+
+        let clusters: HashSet<String> = graph.subgraph.iter().map(|s| s.name.clone()).collect();
+        for edges in graph.subgraph.iter_mut().map(|s| &mut s.edges).chain(std::iter::once(&mut graph.edges)) {
+            for e in edges.iter_mut() {
+                e.lhead = e.lhead.take().filter(|c| clusters.contains(c));
+                e.ltail = e.ltail.take().filter(|c| clusters.contains(c));
+            }
+        }
+        for names in graph.same_rank.iter_mut() {
+            names.retain(|n| is_kept(n));
+        }
+        graph.same_rank.retain(|names| !names.is_empty());
+
+        let left: HashSet<String> = graph.node_names().into_iter().collect();
+        for name in kept.iter().filter(|k| !left.contains(*k)) {
+            graph.nodes.push(Node::implicit(name));
+        }
+        graph
+    }
+}
@@ -0,0 +1,129 @@
+//! Export to [GEXF](https://gexf.net/), the format of Gephi.
+
+use crate::{
+    edge::Endpoint,
+    graph::{Graph, Kind},
+    node::Node,
+    style::Style,
+    utils::{rgb, unescape_label},
+    xml::escape,
+};
+
+impl Graph {
+    /// Renders the graph as a GEXF 1.3 document.
+    ///
+    /// Clusters become nodes of their own and their members point to them
+    /// with `pid`. Labels are written as node and edge labels, in plain
+    /// text without the escapes of DOT, colors as `viz:color` (names Gephi
+    /// could not know are left out), node shapes as the closest `viz:shape`
+    /// and dashed or dotted edges as edge shapes. Edge chains are split
+    /// into one edge per pair.
+    pub fn to_gexf(&self) -> String {
+        let edgetype = match self.kind {
+            Kind::Digraph => "directed",
+            Kind::Graph => "undirected",
+        };
+        let mut lines = vec![
+            String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"),
+            String::from("<gexf xmlns=\"http://gexf.net/1.3\" xmlns:viz=\"http://gexf.net/1.3/viz\" version=\"1.3\">"),
+            format!("  <graph mode=\"static\" defaultedgetype=\"{}\">", edgetype),
+            String::from("    <nodes>"),
+        ];
+
+        let mut declared: Vec<&str> = vec![];
+        for s in self.subgraph.iter() {
+            let label = if s.label.is_empty() { s.name.clone() } else { unescape_label(&s.label) };
+            let viz = color(&s.color);
+            element(&mut lines, &format!("node id=\"{}\" label=\"{}\"", escape(&s.name), escape(&label)), viz, "node");
+            for n in s.nodes.iter() {
+                node(&mut lines, n, Some(&s.name));
+                declared.push(&n.name);
+            }
+        }
+        for n in self.nodes.iter() {
+            node(&mut lines, n, None);
+            declared.push(&n.name);
+        }
+        for name in self.node_names() {
+            if !declared.contains(&name.as_str()) {
+                node(&mut lines, &Node::implicit(&name), None);
+            }
+        }
+        lines.push(String::from("    </nodes>"));
+
+        lines.push(String::from("    <edges>"));
+        let mut count = 0;
+        for e in self.all_edges() {
+            for segment in e.endpoints.windows(2) {
+                let mut tag = format!(
+                    "edge id=\"{}\" source=\"{}\" target=\"{}\"",
+                    count, escape(id(&segment[0])), escape(id(&segment[1]))
+                );
+                count += 1;
+                if !e.label.is_empty() {
+                    tag.push_str(&format!(" label=\"{}\"", escape(&unescape_label(&e.label))));
+                }
+                let mut viz = color(&e.color);
+                let shape = match e.style {
+                    Style::Dashed => Some("dashed"),
+                    Style::Dotted => Some("dotted"),
+                    _ => None,
+                };
+                if let Some(shape) = shape {
+                    viz.push(format!("        <viz:shape value=\"{}\"/>", shape));
+                }
+                element(&mut lines, &tag, viz, "edge");
+            }
+        }
+        lines.push(String::from("    </edges>"));
+
+        lines.push(String::from("  </graph>"));
+        lines.push(String::from("</gexf>"));
+        let mut text = lines.join("\n");
+        text.push('\n');
+        text
+    }
+}
+
+fn id(endpoint: &Endpoint) -> &str {
+    match endpoint {
+        Endpoint::Node(name) => name,
+        Endpoint::Subgraph { name, .. } => name,
+    }
+}
+
+/// Writes `<tag/>`, or `<tag>` with `children` when there are any.
+fn element(lines: &mut Vec<String>, tag: &str, children: Vec<String>, name: &str) {
+    if children.is_empty() {
+        lines.push(format!("      <{}/>", tag));
+    } else {
+        lines.push(format!("      <{}>", tag));
+        lines.extend(children);
+        lines.push(format!("      </{}>", name));
+    }
+}
+
+fn node(lines: &mut Vec<String>, n: &Node, parent: Option<&str>) {
+    let mut tag = format!("node id=\"{}\" label=\"{}\"", escape(&n.name), escape(&unescape_label(&n.label)));
+    if let Some(p) = parent {
+        tag.push_str(&format!(" pid=\"{}\"", escape(p)));
+    }
+    let mut viz = color(&n.color);
+    let shape = n.shape.as_deref().map(|shape| match shape {
+        "box" | "rect" | "rectangle" | "square" => "square",
+        "triangle" | "invtriangle" => "triangle",
+        "diamond" | "Mdiamond" => "diamond",
+        _ => "disc",
+    });
+    if let Some(shape) = shape {
+        viz.push(format!("        <viz:shape value=\"{}\"/>", shape));
+    }
+    element(lines, &tag, viz, "node");
+}
+
+fn color(color: &Option<String>) -> Vec<String> {
+    match color.as_deref().and_then(rgb) {
+        Some((r, g, b)) => vec![format!("        <viz:color r=\"{}\" g=\"{}\" b=\"{}\"/>", r, g, b)],
+        None => vec![],
+    }
+}
@@ -1,44 +1,119 @@
 use crate::{
     node::{Node},
     edge::{Edge}, subgraph::Subgraph, utils::quote_string,
+    collapse::Collapsed,
 };
+use std::collections::HashSet;
 use std::io::prelude::*;
 use std::io;
 
 /// Entry point of this library, use `to_dot_string` to get the string output.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(remote = "Self"))]
 #[derive(Clone)]
 pub struct Graph {
-    name: String,
-    kind: Kind,
-    url: String,
-    nodes: Vec<Node>,
-    edges: Vec<Edge>,
-    subgraph: Vec<Subgraph>
+    pub(crate) name: String,
+    pub(crate) kind: Kind,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) url: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) nodes: Vec<Node>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) edges: Vec<Edge>,
+    #[cfg_attr(feature = "serde", serde(default, rename = "subgraphs"))]
+    pub(crate) subgraph: Vec<Subgraph>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) compound: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) same_rank: Vec<Vec<String>>,
+    /// The bounding box computed by a layout, see `Graph::apply_layout`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) bb: Option<String>,
+    /// The subgraphs to bring back with `Graph::expand`, not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) collapsed: Vec<Collapsed>,
 }
 
 impl Graph {
     pub fn new(name: &str, kind: Kind) -> Graph {
-        Graph { name: String::from(name), kind: kind, nodes: vec![], edges: vec![], subgraph: vec![], url: Default::default() }
+        Graph { name: String::from(name), kind, nodes: vec![], edges: vec![], subgraph: vec![], url: Default::default(), compound: false, same_rank: vec![], bb: None, collapsed: vec![] }
     }
 
-    pub fn add_node(&mut self, node: Node) -> () {
+    pub fn add_node(&mut self, node: Node) {
         self.nodes.push(node);
     }
 
-    pub fn add_edge(&mut self, edge: Edge) -> () {
+    /// Adds an edge; an edge with `lhead`/`ltail` turns on `compound=true`.
+    pub fn add_edge(&mut self, edge: Edge) {
+        if edge.is_compound() {
+            self.compound = true;
+        }
         self.edges.push(edge);
     }
 
-    pub fn add_subgraph(&mut self, subgraph: Subgraph) -> () {
+    pub fn add_subgraph(&mut self, subgraph: Subgraph) {
+        if subgraph.has_compound_edges() {
+            self.compound = true;
+        }
         self.subgraph.push(subgraph.edgeop(self.kind.edgeop()))
     }
 
+    /// Puts the nodes called `names` on the same rank, rendered as
+    /// `{rank=same; ...}`.
+    pub fn same_rank(&mut self, names: &[&str]) {
+        self.same_rank.push(names.iter().map(|n| String::from(*n)).collect());
+    }
+
     pub fn url(&mut self, url: String) -> Self {
         let mut graph = self.clone();
         graph.url = url;
         graph
     }
 
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    pub fn subgraphs(&self) -> &[Subgraph] {
+        &self.subgraph
+    }
+
+    /// All declared nodes, the ones in subgraphs first as they are rendered.
+    pub fn all_nodes(&self) -> Vec<&Node> {
+        self.subgraph
+            .iter()
+            .flat_map(|s| s.nodes.iter())
+            .chain(self.nodes.iter())
+            .collect()
+    }
+
+    /// All edges, the ones in subgraphs first as they are rendered.
+    pub fn all_edges(&self) -> Vec<&Edge> {
+        self.subgraph
+            .iter()
+            .flat_map(|s| s.edges.iter())
+            .chain(self.edges.iter())
+            .collect()
+    }
+
+    /// Names of all nodes, including the ones only referenced by edges,
+    /// without duplicates and in order of appearance.
+    pub fn node_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = vec![];
+        let mut seen: HashSet<String> = HashSet::new();
+        let declared = self.all_nodes().into_iter().map(|n| n.name.clone());
+        let referenced = self.all_edges()
+            .into_iter()
+            .flat_map(|e| e.node_pairs())
+            .flat_map(|(from, to)| vec![from, to]);
+        for name in declared.chain(referenced) {
+            if seen.insert(name.clone()) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
     pub fn to_dot_string(&self) -> io::Result<String> {
         let mut writer = Vec::new();
         self.render_opts(&mut writer).unwrap();
@@ -49,18 +124,7 @@ impl Graph {
 
     /// Renders graph `g` into the writer `w` in DOT syntax.
     /// (Main entry point for the library.)
-    fn render_opts<'a,W: Write>(&self, w: &mut W) -> io::Result<()> {
-        fn writeln<W: Write>(w: &mut W, arg: &[&str]) -> io::Result<()> {
-            for &s in arg {
-                w.write_all(s.as_bytes())?;
-            }
-            write!(w, "\n")
-        }
-
-        fn indent<W: Write>(w: &mut W) -> io::Result<()> {
-            w.write_all(b"    ")
-        }
-
+    fn render_opts<W: Write>(&self, w: &mut W) -> io::Result<()> {
         writeln(w, &[self.kind.keyword(), " ", self.name.as_str(), " {"])?;
 
         if !self.url.is_empty(){
@@ -68,19 +132,35 @@ impl Graph {
             writeln(w, &["URL=", quote_string(self.url.clone()).as_str()])?;
         }
 
+        if self.compound {
+            indent(w)?;
+            writeln(w, &["compound=true"])?;
+        }
+
+        if let Some(bb) = &self.bb {
+            indent(w)?;
+            writeln(w, &["bb=", quote_string(bb.clone()).as_str()])?;
+        }
+
         for n in self.subgraph.iter() {
             indent(w)?;
             let mut text: Vec<&str> = vec![];
             let subgraph_dot_string: String = n.to_dot_string();
-            text.push(&subgraph_dot_string.as_str());
+            text.push(subgraph_dot_string.as_str());
             writeln(w, &text)?;
         }
 
+        for names in self.same_rank.iter() {
+            indent(w)?;
+            let names: Vec<String> = names.iter().map(|n| format!("{}; ", quote_string(n.clone()))).collect();
+            writeln(w, &["{rank=same; ", names.concat().as_str(), "}"])?;
+        }
+
         for n in self.nodes.iter() {
             indent(w)?;
             let mut text: Vec<&str> = vec![];
             let node_dot_string: String = n.to_dot_string();
-            text.push(&node_dot_string.as_str());
+            text.push(node_dot_string.as_str());
             writeln(w, &text)?;
         }
 
@@ -89,7 +169,7 @@ impl Graph {
             indent(w)?;
             let mut text: Vec<&str> = vec![];
             let edge_dot_string: String = e.to_dot_string(edge_symbol);
-            text.push(&edge_dot_string.as_str());
+            text.push(edge_dot_string.as_str());
             writeln(w, &text)?;
         }
 
@@ -97,8 +177,23 @@ impl Graph {
     }
 }
 
+/// Writes all `arg` pieces followed by a line break.
+pub(crate) fn writeln<W: Write>(w: &mut W, arg: &[&str]) -> io::Result<()> {
+    for &s in arg {
+        w.write_all(s.as_bytes())?;
+    }
+    writeln!(w)
+}
+
+/// Writes the indentation of a statement in the graph body.
+pub(crate) fn indent<W: Write>(w: &mut W) -> io::Result<()> {
+    w.write_all(b"    ")
+}
+
 /// Graph kind determines if `digraph` or `graph` is used as keyword
 /// for the graph.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum Kind {
     Digraph,
@@ -122,4 +217,26 @@ impl Kind {
             Kind::Graph => "--",
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Graph {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Graph::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Graph {
+    /// Turns `compound` on for edges with `lhead`/`ltail` and gives the
+    /// subgraphs the edge operator of the graph, like `Graph::add_edge` and
+    /// `Graph::add_subgraph` do, whatever the input says.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut graph = Graph::deserialize(deserializer)?;
+        graph.compound |= graph.all_edges().iter().any(|e| e.is_compound());
+        for s in graph.subgraph.iter_mut() {
+            s.edgeop = String::from(graph.kind.edgeop());
+        }
+        Ok(graph)
+    }
+}
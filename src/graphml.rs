@@ -0,0 +1,417 @@
+//! Reading and writing [GraphML](http://graphml.graphdrawing.org/).
+//!
+//! Clusters are written as nodes holding a nested `<graph>` with their
+//! nodes and edges, and every DOT attribute in use gets a `<key>` named
+//! after it, like `<key id="node_color" for="node" attr.name="color"
+//! attr.type="string"/>`. Labels are plain text there, with line breaks
+//! for the `\n`, `\l` and `\r` of DOT, and are escaped again when read.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+
+use crate::{
+    arrow::Arrow,
+    edge::{Edge, Endpoint},
+    graph::{Graph, Kind},
+    node::{self, Node},
+    style::Style,
+    subgraph::{self, Subgraph},
+    utils::{escape_label, number, unescape_label},
+    xml::{escape, Element},
+};
+
+/// All keys this crate writes, in the order they are declared: the element
+/// they are for, the attribute name and its GraphML type.
+const KEYS: [(&str, &str, &str); 18] = [
+    ("graph", "URL", "string"),
+    ("graph", "compound", "boolean"),
+    ("node", "label", "string"),
+    ("node", "style", "string"),
+    ("node", "color", "string"),
+    ("node", "shape", "string"),
+    ("node", "URL", "string"),
+    ("edge", "label", "string"),
+    ("edge", "labelURL", "string"),
+    ("edge", "URL", "string"),
+    ("edge", "style", "string"),
+    ("edge", "color", "string"),
+    ("edge", "penwidth", "double"),
+    ("edge", "arrowhead", "string"),
+    ("edge", "arrowtail", "string"),
+    ("edge", "dir", "string"),
+    ("edge", "lhead", "string"),
+    ("edge", "ltail", "string"),
+];
+
+impl Graph {
+    /// Renders the graph as a GraphML document.
+    ///
+    /// `Kind` sets `edgedefault`, nodes only referenced by edges get a
+    /// `<node>` of their own, and edge chains or edges to subgraphs are
+    /// split into one `<edge>` per node pair.
+    pub fn to_graphml(&self) -> String {
+        let mut writer = Writer { lines: vec![], used: vec![false; KEYS.len()], edge_count: 0 };
+        let edgedefault = match self.kind {
+            Kind::Digraph => "directed",
+            Kind::Graph => "undirected",
+        };
+
+        writer.lines.push(format!("  <graph id=\"{}\" edgedefault=\"{}\">", escape(&self.name), edgedefault));
+        if !self.url.is_empty() {
+            writer.data("graph", "URL", &self.url, 4);
+        }
+        if self.compound {
+            writer.data("graph", "compound", "true", 4);
+        }
+
+        let mut declared: Vec<&str> = vec![];
+        for s in self.subgraph.iter() {
+            writer.lines.push(format!("    <node id=\"{}\">", escape(&s.name)));
+            writer.data("node", "label", &unescape_label(&s.label), 6);
+            writer.attributes(s.style, &s.color, &None, &s.url, 6);
+            writer.lines.push(format!("      <graph id=\"{}:\" edgedefault=\"{}\">", escape(&s.name), edgedefault));
+            for n in s.nodes.iter() {
+                writer.node(n, 8);
+                declared.push(&n.name);
+            }
+            for e in s.edges.iter() {
+                writer.edge(e, 8);
+            }
+            writer.lines.push(String::from("      </graph>"));
+            writer.lines.push(String::from("    </node>"));
+        }
+        for n in self.nodes.iter() {
+            writer.node(n, 4);
+            declared.push(&n.name);
+        }
+        for name in self.node_names() {
+            if !declared.contains(&name.as_str()) {
+                writer.node(&Node::implicit(&name), 4);
+            }
+        }
+        for e in self.edges.iter() {
+            writer.edge(e, 4);
+        }
+        writer.lines.push(String::from("  </graph>"));
+
+        let mut lines = vec![
+            String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"),
+            String::from("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\" \
+                xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+                xsi:schemaLocation=\"http://graphml.graphdrawing.org/xmlns \
+                http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd\">"),
+        ];
+        for (i, (target, name, kind)) in KEYS.iter().enumerate() {
+            if writer.used[i] {
+                lines.push(format!(
+                    "  <key id=\"{}_{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>",
+                    target, name, target, name, kind
+                ));
+            }
+        }
+        lines.append(&mut writer.lines);
+        lines.push(String::from("</graphml>"));
+
+        let mut text = lines.join("\n");
+        text.push('\n');
+        text
+    }
+
+    /// Reads a GraphML document, as written by `to_graphml` or by tools
+    /// like yEd and Gephi.
+    ///
+    /// `<data>` is matched to DOT attributes by the `attr.name` of its key,
+    /// unknown keys are ignored. Nodes holding a nested graph become
+    /// clusters when their id is a valid `Subgraph` name, the contents of
+    /// deeper nested graphs are flattened into them, and the contents of
+    /// other nested graphs into the graph. Node ids which are not valid
+    /// node names, like the numbers Gephi writes, are renamed (`0` becomes
+    /// `n0`) and kept as the label of nodes without one.
+    pub fn from_graphml(graphml: &str) -> io::Result<Graph> {
+        let root = Element::parse(graphml)?;
+        if root.name != "graphml" {
+            return Err(invalid("the root element is not <graphml>"));
+        }
+        let keys: HashMap<&str, Key> = root
+            .children_named("key")
+            .filter_map(|k| {
+                let id = k.attribute("id")?;
+                let name = k.attribute("attr.name").unwrap_or(id);
+                let default = k.child("default").map(|d| d.text.clone());
+                Some((id, Key { target: k.attribute("for").unwrap_or("all"), name, default }))
+            })
+            .collect();
+        let top = root.child("graph").ok_or_else(|| invalid("missing <graph>"))?;
+        let mut ids = vec![];
+        collect_ids(top, &mut ids);
+        let reader = Reader { keys, names: rename_ids(&ids) };
+
+        let kind = match top.attribute("edgedefault") {
+            Some("undirected") => Kind::Graph,
+            _ => Kind::Digraph,
+        };
+        let mut graph = Graph::new(top.attribute("id").unwrap_or("G"), kind);
+        let data = reader.data(top, "graph");
+        if let Some(url) = data.get("URL") {
+            graph.url = url.clone();
+        }
+        graph.compound = data.get("compound").map(|c| c == "true").unwrap_or(false);
+
+        // nodes and clusters first, so edges could point to clusters
+        let mut nodes: Vec<Node> = vec![];
+        let mut subgraphs: Vec<(Subgraph, Vec<&Element>)> = vec![];
+        let mut edges: Vec<&Element> = vec![];
+        for n in top.children_named("node") {
+            match n.child("graph") {
+                Some(nested) if subgraph::is_valid_name(n.attribute("id").unwrap_or_default()) => {
+                    let data = reader.data(n, "node");
+                    let mut s = Subgraph::new(n.attribute("id").unwrap_or_default());
+                    s.label = data.get("label").map(|l| escape_label(l)).unwrap_or_default();
+                    s.style = style(&data);
+                    s.color = data.get("color").cloned();
+                    s.url = data.get("URL").cloned().unwrap_or_default();
+                    let mut members = vec![];
+                    let mut member_edges = vec![];
+                    reader.flatten(nested, &mut members, &mut member_edges)?;
+                    for m in members {
+                        s.add_node(m);
+                    }
+                    subgraphs.push((s, member_edges));
+                }
+                Some(nested) => reader.flatten(nested, &mut nodes, &mut edges)?,
+                None => nodes.push(reader.node(n)?),
+            }
+        }
+        edges.extend(top.children_named("edge"));
+
+        let clusters: Vec<Subgraph> = subgraphs.iter().map(|(s, _)| s.clone()).collect();
+        for (mut s, member_edges) in subgraphs {
+            for e in member_edges {
+                s.add_edge(reader.edge(e, &clusters)?);
+            }
+            graph.add_subgraph(s);
+        }
+        for n in nodes {
+            graph.add_node(n);
+        }
+        for e in edges {
+            graph.add_edge(reader.edge(e, &clusters)?);
+        }
+        Ok(graph)
+    }
+}
+
+/// The ids of the nodes of `graph` and of the graphs nested in it, and the
+/// ids its edges refer to.
+fn collect_ids<'e>(graph: &'e Element, ids: &mut Vec<&'e str>) {
+    for n in graph.children_named("node") {
+        ids.extend(n.attribute("id"));
+        if let Some(nested) = n.child("graph") {
+            collect_ids(nested, ids);
+        }
+    }
+    for e in graph.children_named("edge") {
+        ids.extend(e.attribute("source"));
+        ids.extend(e.attribute("target"));
+    }
+}
+
+/// Node names for the `ids` which are not valid ones: `_` replaces what a
+/// name cannot have, `n` goes in front of what it cannot start with, and a
+/// suffix is added where needed to keep the names unique.
+fn rename_ids(ids: &[&str]) -> HashMap<String, String> {
+    let taken: HashSet<&str> = ids.iter().copied().collect();
+    let mut names: HashMap<String, String> = HashMap::new();
+    for id in ids.iter().filter(|id| !node::is_valid_name(id)) {
+        if names.contains_key(*id) {
+            continue;
+        }
+        let mut base: String = id.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '.' { c } else { '_' }).collect();
+        if !node::is_valid_name(&base) {
+            base.insert(0, 'n');
+        }
+        let mut name = base.clone();
+        let mut i = 1;
+        while taken.contains(name.as_str()) || names.values().any(|n| *n == name) {
+            name = format!("{}{}", base, i);
+            i += 1;
+        }
+        names.insert(String::from(*id), name);
+    }
+    names
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn style(data: &HashMap<String, String>) -> Style {
+    data.get("style").and_then(|s| Style::from_slice(s)).unwrap_or(Style::None)
+}
+
+struct Writer {
+    lines: Vec<String>,
+    /// Which of `KEYS` are in use.
+    used: Vec<bool>,
+    edge_count: usize,
+}
+
+impl Writer {
+    fn data(&mut self, target: &str, name: &str, value: &str, indent: usize) {
+        let i = KEYS.iter().position(|(t, n, _)| *t == target && *n == name).unwrap();
+        self.used[i] = true;
+        self.lines.push(format!(
+            "{}<data key=\"{}_{}\">{}</data>",
+            " ".repeat(indent), target, name, escape(value)
+        ));
+    }
+
+    fn attributes(&mut self, style: Style, color: &Option<String>, shape: &Option<String>, url: &str, indent: usize) {
+        if style != Style::None {
+            self.data("node", "style", style.as_slice(), indent);
+        }
+        if let Some(c) = color {
+            self.data("node", "color", c, indent);
+        }
+        if let Some(s) = shape {
+            self.data("node", "shape", s, indent);
+        }
+        if !url.is_empty() {
+            self.data("node", "URL", url, indent);
+        }
+    }
+
+    fn node(&mut self, n: &Node, indent: usize) {
+        self.lines.push(format!("{}<node id=\"{}\">", " ".repeat(indent), escape(&n.name)));
+        self.data("node", "label", &unescape_label(&n.label), indent + 2);
+        self.attributes(n.style, &n.color, &n.shape, &n.url, indent + 2);
+        self.lines.push(format!("{}</node>", " ".repeat(indent)));
+    }
+
+    fn edge(&mut self, e: &Edge, indent: usize) {
+        for (from, to) in e.node_pairs() {
+            self.lines.push(format!(
+                "{}<edge id=\"e{}\" source=\"{}\" target=\"{}\">",
+                " ".repeat(indent), self.edge_count, escape(&from), escape(&to)
+            ));
+            self.edge_count += 1;
+            let inner = indent + 2;
+            self.data("edge", "label", &unescape_label(&e.label), inner);
+            if !e.label_url.is_empty() {
+                self.data("edge", "labelURL", &e.label_url, inner);
+            }
+            if !e.url.is_empty() {
+                self.data("edge", "URL", &e.url, inner);
+            }
+            if e.style != Style::None {
+                self.data("edge", "style", e.style.as_slice(), inner);
+            }
+            if let Some(c) = &e.color {
+                self.data("edge", "color", c, inner);
+            }
+            if let Some(p) = e.penwidth {
+                self.data("edge", "penwidth", &number(p), inner);
+            }
+            if !e.end_arrow.is_default() {
+                self.data("edge", "arrowhead", &e.end_arrow.to_dot_string(), inner);
+            }
+            if !e.start_arrow.is_default() {
+                self.data("edge", "arrowtail", &e.start_arrow.to_dot_string(), inner);
+            }
+            if !e.start_arrow.is_default() && !e.end_arrow.is_default() {
+                self.data("edge", "dir", "both", inner);
+            }
+            if let Some(l) = &e.lhead {
+                self.data("edge", "lhead", l, inner);
+            }
+            if let Some(l) = &e.ltail {
+                self.data("edge", "ltail", l, inner);
+            }
+            self.lines.push(format!("{}</edge>", " ".repeat(indent)));
+        }
+    }
+}
+
+struct Key<'a> {
+    target: &'a str,
+    name: &'a str,
+    default: Option<String>,
+}
+
+struct Reader<'a> {
+    keys: HashMap<&'a str, Key<'a>>,
+    /// The node names for ids which are not valid ones.
+    names: HashMap<String, String>,
+}
+
+impl<'a> Reader<'a> {
+    /// The `<data>` of `element` by attribute name, with key defaults.
+    fn data(&self, element: &Element, target: &str) -> HashMap<String, String> {
+        let mut data: HashMap<String, String> = self.keys
+            .values()
+            .filter(|k| k.target == target || k.target == "all")
+            .filter_map(|k| k.default.clone().map(|d| (String::from(k.name), d)))
+            .collect();
+        for d in element.children_named("data") {
+            if let Some(key) = d.attribute("key").and_then(|k| self.keys.get(k)) {
+                data.insert(String::from(key.name), String::from(d.text.trim()));
+            }
+        }
+        data
+    }
+
+    fn node(&self, element: &Element) -> io::Result<Node> {
+        let id = element.attribute("id").ok_or_else(|| invalid("<node> without id"))?;
+        let data = self.data(element, "node");
+        let mut n = Node::new(self.name(id));
+        n.label = escape_label(data.get("label").map_or(id, String::as_str));
+        n.style = style(&data);
+        n.color = data.get("color").cloned();
+        n.shape = data.get("shape").cloned();
+        n.url = data.get("URL").cloned().unwrap_or_default();
+        Ok(n)
+    }
+
+    /// The node name for the id `id`.
+    fn name<'i>(&'i self, id: &'i str) -> &'i str {
+        self.names.get(id).map_or(id, String::as_str)
+    }
+
+    /// Collects the nodes and edges of `graph` and of all graphs nested in it.
+    fn flatten<'e>(&self, graph: &'e Element, nodes: &mut Vec<Node>, edges: &mut Vec<&'e Element>) -> io::Result<()> {
+        for n in graph.children_named("node") {
+            match n.child("graph") {
+                Some(nested) => self.flatten(nested, nodes, edges)?,
+                None => nodes.push(self.node(n)?),
+            }
+        }
+        edges.extend(graph.children_named("edge"));
+        Ok(())
+    }
+
+    fn edge(&self, element: &Element, clusters: &[Subgraph]) -> io::Result<Edge> {
+        let endpoint = |key: &str| -> io::Result<Endpoint> {
+            let id = element.attribute(key).ok_or_else(|| invalid(&format!("<edge> without {}", key)))?;
+            match clusters.iter().find(|s| s.name == id) {
+                Some(s) => Ok(Endpoint::from(s)),
+                None => Ok(Endpoint::from(self.name(id))),
+            }
+        };
+        let data = self.data(element, "edge");
+        let mut e = Edge::new(endpoint("source")?, endpoint("target")?, &escape_label(data.get("label").map(String::as_str).unwrap_or_default()));
+        e.label_url = data.get("labelURL").cloned().unwrap_or_default();
+        e.url = data.get("URL").cloned().unwrap_or_default();
+        e.style = style(&data);
+        e.color = data.get("color").cloned();
+        e.penwidth = data.get("penwidth").and_then(|p| p.parse().ok());
+        e.lhead = data.get("lhead").cloned();
+        e.ltail = data.get("ltail").cloned();
+        if let Some(arrow) = data.get("arrowhead").and_then(|a| Arrow::from_dot_string(a)) {
+            e.end_arrow = arrow;
+        }
+        if let Some(arrow) = data.get("arrowtail").and_then(|a| Arrow::from_dot_string(a)) {
+            e.start_arrow = arrow;
+        }
+        Ok(e)
+    }
+}
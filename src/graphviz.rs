@@ -0,0 +1,226 @@
+//! Rendering with the Graphviz executables, enabled by the `graphviz`
+//! feature.
+//!
+//! `Graph::render_to` pipes the DOT of a graph to an installed layout
+//! engine and returns what it wrote. The process is started by a `Runner`;
+//! `Executable` runs the real programs, tests can put their own `Runner` in
+//! its place with `Graph::render_with`.
+//!
+//! ```rust,no_run
+//! use dot_graph::{Graph, Kind, Edge, graphviz::{Engine, Format}};
+//!
+//! let mut graph = Graph::new("G", Kind::Digraph);
+//! graph.add_edge(Edge::new("a", "b", ""));
+//! let rendered = graph.render_to(Format::Svg, Engine::Dot).unwrap();
+//! std::fs::write("graph.svg", rendered.output).unwrap();
+//! ```
+
+use std::{
+    io::{self, Read, Write},
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::graph::Graph;
+
+/// The layout engines of Graphviz.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Engine {
+    Dot,
+    Neato,
+    Fdp,
+    Sfdp,
+    Circo,
+    Twopi,
+}
+
+impl Engine {
+    /// The name of the executable, which is also the value of `-K`.
+    pub fn as_slice(self) -> &'static str {
+        match self {
+            Engine::Dot => "dot",
+            Engine::Neato => "neato",
+            Engine::Fdp => "fdp",
+            Engine::Sfdp => "sfdp",
+            Engine::Circo => "circo",
+            Engine::Twopi => "twopi",
+        }
+    }
+}
+
+/// The output formats, passed as `-T`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Format {
+    Svg,
+    Png,
+    Pdf,
+    /// The JSON of `-Tjson`, which `Graph::from_graphviz_json` reads.
+    Json,
+    /// The positions read by `Graph::layout_from_plain`.
+    Plain,
+    /// DOT with positions and drawing operations, read by
+    /// `Graph::layout_from_xdot`.
+    Xdot,
+}
+
+impl Format {
+    pub fn as_slice(self) -> &'static str {
+        match self {
+            Format::Svg => "svg",
+            Format::Png => "png",
+            Format::Pdf => "pdf",
+            Format::Json => "json",
+            Format::Plain => "plain",
+            Format::Xdot => "xdot",
+        }
+    }
+}
+
+/// What a layout engine wrote.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Rendered {
+    /// The document in the requested format.
+    pub output: Vec<u8>,
+    /// Warnings written to stderr by a successful run.
+    pub stderr: String,
+}
+
+/// Runs a layout engine on a DOT document.
+pub trait Runner {
+    fn run(&self, engine: Engine, format: Format, dot: &[u8]) -> io::Result<Rendered>;
+}
+
+/// Runs the Graphviz executables found on the `PATH`, or `program`.
+///
+/// A run fails with `ErrorKind::NotFound` if the executable is missing,
+/// with `ErrorKind::TimedOut` when it takes longer than the timeout (the
+/// process is killed then, and the message ends with what it wrote to
+/// stderr so far), and with `ErrorKind::Other` holding the stderr of the
+/// engine when it exits with an error.
+#[derive(Clone, Debug, Default)]
+pub struct Executable {
+    program: Option<PathBuf>,
+    timeout: Option<Duration>,
+}
+
+impl Executable {
+    pub fn new() -> Self {
+        Executable::default()
+    }
+
+    /// Runs `program` with `-K` for the engine instead of the executable
+    /// named after the engine, e.g. a `dot` outside of the `PATH`.
+    pub fn program(&self, program: &str) -> Self {
+        let mut exe = self.clone();
+        exe.program = Some(PathBuf::from(program));
+        exe
+    }
+
+    /// Kills the engine if it has not finished after `timeout`.
+    pub fn timeout(&self, timeout: Duration) -> Self {
+        let mut exe = self.clone();
+        exe.timeout = Some(timeout);
+        exe
+    }
+}
+
+impl Runner for Executable {
+    fn run(&self, engine: Engine, format: Format, dot: &[u8]) -> io::Result<Rendered> {
+        let mut command = match &self.program {
+            Some(program) => {
+                let mut command = Command::new(program);
+                command.arg(format!("-K{}", engine.as_slice()));
+                command
+            }
+            None => Command::new(engine.as_slice()),
+        };
+        let mut child = command
+            .arg(format!("-T{}", format.as_slice()))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // feed and drain the pipes on their own threads, so a large graph
+        // cannot block the engine
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let input = dot.to_vec();
+        let writer = thread::spawn(move || stdin.write_all(&input));
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let reader = thread::spawn(move || {
+            let mut out = vec![];
+            stdout.read_to_end(&mut out).map(|_| out)
+        });
+        // stderr is shared as it comes, to tell what the engine was doing
+        // when it timed out
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let collected = Arc::new(Mutex::new(vec![]));
+        let errors = {
+            let collected = Arc::clone(&collected);
+            thread::spawn(move || {
+                let mut buffer = [0; 4096];
+                loop {
+                    match stderr.read(&mut buffer) {
+                        Ok(0) => return Ok(()),
+                        Ok(n) => collected.lock().unwrap_or_else(PoisonError::into_inner).extend_from_slice(&buffer[..n]),
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+            })
+        };
+        let collected = move || String::from_utf8_lossy(&collected.lock().unwrap_or_else(PoisonError::into_inner)).into_owned();
+
+        let status = match self.timeout {
+            None => child.wait()?,
+            Some(timeout) => {
+                let started = Instant::now();
+                loop {
+                    if let Some(status) = child.try_wait()? {
+                        break status;
+                    }
+                    if started.elapsed() >= timeout {
+                        child.kill()?;
+                        child.wait()?;
+                        let mut message = format!("{} did not finish within {:?}", engine.as_slice(), timeout);
+                        let stderr = collected();
+                        if !stderr.trim_end().is_empty() {
+                            message = format!("{}: {}", message, stderr.trim_end());
+                        }
+                        return Err(io::Error::new(io::ErrorKind::TimedOut, message));
+                    }
+                    thread::sleep(Duration::from_millis(5));
+                }
+            }
+        };
+
+        let joined = |r: thread::Result<io::Result<Vec<u8>>>| r.unwrap_or_else(|_| Err(io::Error::other("pipe thread panicked")));
+        // the engine may exit before reading everything, e.g. on a syntax
+        // error; its status tells more than the broken pipe
+        let written = writer.join().unwrap_or_else(|_| Err(io::Error::other("pipe thread panicked")));
+        let output = joined(reader.join())?;
+        errors.join().unwrap_or_else(|_| Err(io::Error::other("pipe thread panicked")))?;
+        let stderr = collected();
+        if !status.success() {
+            return Err(io::Error::other(format!("{} failed ({}): {}", engine.as_slice(), status, stderr.trim_end())));
+        }
+        written?;
+        Ok(Rendered { output, stderr })
+    }
+}
+
+impl Graph {
+    /// Renders the graph with the Graphviz executable of `engine` from the
+    /// `PATH`, see `Executable`.
+    pub fn render_to(&self, format: Format, engine: Engine) -> io::Result<Rendered> {
+        self.render_with(&Executable::new(), format, engine)
+    }
+
+    /// Renders the graph with `runner`, e.g. an `Executable` with a timeout.
+    pub fn render_with<R: Runner>(&self, runner: &R, format: Format, engine: Engine) -> io::Result<Rendered> {
+        runner.run(engine, format, self.to_dot_string()?.as_bytes())
+    }
+}
@@ -0,0 +1,300 @@
+//! Reading and writing the `json0` format of Graphviz (`dot -Tjson0`).
+//!
+//! Objects are numbered by `_gvid`: the subgraphs first, then the nodes.
+//! Edges have their own numbering and refer to nodes by `tail`/`head`.
+//! Attributes are plain string members of each object, where Graphviz
+//! unescapes `\"` but keeps the other escapes of DOT, like `\n`.
+
+use std::collections::HashMap;
+use std::io;
+
+use crate::{
+    arrow::Arrow,
+    edge::Edge,
+    graph::{Graph, Kind},
+    json::Json,
+    node::{self, Node},
+    style::Style,
+    subgraph::{self, Subgraph},
+    utils::{escape_quotes, number, unescape_quotes},
+};
+
+impl Graph {
+    /// Renders the graph in the Graphviz `json0` schema, the one produced by
+    /// `dot -Tjson0`, without attributes computed by a layout.
+    ///
+    /// Nodes only referenced by edges get an object of their own, and edge
+    /// chains or edges to subgraphs are split into one edge per node pair.
+    pub fn to_graphviz_json(&self) -> String {
+        let names = self.node_names();
+        let subgraph_cnt = self.subgraph.len();
+        let gvid: HashMap<&str, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.as_str(), subgraph_cnt + i))
+            .collect();
+        let declared: HashMap<&str, &Node> = self
+            .all_nodes()
+            .into_iter()
+            .map(|n| (n.name.as_str(), n))
+            .collect();
+
+        // edges of subgraphs come first, so their ids are known while
+        // writing the subgraph objects
+        let mut edges = vec![];
+        let mut subgraph_edges: Vec<Vec<Json>> = vec![];
+        for s in self.subgraph.iter() {
+            let mut ids = vec![];
+            for e in s.edges.iter() {
+                for (from, to) in e.node_pairs() {
+                    ids.push(Json::Number(edges.len() as f64));
+                    edges.push(edge_object(edges.len(), gvid[from.as_str()], gvid[to.as_str()], e));
+                }
+            }
+            subgraph_edges.push(ids);
+        }
+        for e in self.edges.iter() {
+            for (from, to) in e.node_pairs() {
+                edges.push(edge_object(edges.len(), gvid[from.as_str()], gvid[to.as_str()], e));
+            }
+        }
+
+        let mut objects = vec![];
+        for (i, s) in self.subgraph.iter().enumerate() {
+            let mut members = vec![
+                (String::from("_gvid"), Json::Number(i as f64)),
+                (String::from("name"), Json::str(&s.name)),
+                (String::from("label"), text(&s.label)),
+            ];
+            push_attributes(&mut members, s.style, &s.color, &None, &s.url);
+            if !subgraph_edges[i].is_empty() {
+                members.push((String::from("edges"), Json::Array(subgraph_edges[i].clone())));
+            }
+            if !s.nodes.is_empty() {
+                let ids = s.nodes.iter().map(|n| Json::Number(gvid[n.name.as_str()] as f64)).collect();
+                members.push((String::from("nodes"), Json::Array(ids)));
+            }
+            objects.push(Json::Object(members));
+        }
+        for name in names.iter() {
+            let mut members = vec![
+                (String::from("_gvid"), Json::Number(gvid[name.as_str()] as f64)),
+                (String::from("name"), Json::str(name)),
+            ];
+            match declared.get(name.as_str()) {
+                Some(n) => {
+                    members.push((String::from("label"), text(&n.label)));
+                    push_attributes(&mut members, n.style, &n.color, &n.shape, &n.url);
+                }
+                None => members.push((String::from("label"), Json::str(name))),
+            }
+            objects.push(Json::Object(members));
+        }
+
+        let mut members = vec![
+            (String::from("name"), Json::str(&self.name)),
+            (String::from("directed"), Json::Bool(self.kind == Kind::Digraph)),
+            (String::from("strict"), Json::Bool(false)),
+        ];
+        if !self.url.is_empty() {
+            members.push((String::from("URL"), text(&self.url)));
+        }
+        if self.compound {
+            members.push((String::from("compound"), Json::str("true")));
+        }
+        members.push((String::from("_subgraph_cnt"), Json::Number(subgraph_cnt as f64)));
+        if !objects.is_empty() {
+            members.push((String::from("objects"), Json::Array(objects)));
+        }
+        if !edges.is_empty() {
+            members.push((String::from("edges"), Json::Array(edges)));
+        }
+        Json::Object(members).to_pretty_string()
+    }
+
+    /// Reads a graph in the Graphviz `json0` schema.
+    ///
+    /// Subgraphs whose name is not a valid `Subgraph` name (e.g. not
+    /// starting with `cluster_`) are dropped and their nodes and edges
+    /// moved to the graph. Layout attributes like `pos` are ignored.
+    pub fn from_graphviz_json(json: &str) -> io::Result<Graph> {
+        let root = Json::parse(json)?;
+        let name = root.get("name").and_then(Json::as_str).ok_or_else(|| invalid("missing graph name"))?;
+        let kind = match root.get("directed").and_then(Json::as_bool) {
+            Some(false) => Kind::Graph,
+            _ => Kind::Digraph,
+        };
+        let mut graph = Graph::new(name, kind);
+        if let Some(url) = attribute(&root, "URL") {
+            graph.url = url;
+        }
+        graph.compound = attribute(&root, "compound").as_deref() == Some("true");
+
+        let subgraph_cnt = root.get("_subgraph_cnt").and_then(Json::as_usize).unwrap_or(0);
+        let empty = vec![];
+        let objects = root.get("objects").and_then(Json::as_array).unwrap_or(&empty);
+        let edges = root.get("edges").and_then(Json::as_array).unwrap_or(&empty);
+
+        let mut names: HashMap<usize, String> = HashMap::new();
+        let mut nodes: Vec<(usize, Node)> = vec![];
+        for (i, o) in objects.iter().enumerate().skip(subgraph_cnt) {
+            let id = o.get("_gvid").and_then(Json::as_usize).unwrap_or(i);
+            let name = o.get("name").and_then(Json::as_str).ok_or_else(|| invalid("missing node name"))?;
+            if !node::is_valid_name(name) {
+                return Err(invalid(&format!("invalid node name {:?}", name)));
+            }
+            let mut n = Node::new(name);
+            if let Some(label) = attribute(o, "label") {
+                n.label = if label == "\\N" { String::from(name) } else { label };
+            }
+            n.style = style_attribute(o);
+            n.color = attribute(o, "color");
+            n.shape = attribute(o, "shape");
+            n.url = attribute(o, "URL").unwrap_or_default();
+            names.insert(id, String::from(name));
+            nodes.push((id, n));
+        }
+
+        let mut read_edges: Vec<(usize, Edge)> = vec![];
+        for (i, e) in edges.iter().enumerate() {
+            let id = e.get("_gvid").and_then(Json::as_usize).unwrap_or(i);
+            let end = |key: &str| {
+                e.get(key)
+                    .and_then(Json::as_usize)
+                    .and_then(|gvid| names.get(&gvid))
+                    .ok_or_else(|| invalid(&format!("edge {} has no valid {}", id, key)))
+            };
+            let mut edge = Edge::new(end("tail")?, end("head")?, &attribute(e, "label").unwrap_or_default());
+            edge.label_url = attribute(e, "labelURL").unwrap_or_default();
+            edge.url = attribute(e, "URL").unwrap_or_default();
+            edge.style = style_attribute(e);
+            edge.color = attribute(e, "color");
+            edge.penwidth = attribute(e, "penwidth").and_then(|p| p.parse().ok());
+            edge.lhead = attribute(e, "lhead");
+            edge.ltail = attribute(e, "ltail");
+            if let Some(arrow) = attribute(e, "arrowhead").and_then(|a| Arrow::from_dot_string(&a)) {
+                edge.end_arrow = arrow;
+            }
+            if let Some(arrow) = attribute(e, "arrowtail").and_then(|a| Arrow::from_dot_string(&a)) {
+                edge.start_arrow = arrow;
+            }
+            read_edges.push((id, edge));
+        }
+
+        // every node and edge goes to the first cluster listing it
+        let mut subgraphs: Vec<Subgraph> = vec![];
+        for o in objects.iter().take(subgraph_cnt) {
+            let name = o.get("name").and_then(Json::as_str).unwrap_or_default();
+            if !subgraph::is_valid_name(name) {
+                continue;
+            }
+            let mut s = Subgraph::new(name);
+            s.label = attribute(o, "label").unwrap_or_default();
+            s.style = style_attribute(o);
+            s.color = attribute(o, "color");
+            s.url = attribute(o, "URL").unwrap_or_default();
+            let ids = |key: &str| -> Vec<usize> {
+                o.get(key)
+                    .and_then(Json::as_array)
+                    .map(|a| a.iter().filter_map(Json::as_usize).collect())
+                    .unwrap_or_default()
+            };
+            for id in ids("nodes") {
+                if let Some(pos) = nodes.iter().position(|(i, _)| *i == id) {
+                    s.add_node(nodes.remove(pos).1);
+                }
+            }
+            for id in ids("edges") {
+                if let Some(pos) = read_edges.iter().position(|(i, _)| *i == id) {
+                    s.add_edge(read_edges.remove(pos).1);
+                }
+            }
+            subgraphs.push(s);
+        }
+
+        for s in subgraphs {
+            graph.add_subgraph(s);
+        }
+        for (_, n) in nodes {
+            graph.add_node(n);
+        }
+        for (_, e) in read_edges {
+            graph.add_edge(e);
+        }
+        Ok(graph)
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// A string attribute of a json0 object.
+/// An attribute, escaped again as the model holds it.
+fn attribute(object: &Json, key: &str) -> Option<String> {
+    object.get(key).and_then(Json::as_str).map(escape_quotes)
+}
+
+/// Text of the model as Graphviz holds it.
+fn text(value: &str) -> Json {
+    Json::str(&unescape_quotes(value))
+}
+
+fn style_attribute(object: &Json) -> Style {
+    attribute(object, "style")
+        .and_then(|s| Style::from_slice(&s))
+        .unwrap_or(Style::None)
+}
+
+fn push_attributes(
+    members: &mut Vec<(String, Json)>,
+    style: Style,
+    color: &Option<String>,
+    shape: &Option<String>,
+    url: &str,
+) {
+    if style != Style::None {
+        members.push((String::from("style"), Json::str(style.as_slice())));
+    }
+    if let Some(c) = color {
+        members.push((String::from("color"), Json::str(c)));
+    }
+    if let Some(s) = shape {
+        members.push((String::from("shape"), Json::str(s)));
+    }
+    if !url.is_empty() {
+        members.push((String::from("URL"), text(url)));
+    }
+}
+
+fn edge_object(gvid: usize, tail: usize, head: usize, edge: &Edge) -> Json {
+    let mut members = vec![
+        (String::from("_gvid"), Json::Number(gvid as f64)),
+        (String::from("tail"), Json::Number(tail as f64)),
+        (String::from("head"), Json::Number(head as f64)),
+        (String::from("label"), text(&edge.label)),
+    ];
+    if !edge.label_url.is_empty() {
+        members.push((String::from("labelURL"), text(&edge.label_url)));
+    }
+    push_attributes(&mut members, edge.style, &edge.color, &None, &edge.url);
+    if let Some(p) = edge.penwidth {
+        members.push((String::from("penwidth"), Json::str(&number(p))));
+    }
+    if let Some(l) = &edge.lhead {
+        members.push((String::from("lhead"), Json::str(l)));
+    }
+    if let Some(l) = &edge.ltail {
+        members.push((String::from("ltail"), Json::str(l)));
+    }
+    if !edge.end_arrow.is_default() {
+        members.push((String::from("arrowhead"), Json::str(&edge.end_arrow.to_dot_string())));
+    }
+    if !edge.start_arrow.is_default() {
+        members.push((String::from("arrowtail"), Json::str(&edge.start_arrow.to_dot_string())));
+    }
+    if !edge.start_arrow.is_default() && !edge.end_arrow.is_default() {
+        members.push((String::from("dir"), Json::str("both")));
+    }
+    Json::Object(members)
+}
@@ -0,0 +1,200 @@
+//! Showing the results of the `algo` module on the graph itself.
+//!
+//! ```rust
+//! use dot_graph::{algo, highlight::Highlight, Graph, Kind, Edge};
+//!
+//! let mut graph = Graph::new("G", Kind::Digraph);
+//! graph.add_edge(Edge::chain(&["a", "b", "c"], ""));
+//! graph.add_edge(Edge::new("a", "c", ""));
+//! let path = algo::shortest_path(&graph, "a", "c").unwrap();
+//! graph.highlight_path(&path, &Highlight::default());
+//! assert_eq!(graph.to_dot_string().unwrap(),
+//! r#"digraph G {
+//!     "a"[label="a"][style="bold"][color="red"];
+//!     "c"[label="c"][style="bold"][color="red"];
+//!     "a" -> "b" -> "c"[label=""];
+//!     "a" -> "c"[label=""][style="bold"][color="red"];
+//! }
+//! "#);
+//! ```
+
+use std::collections::HashSet;
+
+use crate::{
+    algo::Adjacency,
+    edge::Edge,
+    graph::{Graph, Kind},
+    node::Node,
+    style::Style,
+    subgraph::Subgraph,
+};
+
+/// How highlighted nodes and edges look, bold and red by default.
+#[derive(Clone, Debug)]
+pub struct Highlight {
+    /// Not set when `Style::None`.
+    pub style: Style,
+    /// Not set when `None`.
+    pub color: Option<String>,
+}
+
+impl Default for Highlight {
+    fn default() -> Self {
+        Highlight { style: Style::Bold, color: Some(String::from("red")) }
+    }
+}
+
+impl Highlight {
+    fn node(&self, node: &Node) -> Node {
+        let mut node = node.clone();
+        if self.style != Style::None {
+            node.style = self.style;
+        }
+        if self.color.is_some() {
+            node.color = self.color.clone();
+        }
+        node
+    }
+
+    fn edge(&self, mut edge: Edge) -> Edge {
+        if self.style != Style::None {
+            edge.style = self.style;
+        }
+        if self.color.is_some() {
+            edge.color = self.color.clone();
+        }
+        edge
+    }
+}
+
+impl Graph {
+    /// Highlights the nodes of `ids` and the edges between consecutive ones,
+    /// like a path of `algo::shortest_path`. Nodes only referenced by edges
+    /// are declared; an edge with more node pairs, like `a -> b -> c`, is
+    /// split when only some of them are on the path.
+    pub fn highlight_path<S: AsRef<str>>(&mut self, ids: &[S], highlight: &Highlight) {
+        let ids: Vec<&str> = ids.iter().map(AsRef::as_ref).collect();
+        let steps: HashSet<(&str, &str)> = ids.windows(2).map(|w| (w[0], w[1])).collect();
+        let undirected = self.kind == Kind::Graph;
+        self.highlight(&ids, highlight, |_, from, to| {
+            steps.contains(&(from, to)) || (undirected && steps.contains(&(to, from)))
+        });
+    }
+
+    /// Highlights the nodes and edges on a cycle, see `algo::find_cycle`,
+    /// and returns whether there were any.
+    pub fn highlight_cycles(&mut self, highlight: &Highlight) -> bool {
+        let adjacency = Adjacency::of(self);
+        let on_cycle: Vec<bool> = if self.kind == Kind::Graph {
+            // an edge is on a cycle unless it is a bridge
+            adjacency.bridges().into_iter().map(|bridge| !bridge).collect()
+        } else {
+            let mut component = vec![0; adjacency.names.len()];
+            for (i, members) in adjacency.components().into_iter().enumerate() {
+                for member in members {
+                    component[member] = i;
+                }
+            }
+            self.all_edges()
+                .into_iter()
+                .flat_map(|e| e.node_pairs())
+                .map(|(from, to)| component[adjacency.index[&from]] == component[adjacency.index[&to]])
+                .collect()
+        };
+        let mut nodes = vec![];
+        for (pair, (from, to)) in self.all_edges().into_iter().flat_map(|e| e.node_pairs()).enumerate() {
+            if on_cycle[pair] {
+                nodes.push(from);
+                nodes.push(to);
+            }
+        }
+        let nodes: Vec<&str> = nodes.iter().map(String::as_str).collect();
+        self.highlight(&nodes, highlight, |pair, _, _| on_cycle[pair]);
+        !nodes.is_empty()
+    }
+
+    /// Moves the nodes of each strongly connected component with more than
+    /// one node, see `algo::strongly_connected_components`, to a new cluster
+    /// `cluster_scc_N`, and returns the names of these clusters.
+    ///
+    /// The nodes leave the clusters they were in, with the edges of those
+    /// clusters using them, as a node belongs to one cluster only.
+    pub fn cluster_by_scc(&mut self) -> Vec<String> {
+        let components: Vec<Vec<String>> = crate::algo::strongly_connected_components(self)
+            .into_iter()
+            .filter(|c| c.len() > 1)
+            .collect();
+        let mut names = vec![];
+        let mut number = 0;
+        for members in components {
+            let name = loop {
+                let name = format!("cluster_scc_{}", number);
+                number += 1;
+                if self.subgraph.iter().all(|s| s.name != name) {
+                    break name;
+                }
+            };
+            let moved = |n: &String| members.contains(n);
+            let mut cluster = Subgraph::new(&name);
+            for s in self.subgraph.iter_mut() {
+                let (edges, kept) = s.edges.drain(..).partition(|e: &Edge| e.node_pairs().iter().any(|(f, t)| moved(f) || moved(t)));
+                s.edges = kept;
+                self.edges.extend::<Vec<Edge>>(edges);
+            }
+            let mut declared: Vec<Node> = vec![];
+            for nodes in self.subgraph.iter_mut().map(|s| &mut s.nodes).chain(std::iter::once(&mut self.nodes)) {
+                let (taken, kept) = nodes.drain(..).partition(|n: &Node| moved(&n.name));
+                *nodes = kept;
+                declared.extend::<Vec<Node>>(taken);
+            }
+            for member in members.iter() {
+                match declared.iter().position(|n| &n.name == member) {
+                    Some(i) => cluster.add_node(declared.remove(i)),
+                    None => cluster.add_node(Node::implicit(member)),
+                }
+            }
+            self.add_subgraph(cluster);
+            names.push(name);
+        }
+        names
+    }
+
+    /// Highlights the nodes of `names` and the node pairs of edges `pick`
+    /// accepts, given their index in `Graph::all_edges` order.
+    fn highlight<F: Fn(usize, &str, &str) -> bool>(&mut self, names: &[&str], highlight: &Highlight, pick: F) {
+        let mut pair = 0;
+        for edges in self.subgraph.iter_mut().map(|s| &mut s.edges).chain(std::iter::once(&mut self.edges)) {
+            let mut painted = vec![];
+            for edge in edges.drain(..) {
+                let pairs = edge.node_pairs();
+                let picked: Vec<bool> = pairs.iter().enumerate().map(|(i, (from, to))| pick(pair + i, from, to)).collect();
+                pair += pairs.len();
+                if picked.iter().all(|p| *p) {
+                    painted.push(highlight.edge(edge));
+                } else if !picked.iter().any(|p| *p) {
+                    painted.push(edge);
+                } else {
+                    for ((from, to), picked) in pairs.iter().zip(picked) {
+                        let part = edge.with_endpoints(from, to);
+                        painted.push(if picked { highlight.edge(part) } else { part });
+                    }
+                }
+            }
+            *edges = painted;
+        }
+
+        let mut declared = HashSet::new();
+        for nodes in self.subgraph.iter_mut().map(|s| &mut s.nodes).chain(std::iter::once(&mut self.nodes)) {
+            for node in nodes.iter_mut().filter(|n| names.contains(&n.name.as_str())) {
+                *node = highlight.node(node);
+                declared.insert(node.name.clone());
+            }
+        }
+        let known = self.node_names();
+        for name in names {
+            if known.iter().any(|n| n == name) && declared.insert(String::from(*name)) {
+                self.nodes.push(highlight.node(&Node::implicit(name)));
+            }
+        }
+    }
+}
@@ -0,0 +1,406 @@
+//! Positioning graphs without Graphviz.
+//!
+//! `Graph::layered_layout` places the nodes of a directed graph on ranks
+//! like `dot` does, `Graph::force_layout` spreads out undirected networks
+//! like `neato` or `fdp`. Both give a `LayoutResult`; all coordinates are
+//! in points with the origin in the top left corner and `y` growing
+//! downwards, positions are centers.
+//!
+//! ```rust
+//! use dot_graph::{Graph, Kind, Edge, layout::LayoutOptions};
+//!
+//! let mut graph = Graph::new("G", Kind::Digraph);
+//! graph.add_edge(Edge::new("a", "b", ""));
+//! graph.add_edge(Edge::new("a", "c", ""));
+//!
+//! let layout = graph.layered_layout(&LayoutOptions::default());
+//! let (a, b, c) = (layout.node("a").unwrap(), layout.node("b").unwrap(), layout.node("c").unwrap());
+//! assert!(a.y < b.y && b.y == c.y);
+//! assert!(b.x < a.x && a.x < c.x);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{graph::Graph, node::Node};
+
+mod layered;
+mod force;
+
+/// The space around the contents of a cluster.
+const CLUSTER_MARGIN: f64 = 8.0;
+/// The height of a cluster label.
+const CLUSTER_LABEL: f64 = 18.0;
+/// The space around the whole drawing.
+const MARGIN: f64 = 4.0;
+
+/// A point, in points.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Point {
+        Point { x, y }
+    }
+}
+
+/// The position and size of a node.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeLayout {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// The path of an edge between two nodes; edge chains and edges to
+/// subgraphs have one `EdgeLayout` per node pair.
+#[derive(Clone, PartialEq, Debug)]
+pub struct EdgeLayout {
+    /// The index of the edge in `Graph::all_edges`.
+    pub edge: usize,
+    pub from: String,
+    pub to: String,
+    /// The path from `from` to `to`, starting and ending on the node
+    /// outlines. With `spline` these are the control points of cubic
+    /// Bézier curves: the start and then three points per curve.
+    pub points: Vec<Point>,
+    pub spline: bool,
+    /// Where the label goes, if the edge has one.
+    pub label_pos: Option<Point>,
+}
+
+/// The bounding box of a cluster, including its label.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ClusterLayout {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// The positions computed by a layout engine.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LayoutResult {
+    pub width: f64,
+    pub height: f64,
+    pub nodes: Vec<NodeLayout>,
+    pub edges: Vec<EdgeLayout>,
+    pub clusters: Vec<ClusterLayout>,
+}
+
+impl LayoutResult {
+    pub fn node(&self, name: &str) -> Option<&NodeLayout> {
+        self.nodes.iter().find(|n| n.name == name)
+    }
+
+    pub fn cluster(&self, name: &str) -> Option<&ClusterLayout> {
+        self.clusters.iter().find(|c| c.name == name)
+    }
+}
+
+/// Settings of `Graph::layered_layout`, the defaults match the ones of
+/// `dot`.
+#[derive(Clone, Debug)]
+pub struct LayoutOptions {
+    /// The minimal space between two nodes of a rank (`nodesep`).
+    pub node_sep: f64,
+    /// The minimal space between two ranks (`ranksep`).
+    pub rank_sep: f64,
+    /// Route edges as splines instead of polylines.
+    pub splines: bool,
+    /// Sweeps of the crossing minimization.
+    pub iterations: usize,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        LayoutOptions { node_sep: 18.0, rank_sep: 36.0, splines: true, iterations: 24 }
+    }
+}
+
+/// Settings of `Graph::force_layout`.
+#[derive(Clone, Debug)]
+pub struct ForceOptions {
+    /// The preferred distance between the centers of connected nodes
+    /// (`len` of `neato`).
+    pub edge_length: f64,
+    /// The upper limit of iterations, fewer are run once nodes stop moving.
+    pub iterations: usize,
+    /// The seed of the initial positions, the same seed gives the same
+    /// layout.
+    pub seed: u64,
+    /// Moves nodes apart until they do not overlap (`overlap=false`).
+    pub remove_overlap: bool,
+    /// The space left between nodes when removing overlaps.
+    pub node_sep: f64,
+    /// Route edges as splines instead of straight lines.
+    pub splines: bool,
+}
+
+impl Default for ForceOptions {
+    fn default() -> Self {
+        ForceOptions { edge_length: 72.0, iterations: 500, seed: 1, remove_overlap: true, node_sep: 18.0, splines: false }
+    }
+}
+
+impl Graph {
+    /// Computes a layered layout, the way `dot` lays out directed graphs.
+    ///
+    /// Cycles are broken by reversing edges found by a depth-first search,
+    /// nodes are ranked by longest path and pulled down to their
+    /// successors, long edges get virtual nodes, crossings are reduced by
+    /// barycenter sweeps and nodes are placed as close as possible to
+    /// their neighbors. The members of a cluster are kept together and
+    /// other nodes are kept out of its box, nodes listed in `same_rank`
+    /// share a rank. In undirected graphs edges point from the first node
+    /// to the second.
+    pub fn layered_layout(&self, options: &LayoutOptions) -> LayoutResult {
+        layered::layout(self, options)
+    }
+
+    /// Computes a force-directed layout, for undirected graphs where ranks
+    /// mean nothing.
+    ///
+    /// Nodes start at places drawn from `seed` and are moved by the
+    /// Fruchterman-Reingold forces: all nodes repel each other, edges and
+    /// clusters pull their nodes together and a weak pull to the center
+    /// keeps unconnected parts close. Overlapping nodes are pushed apart
+    /// afterwards. Edges are straight lines; cluster boxes are computed
+    /// around their members, but other nodes are not kept out of them.
+    pub fn force_layout(&self, options: &ForceOptions) -> LayoutResult {
+        force::layout(self, options)
+    }
+}
+
+/// A node to place.
+struct Shape {
+    name: String,
+    width: f64,
+    height: f64,
+    boxed: bool,
+    /// The first subgraph the node is declared in.
+    cluster: Option<usize>,
+}
+
+/// The nodes of `graph` in the order of `Graph::node_names`, and the edges
+/// between them as the index in `Graph::all_edges` and two node indices.
+fn shapes(graph: &Graph) -> (Vec<Shape>, Vec<(usize, usize, usize)>) {
+    let names = graph.node_names();
+    let index: HashMap<&str, usize> = names.iter().enumerate().map(|(i, n)| (n.as_str(), i)).collect();
+    let declared: HashMap<&str, &Node> = graph.all_nodes().into_iter().map(|n| (n.name.as_str(), n)).collect();
+
+    let mut shapes: Vec<Shape> = names
+        .iter()
+        .map(|name| {
+            let (label, shape) = match declared.get(name.as_str()) {
+                Some(n) => (n.label.as_str(), n.shape.as_deref()),
+                None => (name.as_str(), None),
+            };
+            let (width, height) = node_size(label, shape);
+            Shape { name: name.clone(), width, height, boxed: is_boxed(shape), cluster: None }
+        })
+        .collect();
+    for (c, s) in graph.subgraph.iter().enumerate() {
+        for n in s.nodes.iter() {
+            let shape = &mut shapes[index[n.name.as_str()]];
+            if shape.cluster.is_none() {
+                shape.cluster = Some(c);
+            }
+        }
+    }
+
+    let mut pairs = vec![];
+    for (i, e) in graph.all_edges().iter().enumerate() {
+        for (from, to) in e.node_pairs() {
+            pairs.push((i, index[from.as_str()], index[to.as_str()]));
+        }
+    }
+    (shapes, pairs)
+}
+
+/// The boxes of the clusters of `graph` around their `members`, given as
+/// cluster, center and size.
+fn cluster_boxes(graph: &Graph, members: &[(Option<usize>, Point, f64, f64)]) -> Vec<ClusterLayout> {
+    let mut clusters = vec![];
+    for (c, s) in graph.subgraph.iter().enumerate() {
+        let (mut left, mut right, mut top, mut bottom) = (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+        for (_, p, w, h) in members.iter().filter(|m| m.0 == Some(c)) {
+            left = left.min(p.x - w / 2.0);
+            right = right.max(p.x + w / 2.0);
+            top = top.min(p.y - h / 2.0);
+            bottom = bottom.max(p.y + h / 2.0);
+        }
+        if left == f64::INFINITY {
+            continue;
+        }
+        let label = if s.label.is_empty() { 0.0 } else { CLUSTER_LABEL };
+        let (left, right) = (left - CLUSTER_MARGIN, right + CLUSTER_MARGIN);
+        let (top, bottom) = (top - CLUSTER_MARGIN - label, bottom + CLUSTER_MARGIN);
+        clusters.push(ClusterLayout {
+            name: s.name.clone(),
+            x: (left + right) / 2.0,
+            y: (top + bottom) / 2.0,
+            width: right - left,
+            height: bottom - top,
+        });
+    }
+    clusters
+}
+
+/// The translation moving the top left corner of `boxes` (centers and
+/// sizes) to the margin.
+fn offset(boxes: impl Iterator<Item = (Point, f64, f64)>) -> Point {
+    let (mut left, mut top) = (f64::INFINITY, f64::INFINITY);
+    for (p, w, h) in boxes {
+        left = left.min(p.x - w / 2.0);
+        top = top.min(p.y - h / 2.0);
+    }
+    if left == f64::INFINITY {
+        return Point::new(0.0, 0.0);
+    }
+    Point::new(MARGIN - left, MARGIN - top)
+}
+
+/// A path from the right side of a node back to it, reaching `reach`
+/// points out.
+fn loop_points(center: Point, width: f64, height: f64, boxed: bool, reach: f64) -> Vec<Point> {
+    let side = center.x + width / 2.0;
+    vec![
+        clip(center, width, height, boxed, Point::new(side, center.y - height / 2.0)),
+        Point::new(side + reach, center.y),
+        clip(center, width, height, boxed, Point::new(side, center.y + height / 2.0)),
+    ]
+}
+
+/// Puts the result together from placed nodes and clusters and edge
+/// paths, given as edge index, node indices and polyline.
+fn finish(
+    graph: &Graph,
+    nodes: Vec<NodeLayout>,
+    clusters: Vec<ClusterLayout>,
+    paths: Vec<(usize, usize, usize, Vec<Point>)>,
+    splines: bool,
+) -> LayoutResult {
+    let all_edges = graph.all_edges();
+    let edges = paths
+        .into_iter()
+        .map(|(edge, from, to, points)| EdgeLayout {
+            edge,
+            from: nodes[from].name.clone(),
+            to: nodes[to].name.clone(),
+            label_pos: if all_edges[edge].label.is_empty() { None } else { Some(midpoint(&points)) },
+            points: if splines { spline(&points) } else { points },
+            spline: splines,
+        })
+        .collect();
+    let (mut width, mut height) = (0.0, 0.0);
+    let boxes = nodes.iter().map(|n| (n.x, n.y, n.width, n.height)).chain(clusters.iter().map(|c| (c.x, c.y, c.width, c.height)));
+    for (x, y, w, h) in boxes {
+        width = f64::max(width, x + w / 2.0 + MARGIN);
+        height = f64::max(height, y + h / 2.0 + MARGIN);
+    }
+    LayoutResult { width, height, nodes, edges, clusters }
+}
+
+/// The size of a node with `label` and `shape`, estimated the way
+/// Graphviz sizes a 14pt label with its default margins.
+pub(crate) fn node_size(label: &str, shape: Option<&str>) -> (f64, f64) {
+    let lines = label_lines(label);
+    let chars = lines.iter().map(|(l, _)| l.chars().count()).max().unwrap_or(0);
+    let width = (chars as f64 * 7.0 + 16.0).max(54.0);
+    let height = (lines.len() as f64 * 18.0 + 8.0).max(36.0);
+    match shape {
+        Some("point") => (3.6, 3.6),
+        Some("circle" | "doublecircle" | "square" | "Msquare") => (width.max(height), width.max(height)),
+        _ => (width, height),
+    }
+}
+
+/// The lines of a label, split at the escapes `\n` (centered), `\l`
+/// (left aligned) and `\r` (right aligned) and each with the letter of its
+/// escape. `\"` and `\\` are unescaped.
+pub(crate) fn label_lines(label: &str) -> Vec<(String, char)> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    let mut chars = label.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            line.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(e @ ('n' | 'l' | 'r')) => lines.push((std::mem::take(&mut line), e)),
+            Some(e) => line.push(e),
+            None => line.push(c),
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push((line, 'n'));
+    }
+    lines
+}
+
+/// Whether Graphviz draws `shape` as a box rather than an ellipse.
+pub(crate) fn is_boxed(shape: Option<&str>) -> bool {
+    matches!(
+        shape,
+        Some("box" | "rect" | "rectangle" | "square" | "plaintext" | "plain" | "none" | "note" | "tab"
+            | "folder" | "component" | "Msquare" | "record" | "Mrecord" | "cylinder" | "box3d")
+    )
+}
+
+/// The point where the line from the center of a node towards `toward`
+/// leaves its outline.
+pub(crate) fn clip(center: Point, width: f64, height: f64, boxed: bool, toward: Point) -> Point {
+    let (dx, dy) = (toward.x - center.x, toward.y - center.y);
+    if dx == 0.0 && dy == 0.0 {
+        return center;
+    }
+    let (a, b) = (width / 2.0, height / 2.0);
+    let t = if boxed {
+        let tx = if dx == 0.0 { f64::INFINITY } else { a / dx.abs() };
+        let ty = if dy == 0.0 { f64::INFINITY } else { b / dy.abs() };
+        tx.min(ty)
+    } else {
+        1.0 / ((dx / a).powi(2) + (dy / b).powi(2)).sqrt()
+    };
+    let t = t.min(1.0);
+    Point::new(center.x + dx * t, center.y + dy * t)
+}
+
+/// Cubic Bézier control points through all of `points`, Catmull-Rom style.
+pub(crate) fn spline(points: &[Point]) -> Vec<Point> {
+    if points.len() < 2 {
+        return points.to_vec();
+    }
+    let at = |i: isize| points[i.clamp(0, points.len() as isize - 1) as usize];
+    let mut out = vec![points[0]];
+    for i in 0..points.len() as isize - 1 {
+        let (p0, p1, p2, p3) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+        out.push(Point::new(p1.x + (p2.x - p0.x) / 6.0, p1.y + (p2.y - p0.y) / 6.0));
+        out.push(Point::new(p2.x - (p3.x - p1.x) / 6.0, p2.y - (p3.y - p1.y) / 6.0));
+        out.push(p2);
+    }
+    out
+}
+
+/// The point halfway along a polyline.
+pub(crate) fn midpoint(points: &[Point]) -> Point {
+    let length = |a: Point, b: Point| ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt();
+    let total: f64 = points.windows(2).map(|w| length(w[0], w[1])).sum();
+    let mut rest = total / 2.0;
+    for w in points.windows(2) {
+        let l = length(w[0], w[1]);
+        if l >= rest && l > 0.0 {
+            let t = rest / l;
+            return Point::new(w[0].x + (w[1].x - w[0].x) * t, w[0].y + (w[1].y - w[0].y) * t);
+        }
+        rest -= l;
+    }
+    points[0]
+}
@@ -0,0 +1,191 @@
+//! The force-directed engine behind `Graph::force_layout`.
+
+use super::{clip, cluster_boxes, finish, loop_points, offset, shapes, ForceOptions, LayoutResult, NodeLayout, Point};
+use crate::graph::Graph;
+
+/// How strongly the members of a cluster are pulled to its center,
+/// relative to an edge.
+const CLUSTER_PULL: f64 = 0.5;
+/// How strongly all nodes are pulled to the center of the drawing, which
+/// keeps unconnected parts close.
+const GRAVITY: f64 = 0.3;
+/// Rounds of overlap removal.
+const OVERLAP_ROUNDS: usize = 500;
+
+pub(super) fn layout(graph: &Graph, options: &ForceOptions) -> LayoutResult {
+    let (shapes, pairs) = shapes(graph);
+    let n = shapes.len();
+    let k = options.edge_length;
+
+    let mut random = Random(options.seed);
+    let side = k * (n as f64).sqrt();
+    let mut positions: Vec<Point> = (0..n).map(|_| Point::new(random.next() * side, random.next() * side)).collect();
+    let clusters: Vec<Vec<usize>> = (0..graph.subgraph.len())
+        .map(|c| (0..n).filter(|&v| shapes[v].cluster == Some(c)).collect())
+        .collect();
+
+    // Fruchterman-Reingold with a linearly falling temperature
+    let start = side / 10.0 + k;
+    for iteration in 0..options.iterations {
+        let temperature = start * (1.0 - iteration as f64 / options.iterations as f64);
+        let mut forces = vec![Point::new(0.0, 0.0); n];
+        for u in 0..n {
+            for v in u + 1..n {
+                let (d, distance) = direction(positions[u], positions[v], u, v);
+                let repulsion = k * k / distance;
+                push(&mut forces, u, d, repulsion);
+                push(&mut forces, v, d, -repulsion);
+            }
+        }
+        for &(_, u, v) in pairs.iter() {
+            if u == v {
+                continue;
+            }
+            let (d, distance) = direction(positions[u], positions[v], u, v);
+            let attraction = distance * distance / k;
+            push(&mut forces, u, d, -attraction);
+            push(&mut forces, v, d, attraction);
+        }
+        let pull = |forces: &mut [Point], members: &[usize], strength: f64| {
+            let center = centroid(&positions, members);
+            for &v in members {
+                let (d, distance) = direction(positions[v], center, v, n);
+                push(forces, v, d, -strength * distance * distance / k);
+            }
+        };
+        for members in clusters.iter().filter(|m| m.len() > 1) {
+            pull(&mut forces, members, CLUSTER_PULL);
+        }
+        let everything: Vec<usize> = (0..n).collect();
+        pull(&mut forces, &everything, GRAVITY);
+
+        let mut largest: f64 = 0.0;
+        for (p, f) in positions.iter_mut().zip(forces.iter()) {
+            let length = (f.x * f.x + f.y * f.y).sqrt();
+            if length == 0.0 {
+                continue;
+            }
+            let step = length.min(temperature);
+            p.x += f.x / length * step;
+            p.y += f.y / length * step;
+            largest = largest.max(step);
+        }
+        if largest < k * 1e-3 {
+            break;
+        }
+    }
+
+    if options.remove_overlap {
+        remove_overlap(&mut positions, &shapes.iter().map(|s| (s.width, s.height)).collect::<Vec<_>>(), options.node_sep);
+    }
+
+    let members: Vec<(Option<usize>, Point, f64, f64)> =
+        shapes.iter().zip(positions.iter()).map(|(s, p)| (s.cluster, *p, s.width, s.height)).collect();
+    let mut cluster_layouts = cluster_boxes(graph, &members);
+    let shift = offset(
+        members.iter().map(|m| (m.1, m.2, m.3)).chain(cluster_layouts.iter().map(|c| (Point::new(c.x, c.y), c.width, c.height))),
+    );
+    for p in positions.iter_mut() {
+        p.x += shift.x;
+        p.y += shift.y;
+    }
+    for c in cluster_layouts.iter_mut() {
+        c.x += shift.x;
+        c.y += shift.y;
+    }
+
+    let paths = pairs
+        .iter()
+        .map(|&(edge, from, to)| {
+            let (a, b) = (&shapes[from], &shapes[to]);
+            let points = if from == to {
+                loop_points(positions[from], a.width, a.height, a.boxed, options.node_sep)
+            } else {
+                vec![
+                    clip(positions[from], a.width, a.height, a.boxed, positions[to]),
+                    clip(positions[to], b.width, b.height, b.boxed, positions[from]),
+                ]
+            };
+            (edge, from, to, points)
+        })
+        .collect();
+
+    let nodes = shapes
+        .iter()
+        .zip(positions.iter())
+        .map(|(s, p)| NodeLayout { name: s.name.clone(), x: p.x, y: p.y, width: s.width, height: s.height })
+        .collect();
+    finish(graph, nodes, cluster_layouts, paths, options.splines)
+}
+
+/// The unit vector from `b` to `a` and their distance. Nodes at the same
+/// place are split in a direction depending on their indices.
+fn direction(a: Point, b: Point, i: usize, j: usize) -> (Point, f64) {
+    let (dx, dy) = (a.x - b.x, a.y - b.y);
+    let distance = (dx * dx + dy * dy).sqrt();
+    if distance < 1e-9 {
+        let d = [Point::new(1.0, 0.0), Point::new(0.0, 1.0), Point::new(-1.0, 0.0), Point::new(0.0, -1.0)];
+        return (d[(i + j) % 4], 0.01);
+    }
+    (Point::new(dx / distance, dy / distance), distance)
+}
+
+fn push(forces: &mut [Point], v: usize, d: Point, strength: f64) {
+    forces[v].x += d.x * strength;
+    forces[v].y += d.y * strength;
+}
+
+fn centroid(positions: &[Point], members: &[usize]) -> Point {
+    let count = members.len() as f64;
+    Point::new(
+        members.iter().map(|&v| positions[v].x).sum::<f64>() / count,
+        members.iter().map(|&v| positions[v].y).sum::<f64>() / count,
+    )
+}
+
+/// Pushes overlapping nodes apart along the axis where they overlap least,
+/// until `sep` is left between all of them.
+fn remove_overlap(positions: &mut [Point], sizes: &[(f64, f64)], sep: f64) {
+    for _ in 0..OVERLAP_ROUNDS {
+        let mut moved = false;
+        for u in 0..positions.len() {
+            for v in u + 1..positions.len() {
+                let (a, b) = (positions[u], positions[v]);
+                let overlap_x = (sizes[u].0 + sizes[v].0) / 2.0 + sep - (a.x - b.x).abs();
+                let overlap_y = (sizes[u].1 + sizes[v].1) / 2.0 + sep - (a.y - b.y).abs();
+                if overlap_x <= 1e-9 || overlap_y <= 1e-9 {
+                    continue;
+                }
+                moved = true;
+                if overlap_x < overlap_y {
+                    let sign = if a.x < b.x || (a.x == b.x && u < v) { -1.0 } else { 1.0 };
+                    positions[u].x += sign * overlap_x / 2.0;
+                    positions[v].x -= sign * overlap_x / 2.0;
+                } else {
+                    let sign = if a.y < b.y || (a.y == b.y && u < v) { -1.0 } else { 1.0 };
+                    positions[u].y += sign * overlap_y / 2.0;
+                    positions[v].y -= sign * overlap_y / 2.0;
+                }
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+}
+
+/// A SplitMix64 generator, so layouts do not depend on a random crate and
+/// are the same on every platform.
+struct Random(u64);
+
+impl Random {
+    /// A number in `[0, 1)`.
+    fn next(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,497 @@
+//! The layered engine behind `Graph::layered_layout`.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::{clip, cluster_boxes, finish, loop_points, offset, shapes, LayoutOptions, LayoutResult, NodeLayout, Point, CLUSTER_MARGIN};
+use crate::graph::Graph;
+/// Rounds of coordinate assignment.
+const PLACEMENT_ROUNDS: usize = 8;
+
+/// A node of the layered graph.
+struct Vertex {
+    /// `None` for the virtual nodes of edges spanning several ranks.
+    name: Option<String>,
+    width: f64,
+    height: f64,
+    boxed: bool,
+    cluster: Option<usize>,
+    rank: usize,
+    x: f64,
+    y: f64,
+}
+
+/// How an edge between two nodes is drawn.
+enum Route {
+    /// Through the vertices of `path`, from the upper rank to the lower.
+    Ranked { path: Vec<usize>, reversed: bool },
+    /// Between two nodes of the same rank.
+    Flat,
+    /// From a node to itself.
+    Loop,
+}
+
+pub(super) fn layout(graph: &Graph, options: &LayoutOptions) -> LayoutResult {
+    let (shapes, pairs) = shapes(graph);
+    let node_count = shapes.len();
+    let index: HashMap<&str, usize> = shapes.iter().enumerate().map(|(i, s)| (s.name.as_str(), i)).collect();
+    let groups: Vec<Vec<usize>> = graph
+        .same_rank
+        .iter()
+        .map(|g| g.iter().filter_map(|n| index.get(n.as_str()).copied()).collect())
+        .collect();
+    let mut vertices: Vec<Vertex> = shapes
+        .into_iter()
+        .map(|s| Vertex { name: Some(s.name), width: s.width, height: s.height, boxed: s.boxed, cluster: s.cluster, rank: 0, x: 0.0, y: 0.0 })
+        .collect();
+    let ends: Vec<(usize, usize)> = pairs.iter().map(|&(_, f, t)| (f, t)).collect();
+    let ranks = rank(node_count, &ends, &groups);
+    for (v, r) in vertices.iter_mut().zip(ranks.iter()) {
+        v.rank = *r;
+    }
+
+    // virtual nodes for edges spanning several ranks
+    let mut routes = vec![];
+    for &(_, from, to) in pairs.iter() {
+        let route = if from == to {
+            Route::Loop
+        } else if ranks[from] == ranks[to] {
+            Route::Flat
+        } else {
+            let reversed = ranks[from] > ranks[to];
+            let (upper, lower) = if reversed { (to, from) } else { (from, to) };
+            let cluster = if vertices[upper].cluster == vertices[lower].cluster { vertices[upper].cluster } else { None };
+            let mut path = vec![upper];
+            for r in ranks[upper] + 1..ranks[lower] {
+                path.push(vertices.len());
+                vertices.push(Vertex { name: None, width: 0.0, height: 0.0, boxed: false, cluster, rank: r, x: 0.0, y: 0.0 });
+            }
+            path.push(lower);
+            Route::Ranked { path, reversed }
+        };
+        routes.push(route);
+    }
+
+    let rank_count = vertices.iter().map(|v| v.rank + 1).max().unwrap_or(0);
+    let mut layers: Vec<Vec<usize>> = vec![vec![]; rank_count];
+    for (i, v) in vertices.iter().enumerate() {
+        layers[v.rank].push(i);
+    }
+    let mut up: Vec<Vec<usize>> = vec![vec![]; vertices.len()];
+    let mut down: Vec<Vec<usize>> = vec![vec![]; vertices.len()];
+    for route in routes.iter() {
+        if let Route::Ranked { path, .. } = route {
+            for w in path.windows(2) {
+                down[w[0]].push(w[1]);
+                up[w[1]].push(w[0]);
+            }
+        }
+    }
+
+    order(&mut layers, &vertices, &up, &down, options.iterations);
+    place(&layers, &mut vertices, &up, &down, options);
+    separate_clusters(&layers, &mut vertices, graph.subgraph.len(), options);
+
+    let members: Vec<(Option<usize>, Point, f64, f64)> =
+        vertices.iter().map(|v| (v.cluster, Point::new(v.x, v.y), v.width, v.height)).collect();
+    let mut clusters = cluster_boxes(graph, &members);
+    let shift = offset(
+        members.iter().map(|m| (m.1, m.2, m.3)).chain(clusters.iter().map(|c| (Point::new(c.x, c.y), c.width, c.height))),
+    );
+    for v in vertices.iter_mut() {
+        v.x += shift.x;
+        v.y += shift.y;
+    }
+    for c in clusters.iter_mut() {
+        c.x += shift.x;
+        c.y += shift.y;
+    }
+
+    let mut position = vec![0; vertices.len()];
+    for layer in layers.iter() {
+        for (i, &v) in layer.iter().enumerate() {
+            position[v] = i;
+        }
+    }
+    let rank_height: Vec<f64> = layers
+        .iter()
+        .map(|l| l.iter().map(|&v| vertices[v].height).fold(0.0, f64::max))
+        .collect();
+    let center = |v: &Vertex| Point::new(v.x, v.y);
+    let clip_to = |v: usize, toward: Point| {
+        let vertex = &vertices[v];
+        clip(center(vertex), vertex.width, vertex.height, vertex.boxed, toward)
+    };
+
+    let mut paths = vec![];
+    for (&(edge, from, to), route) in pairs.iter().zip(routes.iter()) {
+        let points = match route {
+            Route::Ranked { path, reversed } => {
+                let mut points: Vec<Point> = path.iter().map(|&v| center(&vertices[v])).collect();
+                let last = points.len() - 1;
+                points[0] = clip_to(path[0], points[1]);
+                points[last] = clip_to(path[last], points[last - 1]);
+                if *reversed {
+                    points.reverse();
+                }
+                points
+            }
+            Route::Flat => {
+                let (a, b) = (&vertices[from], &vertices[to]);
+                if position[from].abs_diff(position[to]) == 1 {
+                    vec![clip_to(from, center(b)), clip_to(to, center(a))]
+                } else {
+                    // arch over the nodes in between
+                    let top = a.y - rank_height[a.rank] / 2.0 - options.rank_sep / 3.0;
+                    let mid = Point::new((a.x + b.x) / 2.0, top);
+                    vec![clip_to(from, mid), mid, clip_to(to, mid)]
+                }
+            }
+            Route::Loop => {
+                let v = &vertices[from];
+                loop_points(center(v), v.width, v.height, v.boxed, options.node_sep)
+            }
+        };
+        paths.push((edge, from, to, points));
+    }
+
+    let nodes: Vec<NodeLayout> = vertices[..node_count]
+        .iter()
+        .map(|v| NodeLayout { name: v.name.clone().unwrap_or_default(), x: v.x, y: v.y, width: v.width, height: v.height })
+        .collect();
+    finish(graph, nodes, clusters, paths, options.splines)
+}
+
+/// Ranks `n` nodes connected by `edges`, the nodes of each of `groups`
+/// sharing one rank.
+fn rank(n: usize, edges: &[(usize, usize)], groups: &[Vec<usize>]) -> Vec<usize> {
+    // every group is ranked through its first node
+    let mut rep: Vec<usize> = (0..n).collect();
+    for group in groups {
+        if let Some(&first) = group.first() {
+            let target = rep[first];
+            for &v in group {
+                let old = rep[v];
+                for r in rep.iter_mut() {
+                    if *r == old {
+                        *r = target;
+                    }
+                }
+            }
+        }
+    }
+    let mut adjacent: Vec<Vec<usize>> = vec![vec![]; n];
+    for &(from, to) in edges {
+        if rep[from] != rep[to] {
+            adjacent[rep[from]].push(rep[to]);
+        }
+    }
+
+    // break cycles by turning back the edges closing them in a depth-first search
+    let mut state = vec![0u8; n];
+    let mut dag: Vec<Vec<usize>> = vec![vec![]; n];
+    for start in 0..n {
+        if rep[start] != start || state[start] != 0 {
+            continue;
+        }
+        let mut stack = vec![(start, 0)];
+        state[start] = 1;
+        while let Some((v, i)) = stack.pop() {
+            if i == adjacent[v].len() {
+                state[v] = 2;
+                continue;
+            }
+            stack.push((v, i + 1));
+            let w = adjacent[v][i];
+            match state[w] {
+                0 => {
+                    dag[v].push(w);
+                    state[w] = 1;
+                    stack.push((w, 0));
+                }
+                1 => dag[w].push(v),
+                _ => dag[v].push(w),
+            }
+        }
+    }
+
+    // longest path from the sources
+    let mut indegree = vec![0; n];
+    for targets in dag.iter() {
+        for &w in targets {
+            indegree[w] += 1;
+        }
+    }
+    let sources: Vec<bool> = indegree.iter().map(|&d| d == 0).collect();
+    let mut queue: VecDeque<usize> = (0..n).filter(|&v| rep[v] == v && indegree[v] == 0).collect();
+    let mut ranks = vec![0; n];
+    let mut sorted = vec![];
+    while let Some(v) = queue.pop_front() {
+        sorted.push(v);
+        for &w in dag[v].iter() {
+            ranks[w] = ranks[w].max(ranks[v] + 1);
+            indegree[w] -= 1;
+            if indegree[w] == 0 {
+                queue.push_back(w);
+            }
+        }
+    }
+    // pull sources down to their successors
+    for &v in sorted.iter().rev() {
+        if sources[v] && !dag[v].is_empty() {
+            ranks[v] = dag[v].iter().map(|&w| ranks[w]).min().unwrap() - 1;
+        }
+    }
+    let min = sorted.iter().map(|&v| ranks[v]).min().unwrap_or(0);
+    (0..n).map(|v| ranks[rep[v]] - min).collect()
+}
+
+/// Orders the ranks to reduce crossings, keeping the members of a cluster
+/// next to each other.
+fn order(layers: &mut [Vec<usize>], vertices: &[Vertex], up: &[Vec<usize>], down: &[Vec<usize>], iterations: usize) {
+    let mut position = vec![0.0; vertices.len()];
+    let update = |layer: &[usize], position: &mut Vec<f64>| {
+        for (i, &v) in layer.iter().enumerate() {
+            position[v] = i as f64;
+        }
+    };
+    for layer in layers.iter_mut() {
+        update(layer, &mut position);
+        let keys: Vec<f64> = layer.iter().map(|&v| position[v]).collect();
+        sort_layer(layer, &keys, vertices);
+        update(layer, &mut position);
+    }
+
+    let mut best = layers.to_vec();
+    let mut best_crossings = crossings(layers, down, &position);
+    for iteration in 0..iterations {
+        let ranks: Vec<usize> = if iteration % 2 == 0 {
+            (1..layers.len()).collect()
+        } else {
+            (0..layers.len().saturating_sub(1)).rev().collect()
+        };
+        let neighbors = if iteration % 2 == 0 { up } else { down };
+        for r in ranks {
+            let keys: Vec<f64> = layers[r]
+                .iter()
+                .map(|&v| {
+                    if neighbors[v].is_empty() {
+                        position[v]
+                    } else {
+                        neighbors[v].iter().map(|&w| position[w]).sum::<f64>() / neighbors[v].len() as f64
+                    }
+                })
+                .collect();
+            sort_layer(&mut layers[r], &keys, vertices);
+            update(&layers[r], &mut position);
+        }
+        let c = crossings(layers, down, &position);
+        if c < best_crossings {
+            best_crossings = c;
+            best = layers.to_vec();
+        }
+    }
+    layers.clone_from_slice(&best);
+}
+
+/// Sorts `layer` by `keys`, moving clusters as a whole by the mean key of
+/// their members.
+fn sort_layer(layer: &mut [usize], keys: &[f64], vertices: &[Vertex]) {
+    // (cluster or node, sum of keys, count, first position)
+    let block = |v: usize| vertices[v].cluster.map(|c| (true, c)).unwrap_or((false, v));
+    let mut blocks: HashMap<(bool, usize), (f64, usize, usize)> = HashMap::new();
+    for (i, &v) in layer.iter().enumerate() {
+        let entry = blocks.entry(block(v)).or_insert((0.0, 0, i));
+        entry.0 += keys[i];
+        entry.1 += 1;
+    }
+    let mut items: Vec<(f64, usize, f64, usize, usize)> = layer
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            let (sum, count, first) = blocks[&block(v)];
+            (sum / count as f64, first, keys[i], i, v)
+        })
+        .collect();
+    items.sort_by(|a, b| {
+        a.0.total_cmp(&b.0)
+            .then(a.1.cmp(&b.1))
+            .then(a.2.total_cmp(&b.2))
+            .then(a.3.cmp(&b.3))
+    });
+    for (slot, item) in layer.iter_mut().zip(items) {
+        *slot = item.4;
+    }
+}
+
+fn crossings(layers: &[Vec<usize>], down: &[Vec<usize>], position: &[f64]) -> usize {
+    let mut count = 0;
+    for layer in layers.iter() {
+        let segments: Vec<(f64, f64)> = layer
+            .iter()
+            .flat_map(|&v| down[v].iter().map(move |&w| (position[v], position[w])))
+            .collect();
+        for (i, a) in segments.iter().enumerate() {
+            for b in segments[i + 1..].iter() {
+                if (a.0 < b.0 && a.1 > b.1) || (a.0 > b.0 && a.1 < b.1) {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+/// The minimal distance between the centers of `a` and `b` next to each
+/// other on a rank.
+fn gap(a: &Vertex, b: &Vertex, options: &LayoutOptions) -> f64 {
+    let mut gap = (a.width + b.width) / 2.0 + options.node_sep;
+    if a.cluster != b.cluster {
+        gap += CLUSTER_MARGIN * (a.cluster.is_some() as u8 + b.cluster.is_some() as u8) as f64;
+    }
+    gap
+}
+
+/// Assigns coordinates, moving every node towards the mean of its
+/// neighbors on the adjacent ranks.
+fn place(layers: &[Vec<usize>], vertices: &mut [Vertex], up: &[Vec<usize>], down: &[Vec<usize>], options: &LayoutOptions) {
+    let mut y = 0.0;
+    for layer in layers.iter() {
+        let height = layer.iter().map(|&v| vertices[v].height).fold(0.0, f64::max);
+        for &v in layer.iter() {
+            vertices[v].y = y + height / 2.0;
+        }
+        y += height + options.rank_sep;
+    }
+
+    for layer in layers.iter() {
+        let mut x = 0.0;
+        for (i, &v) in layer.iter().enumerate() {
+            if i > 0 {
+                x += gap(&vertices[layer[i - 1]], &vertices[v], options);
+            }
+            vertices[v].x = x;
+        }
+    }
+
+    let rank_count = layers.len();
+    for round in 0..=PLACEMENT_ROUNDS {
+        let sweeps: Vec<(usize, bool, bool)> = if round == PLACEMENT_ROUNDS {
+            (0..rank_count).map(|r| (r, true, true)).collect()
+        } else {
+            (1..rank_count)
+                .map(|r| (r, true, false))
+                .chain((0..rank_count.saturating_sub(1)).rev().map(|r| (r, false, true)))
+                .collect()
+        };
+        for (r, use_up, use_down) in sweeps {
+            let layer = &layers[r];
+            let desired: Vec<f64> = layer
+                .iter()
+                .map(|&v| {
+                    let mut neighbors: Vec<usize> = vec![];
+                    if use_up {
+                        neighbors.extend(up[v].iter());
+                    }
+                    if use_down {
+                        neighbors.extend(down[v].iter());
+                    }
+                    if neighbors.is_empty() {
+                        vertices[v].x
+                    } else {
+                        neighbors.iter().map(|&w| vertices[w].x).sum::<f64>() / neighbors.len() as f64
+                    }
+                })
+                .collect();
+            // virtual nodes weigh more to keep long edges straight
+            let weights: Vec<f64> = layer.iter().map(|&v| if vertices[v].name.is_none() { 2.0 } else { 1.0 }).collect();
+            let gaps: Vec<f64> = layer.windows(2).map(|w| gap(&vertices[w[0]], &vertices[w[1]], options)).collect();
+            for (&v, x) in layer.iter().zip(isotonic(&desired, &weights, &gaps)) {
+                vertices[v].x = x;
+            }
+        }
+    }
+}
+
+/// The positions closest to `desired` (by weighted least squares) which
+/// keep at least `gaps` between neighbors, by pooling adjacent violators.
+fn isotonic(desired: &[f64], weights: &[f64], gaps: &[f64]) -> Vec<f64> {
+    let mut offsets = vec![0.0; desired.len()];
+    for i in 1..desired.len() {
+        offsets[i] = offsets[i - 1] + gaps[i - 1];
+    }
+    // (weighted sum, weight, count)
+    let mut blocks: Vec<(f64, f64, usize)> = vec![];
+    for i in 0..desired.len() {
+        blocks.push((weights[i] * (desired[i] - offsets[i]), weights[i], 1));
+        while blocks.len() > 1 {
+            let (s1, w1, c1) = blocks[blocks.len() - 1];
+            let (s0, w0, c0) = blocks[blocks.len() - 2];
+            if s0 / w0 <= s1 / w1 {
+                break;
+            }
+            blocks.pop();
+            *blocks.last_mut().unwrap() = (s0 + s1, w0 + w1, c0 + c1);
+        }
+    }
+    let mut positions = vec![];
+    for (sum, weight, count) in blocks {
+        for _ in 0..count {
+            let i = positions.len();
+            positions.push(sum / weight + offsets[i]);
+        }
+    }
+    positions
+}
+
+/// Moves nodes out of the boxes of clusters they do not belong to.
+fn separate_clusters(layers: &[Vec<usize>], vertices: &mut [Vertex], cluster_count: usize, options: &LayoutOptions) {
+    let space = CLUSTER_MARGIN + options.node_sep / 2.0;
+    for _ in 0..10 {
+        let mut moved = false;
+        for c in 0..cluster_count {
+            let members: Vec<usize> = (0..vertices.len()).filter(|&v| vertices[v].cluster == Some(c)).collect();
+            if members.is_empty() {
+                continue;
+            }
+            let left = members.iter().map(|&v| vertices[v].x - vertices[v].width / 2.0).fold(f64::INFINITY, f64::min);
+            let right = members.iter().map(|&v| vertices[v].x + vertices[v].width / 2.0).fold(f64::NEG_INFINITY, f64::max);
+            let first_rank = members.iter().map(|&v| vertices[v].rank).min().unwrap();
+            let last_rank = members.iter().map(|&v| vertices[v].rank).max().unwrap();
+
+            for layer in layers[first_rank..=last_rank].iter() {
+                let inside: Vec<usize> = (0..layer.len()).filter(|&i| vertices[layer[i]].cluster == Some(c)).collect();
+                let (before, after) = match (inside.first(), inside.last()) {
+                    (Some(&first), Some(&last)) => (first, last + 1),
+                    _ => {
+                        let middle = (left + right) / 2.0;
+                        let split = layer.iter().position(|&v| vertices[v].x > middle).unwrap_or(layer.len());
+                        (split, split)
+                    }
+                };
+                if before > 0 {
+                    let v = &vertices[layer[before - 1]];
+                    let overlap = v.x + v.width / 2.0 - (left - space);
+                    if overlap > 1e-6 {
+                        for &w in layer[..before].iter() {
+                            vertices[w].x -= overlap;
+                        }
+                        moved = true;
+                    }
+                }
+                if after < layer.len() {
+                    let v = &vertices[layer[after]];
+                    let overlap = right + space - (v.x - v.width / 2.0);
+                    if overlap > 1e-6 {
+                        for &w in layer[after..].iter() {
+                            vertices[w].x += overlap;
+                        }
+                        moved = true;
+                    }
+                }
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+}
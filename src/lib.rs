@@ -69,44 +69,178 @@
 //! After version 0.2.1, dot_graph support subgraph generation, for example:
 //! 
 //! ```rust
-//! #[test]
-//! fn test_subgraph() {
-//!     let mut graph = Graph::new("di", Kind::Digraph);
-//!     let mut c1 = Subgraph::new("cluster_0").label("");
-//!     c1.add_node(Node::new("N0"));
-//!     c1.add_node(Node::new("N1"));
-//!     let mut c2 = Subgraph::new("cluster_1").label("");
-//!     c2.add_node(Node::new("N2"));
-//!     c2.add_node(Node::new("N3"));
-//!     graph.add_subgraph(c1);
-//!     graph.add_subgraph(c2);
-//!     graph.add_edge(Edge::new("N0", "N1", ""));
-//!     graph.add_edge(Edge::new("N0", "N2", ""));
-//!     graph.add_edge(Edge::new("N1", "N3", ""));
-//!     graph.add_edge(Edge::new("N2", "N3", ""));
-//!     
-//! 
-//!     assert_eq!(graph.to_dot_string().unwrap(),
+//! use dot_graph::{Graph, Kind, Node, Edge, Subgraph};
+//!
+//! let mut graph = Graph::new("di", Kind::Digraph);
+//! let mut c1 = Subgraph::new("cluster_0").label("");
+//! c1.add_node(Node::new("N0"));
+//! c1.add_node(Node::new("N1"));
+//! let mut c2 = Subgraph::new("cluster_1").label("");
+//! c2.add_node(Node::new("N2"));
+//! c2.add_node(Node::new("N3"));
+//! graph.add_subgraph(c1);
+//! graph.add_subgraph(c2);
+//! graph.add_edge(Edge::new("N0", "N1", ""));
+//! graph.add_edge(Edge::new("N0", "N2", ""));
+//! graph.add_edge(Edge::new("N1", "N3", ""));
+//! graph.add_edge(Edge::new("N2", "N3", ""));
+//!
+//! assert_eq!(graph.to_dot_string().unwrap(),
 //! r#"digraph di {
-//! subgraph cluster_0 {
-//!     label="";
-//!     "N0"[label="N0"];
-//!     "N1"[label="N1"];
+//!     subgraph cluster_0 {
+//!         label="";
+//!         "N0"[label="N0"];
+//!         "N1"[label="N1"];
+//!     }
+//!     subgraph cluster_1 {
+//!         label="";
+//!         "N2"[label="N2"];
+//!         "N3"[label="N3"];
+//!     }
+//!     "N0" -> "N1"[label=""];
+//!     "N0" -> "N2"[label=""];
+//!     "N1" -> "N3"[label=""];
+//!     "N2" -> "N3"[label=""];
 //! }
-//! subgraph cluster_1 {
-//!     label="";
-//!     "N2"[label="N2"];
-//!     "N3"[label="N3"];
-//! }
-//! "N0" -> "N1"[label=""];
-//! "N0" -> "N2"[label=""];
-//! "N1" -> "N3"[label=""];
-//! "N2" -> "N3"[label=""];
+//! "#);
+//! ```
+//!
+//! Edges could also be chained (`a -> b -> c`), point to all nodes of a
+//! subgraph (`a -> {b c}`), or be clipped at a cluster boundary with
+//! `lhead`/`ltail`, which turns on `compound=true` for the graph:
+//!
+//! ```rust
+//! use dot_graph::{Graph, Kind, Node, Edge, Subgraph};
+//!
+//! let mut graph = Graph::new("di", Kind::Digraph);
+//! let mut c0 = Subgraph::new("cluster_0").label("");
+//! c0.add_node(Node::new("b"));
+//! c0.add_node(Node::new("c"));
+//! graph.add_edge(Edge::chain(&["a", "b", "c"], ""));
+//! graph.add_edge(Edge::new("a", &c0, ""));
+//! graph.add_edge(Edge::new("a", "b", "").lhead(&c0));
+//! graph.add_subgraph(c0);
+//!
+//! assert_eq!(graph.to_dot_string().unwrap(),
+//! r#"digraph di {
+//!     compound=true
+//!     subgraph cluster_0 {
+//!         label="";
+//!         "b"[label="b"];
+//!         "c"[label="c"];
+//!     }
+//!     "a" -> "b" -> "c"[label=""];
+//!     "a" -> {"b" "c"}[label=""];
+//!     "a" -> "b"[label=""][lhead="cluster_0"];
 //! }
 //! "#);
+//! ```
+//!
+//! Your own data structures could also be rendered directly, without copying
+//! them into a `Graph`, by implementing `Labeller` and `GraphWalk` and
+//! calling `render_walk`.
+//!
+//! DOT written by this library or by other tools could be read back with
+//! `Graph::from_dot_string`; `dot::read` also tells what the model could not
+//! keep, see the `dot` module.
+//!
+//! `Graph::diff` lists what was added, removed or changed between two versions
+//! of a graph and draws them in one colored graph, see the `diff` module.
+//!
+//! `Graph::merge` adds another graph to one, joining nodes, subgraphs and edges
+//! with the same names; `merge::MergePolicy` decides conflicting attributes and
+//! can prefix the names of the added graph.
+//!
+//! `Graph::canonicalize` sorts subgraphs, nodes and edges by name, so graphs
+//! with the same elements render the same whatever order they were built in;
+//! `Graph::to_canonical_dot_string` renders a sorted copy.
+//!
+//! The `algo` module analyses a graph across its subgraphs: topological order,
+//! cycles, strongly connected and connected components, ancestors and
+//! descendants, and shortest paths.
+//! `Graph::highlight_path` and `Graph::highlight_cycles` show the results on the
+//! graph, and `Graph::cluster_by_scc` groups strongly connected nodes into
+//! clusters, see the `highlight` module.
+//!
+//! `Graph::transitive_reduction` drops the edges implied by longer paths, and
+//! `Graph::merge_parallel_edges` draws parallel edges as one, see the `simplify`
+//! module.
+//!
+//! `Graph::neighborhood`, `Graph::induced` and `Graph::filter` return smaller
+//! views of a graph, see the `extract` module.
+//!
+//! `Graph::collapse_subgraph` draws a cluster as a single node, with the edges
+//! crossing its boundary, and `Graph::expand` brings it back.
+//!
+//! `Graph::stats` counts nodes, edges and clusters and measures degrees,
+//! density, the longest path and the top fan-in/out (as many nodes as
+//! asked for with `Graph::stats_with`), printed as text or with
+//! `Stats::to_json`, see the `stats` module.
+//!
+//! Besides DOT, a graph could be written in and read back from the Graphviz
+//! `json0` schema with `Graph::to_graphviz_json` and
+//! `Graph::from_graphviz_json`, or as GraphML with `Graph::to_graphml` and
+//! `Graph::from_graphml`.
+//!
+//! It could also be exported as a Mermaid flowchart with `Graph::to_mermaid`,
+//! see the `mermaid` module, as a PlantUML document with `Graph::to_plantuml`,
+//! and for Gephi and Cytoscape.js with `Graph::to_gexf` and
+//! `Graph::to_cytoscape_json`.
+//!
+//! Without Graphviz at hand, `Graph::layered_layout` (like `dot`) and
+//! `Graph::force_layout` (like `neato`) compute node positions and edge paths
+//! themselves, see the `layout` module, and `Graph::to_svg` draws the result.
+//! `Graph::to_text` draws small graphs with box-drawing characters for
+//! terminals and logs, see the `text` module.
+//!
+//! The positions Graphviz computed could be read back from its `-Tplain` or
+//! `-Txdot` output with `Graph::layout_from_plain` and `Graph::layout_from_xdot`,
+//! and `Graph::apply_layout` writes a layout to the graph as `pos` and `bb`.
+//!
+//! # Optional features
+//!
+//! * `cli`: the `dot_graph` command line tool, with the subcommands `fmt`,
+//!   `check`, `convert`, `stats` and `diff`; run `dot_graph help`.
+//! * `graphviz`: `Graph::render_to` runs the installed Graphviz engines
+//!   (`dot`, `neato`, ...) on the graph, see the `graphviz` module.
+//! * `petgraph`: conversions from and into [petgraph](https://docs.rs/petgraph)
+//!   graphs, see `Graph::from_petgraph`.
+//! * `serde`: `Serialize`/`Deserialize` for the whole graph model, with the
+//!   schema below (shown as JSON). Enum values are the lowercase DOT names,
+//!   e.g. `"invis"` for `Style::Invisible` or `{"normal": ["open", "left"]}`
+//!   for `ArrowShape::Normal(Fill::Open, Side::Left)`. Only the fields
+//!   without a value here are required when deserializing, the others
+//!   default to what the constructors set. Names are not validated on
+//!   deserialization, `compound` is turned on when an edge has `lhead` or
+//!   `ltail` and the edges of subgraphs follow `kind`. Collapsed subgraphs
+//!   are not serialized, so `Graph::expand` cannot bring them back after a
+//!   round trip.
+//!
+//! ```json
+//! {
+//!   "name": "G",
+//!   "kind": "digraph",
+//!   "url": "",
+//!   "compound": false,
+//!   "same_rank": [],
+//!   "bb": null,
+//!   "nodes": [
+//!     {"name": <string>, "label": <string>, "style": "none",
+//!      "color": null, "shape": null, "url": "", "pos": null}
+//!   ],
+//!   "edges": [
+//!     {"endpoints": [{"node": "a"}, {"subgraph": {"name": "cluster_0", "nodes": ["b"]}}],
+//!      "label": <string>, "label_url": "", "url": "", "style": "none",
+//!      "start_arrow": {"arrows": []}, "end_arrow": {"arrows": []},
+//!      "color": null, "penwidth": null, "lhead": null, "ltail": null, "pos": null}
+//!   ],
+//!   "subgraphs": [
+//!     {"name": <string>, "nodes": [], "edges": [], "label": "",
+//!      "style": "none", "color": null, "url": "", "bb": null}
+//!   ]
 //! }
 //! ```
-//! 
+//!
 //! For more examples, please check the tests.
 //! 
 //! The library is under active development, we'll include more dot attributes
@@ -125,11 +259,40 @@ mod edge;
 mod graph;
 mod utils;
 mod subgraph;
+mod walk;
+mod json;
+mod graphviz_json;
+pub mod mermaid;
+mod plantuml;
+mod xml;
+mod graphml;
+pub mod dot;
+pub mod diff;
+pub mod merge;
+pub mod algo;
+pub mod highlight;
+pub mod simplify;
+pub mod extract;
+pub mod stats;
+mod gexf;
+mod cytoscape;
+pub mod layout;
+mod svg;
+mod xdot;
+mod canonical;
+mod collapse;
+pub mod text;
+#[cfg(feature = "petgraph")]
+mod petgraph_conv;
+#[cfg(feature = "graphviz")]
+pub mod graphviz;
 
 pub use style::Style;
 pub use arrow::{Arrow, ArrowShape, Side, Fill};
 pub use node::{Node};
-pub use edge::{Edge};
+pub use edge::{Edge, Endpoint};
 pub use graph::{Graph, Kind};
 pub use subgraph::Subgraph;
+pub use walk::{Labeller, GraphWalk, Nodes, Edges, render_walk};
+pub use collapse::CollapseError;
 
@@ -0,0 +1,326 @@
+//! Combining graphs, see `Graph::merge`.
+//!
+//! ```rust
+//! use dot_graph::{Graph, Kind, Node, Edge, merge::{Conflict, MergePolicy}};
+//!
+//! let mut workspace = Graph::new("workspace", Kind::Digraph);
+//! workspace.add_node(Node::new("serde").color(Some("blue")));
+//!
+//! let mut core = Graph::new("core", Kind::Digraph);
+//! core.add_node(Node::new("serde").shape(Some("box")));
+//! core.add_edge(Edge::new("core", "serde", ""));
+//!
+//! workspace.merge(&core, &MergePolicy::default()).unwrap();
+//! assert_eq!(workspace.to_dot_string().unwrap(),
+//! r#"digraph workspace {
+//!     "serde"[label="serde"][color="blue"][shape="box"];
+//!     "core" -> "serde"[label=""];
+//! }
+//! "#);
+//!
+//! let mut red = Graph::new("red", Kind::Digraph);
+//! red.add_node(Node::new("serde").color(Some("red")));
+//! let policy = MergePolicy { on_conflict: Conflict::Error, ..Default::default() };
+//! assert!(workspace.merge(&red, &policy).is_err());
+//! ```
+
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    arrow::Arrow,
+    edge::{Edge, Endpoint},
+    graph::{Graph, Kind},
+    node::{self, Node},
+    style::Style,
+    subgraph,
+};
+
+/// What to do when both graphs set an attribute to different values.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Conflict {
+    /// Keep the value of the graph merged into.
+    #[default]
+    KeepLeft,
+    /// Take the value of the graph being merged.
+    KeepRight,
+    /// Fail with `MergeError::Conflict`, leaving the graph unchanged.
+    Error,
+}
+
+/// Why `Graph::merge` failed; the graph merged into is left unchanged.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum MergeError {
+    /// The graphs are of different kinds.
+    KindMismatch { left: Kind, right: Kind },
+    /// Both graphs set an attribute to different values and
+    /// `Conflict::Error` was asked for. `what` is the attribute and its
+    /// owner, like `"color of node a"`, the values are in `Debug` form.
+    Conflict { what: String, left: String, right: String },
+    /// A node or subgraph name that is not valid once prefixed.
+    InvalidPrefix { name: String },
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MergeError::KindMismatch { left, right } => write!(f, "cannot merge a {} into a {}", right.keyword(), left.keyword()),
+            MergeError::Conflict { what, left, right } => write!(f, "{}: {} conflicts with {}", what, left, right),
+            MergeError::InvalidPrefix { name } => write!(f, "invalid name {:?} with the prefix", name),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// Options of `Graph::merge`.
+#[derive(Clone, Debug, Default)]
+pub struct MergePolicy {
+    pub on_conflict: Conflict,
+    /// Put in front of the node names of the merged graph, like `"core."`,
+    /// and after `cluster_` in its subgraph names (with `.` replaced by
+    /// `_`), so graphs using the same names stay apart. Labels are kept.
+    pub prefix: Option<String>,
+}
+
+impl Graph {
+    /// Adds the nodes, edges and subgraphs of `other` to this graph.
+    ///
+    /// Nodes and subgraphs with the same name are merged, an attribute set
+    /// in only one of them is kept. Edges are the same when they have the
+    /// same endpoints, parallel edges matched in order. Nodes and edges of
+    /// a cluster of `other` go to the cluster of the same name, which is
+    /// added if needed; a node declared in different clusters is a
+    /// conflict, decided like the attributes. `same_rank` groups are
+    /// added, and `compound` is kept if either graph has it.
+    ///
+    /// Merging graphs of different `Kind`s fails, like an invalid prefix,
+    /// see `MergeError`.
+    pub fn merge(&mut self, other: &Graph, policy: &MergePolicy) -> Result<(), MergeError> {
+        if self.kind != other.kind {
+            return Err(MergeError::KindMismatch { left: self.kind, right: other.kind });
+        }
+        let other = match &policy.prefix {
+            Some(prefix) => prefixed(other, prefix)?,
+            None => other.clone(),
+        };
+        let on_conflict = policy.on_conflict;
+        let mut merged = self.clone();
+
+        pick(&mut merged.url, &other.url, &String::new(), on_conflict, "URL of the graph")?;
+        pick(&mut merged.bb, &other.bb, &None, on_conflict, "bb of the graph")?;
+        merged.compound |= other.compound;
+        merged.same_rank.extend(other.same_rank.iter().cloned());
+
+        for s in other.subgraph.iter() {
+            match merged.subgraph.iter_mut().find(|t| t.name == s.name) {
+                Some(t) => {
+                    let what = |attribute: &str| format!("{} of {}", attribute, s.name);
+                    pick(&mut t.label, &s.label, &String::new(), on_conflict, &what("label"))?;
+                    pick(&mut t.style, &s.style, &Style::None, on_conflict, &what("style"))?;
+                    pick(&mut t.color, &s.color, &None, on_conflict, &what("color"))?;
+                    pick(&mut t.url, &s.url, &String::new(), on_conflict, &what("URL"))?;
+                    pick(&mut t.bb, &s.bb, &None, on_conflict, &what("bb"))?;
+                }
+                None => {
+                    let mut t = s.clone();
+                    t.nodes = vec![];
+                    t.edges = vec![];
+                    merged.subgraph.push(t.edgeop(merged.kind.edgeop()));
+                }
+            }
+        }
+
+        let clusters = other.subgraph.iter().flat_map(|s| s.nodes.iter().map(move |n| (n, Some(s.name.as_str()))));
+        for (node, cluster) in clusters.chain(other.nodes.iter().map(|n| (n, None))) {
+            merged.merge_node(node, cluster, on_conflict)?;
+        }
+
+        // the edges of `merged` by endpoints, to match the ones of `other`
+        let mut existing: HashMap<Key, Vec<(Option<usize>, usize)>> = HashMap::new();
+        for (i, s) in merged.subgraph.iter().enumerate() {
+            for (j, e) in s.edges.iter().enumerate() {
+                existing.entry(key(e)).or_default().push((Some(i), j));
+            }
+        }
+        for (j, e) in merged.edges.iter().enumerate() {
+            existing.entry(key(e)).or_default().push((None, j));
+        }
+        let mut seen: HashMap<Key, usize> = HashMap::new();
+        let clusters = other.subgraph.iter().flat_map(|s| s.edges.iter().map(move |e| (e, Some(s.name.as_str()))));
+        for (edge, cluster) in clusters.chain(other.edges.iter().map(|e| (e, None))) {
+            let k = key(edge);
+            let count = seen.entry(k.clone()).or_default();
+            match existing.get(&k).and_then(|found| found.get(*count)).copied() {
+                Some((i, j)) => {
+                    let target = match i {
+                        Some(i) => &mut merged.subgraph[i].edges[j],
+                        None => &mut merged.edges[j],
+                    };
+                    merge_edge(target, edge, on_conflict)?;
+                }
+                None => match cluster.and_then(|c| merged.subgraph.iter_mut().find(|s| s.name == c)) {
+                    Some(s) => s.add_edge(edge.clone()),
+                    None => merged.edges.push(edge.clone()),
+                },
+            }
+            *count += 1;
+        }
+
+        *self = merged;
+        Ok(())
+    }
+
+    /// Merges a node declared in `cluster` of another graph.
+    fn merge_node(&mut self, node: &Node, cluster: Option<&str>, on_conflict: Conflict) -> Result<(), MergeError> {
+        let found = self
+            .subgraph
+            .iter()
+            .enumerate()
+            .find_map(|(i, s)| s.nodes.iter().position(|n| n.name == node.name).map(|j| (Some(i), j)))
+            .or_else(|| self.nodes.iter().position(|n| n.name == node.name).map(|j| (None, j)));
+        let target = cluster.and_then(|c| self.subgraph.iter().position(|s| s.name == c));
+        let Some((i, j)) = found else {
+            match target {
+                Some(t) => self.subgraph[t].add_node(node.clone()),
+                None => self.nodes.push(node.clone()),
+            }
+            return Ok(());
+        };
+
+        let current = match i {
+            Some(i) => &mut self.subgraph[i].nodes[j],
+            None => &mut self.nodes[j],
+        };
+        let what = |attribute: &str| format!("{} of node {}", attribute, node.name);
+        pick(&mut current.label, &node.label, &node.name, on_conflict, &what("label"))?;
+        pick(&mut current.style, &node.style, &Style::None, on_conflict, &what("style"))?;
+        pick(&mut current.color, &node.color, &None, on_conflict, &what("color"))?;
+        pick(&mut current.shape, &node.shape, &None, on_conflict, &what("shape"))?;
+        pick(&mut current.url, &node.url, &String::new(), on_conflict, &what("URL"))?;
+        pick(&mut current.pos, &node.pos, &None, on_conflict, &what("pos"))?;
+
+        // the cluster is an attribute too, with the top level as unset
+        let name = |i: Option<usize>| i.map(|i| self.subgraph[i].name.clone());
+        let mut place = name(i);
+        pick(&mut place, &name(target), &None, on_conflict, &what("cluster"))?;
+        if place != name(i) {
+            let moved = match i {
+                Some(i) => self.subgraph[i].nodes.remove(j),
+                None => self.nodes.remove(j),
+            };
+            match target {
+                Some(t) => self.subgraph[t].nodes.push(moved),
+                None => self.nodes.push(moved),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Merges the attributes of an edge with the same endpoints.
+fn merge_edge(target: &mut Edge, edge: &Edge, on_conflict: Conflict) -> Result<(), MergeError> {
+    let name = key(edge).iter().map(|names| names.join(" ")).collect::<Vec<String>>().join(" - ");
+    let what = |attribute: &str| format!("{} of edge {}", attribute, name);
+    pick(&mut target.label, &edge.label, &String::new(), on_conflict, &what("label"))?;
+    pick(&mut target.label_url, &edge.label_url, &String::new(), on_conflict, &what("labelURL"))?;
+    pick(&mut target.url, &edge.url, &String::new(), on_conflict, &what("URL"))?;
+    pick(&mut target.style, &edge.style, &Style::None, on_conflict, &what("style"))?;
+    pick(&mut target.color, &edge.color, &None, on_conflict, &what("color"))?;
+    pick(&mut target.penwidth, &edge.penwidth, &None, on_conflict, &what("penwidth"))?;
+    // arrows are compared by their DOT text
+    let unset = Arrow::default().to_dot_string();
+    let mut tail = target.start_arrow.to_dot_string();
+    pick(&mut tail, &edge.start_arrow.to_dot_string(), &unset, on_conflict, &what("arrowtail"))?;
+    if tail == edge.start_arrow.to_dot_string() {
+        target.start_arrow = edge.start_arrow.clone();
+    }
+    let mut head = target.end_arrow.to_dot_string();
+    pick(&mut head, &edge.end_arrow.to_dot_string(), &unset, on_conflict, &what("arrowhead"))?;
+    if head == edge.end_arrow.to_dot_string() {
+        target.end_arrow = edge.end_arrow.clone();
+    }
+    pick(&mut target.lhead, &edge.lhead, &None, on_conflict, &what("lhead"))?;
+    pick(&mut target.ltail, &edge.ltail, &None, on_conflict, &what("ltail"))?;
+    pick(&mut target.pos, &edge.pos, &None, on_conflict, &what("pos"))
+}
+
+/// The node names of each endpoint of an edge.
+type Key = Vec<Vec<String>>;
+
+/// The key of an edge, edges with the same key are the same.
+fn key(edge: &Edge) -> Key {
+    edge.endpoints.iter().map(Endpoint::node_names).collect()
+}
+
+/// Sets `left` to `right` when `left` is `unset`, or when both are set
+/// and `on_conflict` says so.
+fn pick<T: PartialEq + Clone + std::fmt::Debug>(left: &mut T, right: &T, unset: &T, on_conflict: Conflict, what: &str) -> Result<(), MergeError> {
+    if right == unset || left == right {
+        return Ok(());
+    }
+    if left == unset {
+        *left = right.clone();
+        return Ok(());
+    }
+    match on_conflict {
+        Conflict::KeepLeft => {}
+        Conflict::KeepRight => *left = right.clone(),
+        Conflict::Error => {
+            return Err(MergeError::Conflict { what: String::from(what), left: format!("{:?}", left), right: format!("{:?}", right) })
+        }
+    }
+    Ok(())
+}
+
+/// A copy of `graph` with prefixed names; nodes only referenced by edges
+/// are declared to keep their label.
+fn prefixed(graph: &Graph, prefix: &str) -> Result<Graph, MergeError> {
+    let rename = |name: &str| -> Result<String, MergeError> {
+        let renamed = format!("{}{}", prefix, name);
+        if node::is_valid_name(&renamed) { Ok(renamed) } else { Err(MergeError::InvalidPrefix { name: renamed }) }
+    };
+    let cluster_prefix = prefix.replace('.', "_");
+    let rename_cluster = |name: &str| -> Result<String, MergeError> {
+        let renamed = format!("cluster_{}{}", cluster_prefix, name.trim_start_matches("cluster_"));
+        if subgraph::is_valid_name(&renamed) { Ok(renamed) } else { Err(MergeError::InvalidPrefix { name: renamed }) }
+    };
+    let rename_node = |node: &Node| -> Result<Node, MergeError> {
+        let mut renamed = node.clone();
+        renamed.name = rename(&node.name)?;
+        Ok(renamed)
+    };
+    let rename_edge = |edge: &Edge| -> Result<Edge, MergeError> {
+        let mut renamed = edge.clone();
+        for endpoint in renamed.endpoints.iter_mut() {
+            *endpoint = match &*endpoint {
+                Endpoint::Node(name) => Endpoint::Node(rename(name)?),
+                Endpoint::Subgraph { name, nodes } => Endpoint::Subgraph {
+                    name: if name.is_empty() { String::new() } else { rename_cluster(name)? },
+                    nodes: nodes.iter().map(|n| rename(n)).collect::<Result<_, MergeError>>()?,
+                },
+            };
+        }
+        renamed.lhead = edge.lhead.as_deref().map(rename_cluster).transpose()?;
+        renamed.ltail = edge.ltail.as_deref().map(rename_cluster).transpose()?;
+        Ok(renamed)
+    };
+
+    let mut result = graph.clone();
+    for s in result.subgraph.iter_mut() {
+        s.name = rename_cluster(&s.name)?;
+        s.nodes = s.nodes.iter().map(rename_node).collect::<Result<_, MergeError>>()?;
+        s.edges = s.edges.iter().map(rename_edge).collect::<Result<_, MergeError>>()?;
+    }
+    let declared: Vec<String> = graph.all_nodes().iter().map(|n| n.name.clone()).collect();
+    result.nodes = result.nodes.iter().map(rename_node).collect::<Result<_, MergeError>>()?;
+    for name in graph.node_names().into_iter().filter(|n| !declared.contains(n)) {
+        let mut node = Node::new(&rename(&name)?);
+        node.label = name;
+        result.nodes.push(node);
+    }
+    result.edges = result.edges.iter().map(rename_edge).collect::<Result<_, MergeError>>()?;
+    for names in result.same_rank.iter_mut() {
+        *names = names.iter().map(|n| rename(n)).collect::<Result<_, MergeError>>()?;
+    }
+    Ok(result)
+}
@@ -0,0 +1,364 @@
+//! Export to [Mermaid](https://mermaid.js.org/) flowcharts.
+//!
+//! ```rust
+//! use dot_graph::{Graph, Kind, Node, Edge, Style, mermaid::Direction};
+//!
+//! let mut graph = Graph::new("G", Kind::Digraph);
+//! graph.add_node(Node::new("a").shape(Some("diamond")));
+//! graph.add_node(Node::new("b"));
+//! graph.add_edge(Edge::new("a", "b", "yes").style(Style::Dashed));
+//!
+//! let flowchart = graph.to_mermaid(Direction::LeftRight);
+//! assert_eq!(flowchart.text,
+//! r#"flowchart LR
+//!     a{"a"}
+//!     b["b"]
+//!     a -.->|"yes"| b
+//! "#);
+//! assert!(flowchart.untranslated.is_empty());
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    arrow::{Arrow, ArrowShape},
+    edge::{Edge, Endpoint},
+    graph::{Graph, Kind},
+    node::Node,
+    style::Style,
+    utils::{number, safe_ids},
+};
+
+/// The direction of the flowchart.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Direction {
+    TopDown,
+    BottomTop,
+    LeftRight,
+    RightLeft,
+}
+
+impl Direction {
+    pub fn as_slice(self) -> &'static str {
+        match self {
+            Direction::TopDown => "TD",
+            Direction::BottomTop => "BT",
+            Direction::LeftRight => "LR",
+            Direction::RightLeft => "RL",
+        }
+    }
+}
+
+/// An attribute which has no equivalent in Mermaid and was left out.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Untranslated {
+    /// The graph, node, subgraph or edge (as `"a -> b"`) it belongs to.
+    pub element: String,
+    /// The DOT name of the attribute, like `arrowhead`.
+    pub attribute: String,
+    pub value: String,
+}
+
+/// The result of `Graph::to_mermaid`.
+#[derive(Clone, Debug)]
+pub struct Flowchart {
+    /// The Mermaid source.
+    pub text: String,
+    /// Everything that could not be translated.
+    pub untranslated: Vec<Untranslated>,
+}
+
+impl Graph {
+    /// Converts the graph into a Mermaid flowchart.
+    ///
+    /// `Kind` decides between arrows and plain links, clusters become
+    /// `subgraph ... end` blocks, dashed and dotted edges become dotted
+    /// links, bold edges thick links and invisible edges `~~~`. Node shapes
+    /// are mapped to the closest Mermaid shape, colors to `classDef`,
+    /// `style` and `linkStyle` lines, and node URLs to `click` lines.
+    /// Node names are used as ids, with `.` and other characters Mermaid
+    /// does not allow replaced by `_`. Edges to a subgraph which is not a
+    /// cluster of the graph, like `a -> {b c}`, link to each of its nodes.
+    pub fn to_mermaid(&self, direction: Direction) -> Flowchart {
+        let mut writer = Writer {
+            kind: self.kind,
+            ids: safe_ids(&self.node_names(), &["end"]),
+            clusters: self.subgraph.iter().map(|s| s.name.clone()).collect(),
+            lines: vec![format!("flowchart {}", direction.as_slice())],
+            styles: vec![],
+            link_count: 0,
+            classes: vec![],
+            clicks: vec![],
+            untranslated: vec![],
+        };
+
+        if !self.url.is_empty() {
+            writer.untranslated("graph", "URL", &self.url);
+        }
+        if self.compound {
+            writer.untranslated("graph", "compound", "true");
+        }
+
+        let mut declared: HashSet<&str> = HashSet::new();
+        for s in self.subgraph.iter() {
+            if s.label.is_empty() {
+                writer.lines.push(format!("    subgraph {} [\" \"]", s.name));
+            } else {
+                writer.lines.push(format!("    subgraph {} [{}]", s.name, text(&s.label)));
+            }
+            for n in s.nodes.iter() {
+                writer.node(n, "        ");
+                declared.insert(&n.name);
+            }
+            for e in s.edges.iter() {
+                writer.edge(e, "        ");
+            }
+            writer.lines.push(String::from("    end"));
+
+            let mut css = vec![];
+            match (s.style, &s.color) {
+                (Style::Filled, Some(c)) => css.push(format!("fill:{}", c)),
+                (_, Some(c)) => css.push(format!("stroke:{}", c)),
+                _ => {}
+            }
+            css.extend(style_css(s.style, &s.name, &mut writer.untranslated));
+            if !css.is_empty() {
+                writer.styles.push(format!("    style {} {}", s.name, css.join(",")));
+            }
+            if !s.url.is_empty() {
+                writer.untranslated(&s.name, "URL", &s.url);
+            }
+        }
+
+        for n in self.nodes.iter() {
+            writer.node(n, "    ");
+            declared.insert(&n.name);
+        }
+        for name in self.node_names() {
+            if !declared.contains(name.as_str()) {
+                writer.node(&Node::implicit(&name), "    ");
+            }
+        }
+        for e in self.edges.iter() {
+            writer.edge(e, "    ");
+        }
+
+        let mut lines = writer.lines;
+        for (i, (css, members)) in writer.classes.iter().enumerate() {
+            lines.push(format!("    classDef class{} {}", i, css));
+            lines.push(format!("    class {} class{}", members.join(","), i));
+        }
+        lines.extend(writer.styles);
+        lines.extend(writer.clicks);
+
+        let mut text = lines.join("\n");
+        text.push('\n');
+        Flowchart { text, untranslated: writer.untranslated }
+    }
+}
+
+struct Writer {
+    kind: Kind,
+    ids: HashMap<String, String>,
+    /// The names of the clusters, which edges can link to.
+    clusters: HashSet<String>,
+    lines: Vec<String>,
+    styles: Vec<String>,
+    link_count: usize,
+    /// `classDef` contents with the ids of the nodes using them.
+    classes: Vec<(String, Vec<String>)>,
+    clicks: Vec<String>,
+    untranslated: Vec<Untranslated>,
+}
+
+impl Writer {
+    fn untranslated(&mut self, element: &str, attribute: &str, value: &str) {
+        self.untranslated.push(Untranslated {
+            element: String::from(element),
+            attribute: String::from(attribute),
+            value: String::from(value),
+        });
+    }
+
+    fn node(&mut self, n: &Node, indent: &str) {
+        let id = self.ids[&n.name].clone();
+        let shape = n.shape.as_deref().unwrap_or("box");
+        let (open, close) = match node_shape(shape) {
+            Some(delimiters) => delimiters,
+            None => {
+                self.untranslated(&n.name, "shape", shape);
+                ("[", "]")
+            }
+        };
+        self.lines.push(format!("{}{}{}{}{}", indent, id, open, text(&n.label), close));
+
+        let mut css = vec![];
+        if let Some(c) = &n.color {
+            css.push(format!("stroke:{}", c));
+            if n.style == Style::Filled {
+                css.push(format!("fill:{}", c));
+            }
+        }
+        css.extend(style_css(n.style, &n.name, &mut self.untranslated));
+        if !css.is_empty() {
+            let css = css.join(",");
+            match self.classes.iter_mut().find(|(c, _)| *c == css) {
+                Some((_, members)) => members.push(id.clone()),
+                None => self.classes.push((css, vec![id.clone()])),
+            }
+        }
+
+        if !n.url.is_empty() {
+            self.clicks.push(format!("    click {} href {}", id, text(&n.url)));
+        }
+    }
+
+    fn edge(&mut self, e: &Edge, indent: &str) {
+        let element = e.endpoints
+            .iter()
+            .map(|p| match p {
+                Endpoint::Node(name) => name.clone(),
+                Endpoint::Subgraph { name, .. } => name.clone(),
+            })
+            .collect::<Vec<String>>()
+            .join(&format!(" {} ", self.kind.edgeop()));
+
+        let head = if self.kind == Kind::Digraph { ">" } else { "" };
+        let start = self.arrow(&e.start_arrow, "", "<", &element, "arrowtail");
+        let end = self.arrow(&e.end_arrow, head, ">", &element, "arrowhead");
+        let link = match e.style {
+            Style::Invisible => String::from("~~~"),
+            Style::Dashed | Style::Dotted => format!("{}-.-{}", start, end),
+            Style::Bold => {
+                if end.is_empty() { format!("{}===", start) } else { format!("{}=={}", start, end) }
+            }
+            Style::None | Style::Solid => {
+                if end.is_empty() { format!("{}---", start) } else { format!("{}--{}", start, end) }
+            }
+            other => {
+                self.untranslated(&element, "style", other.as_slice());
+                if end.is_empty() { format!("{}---", start) } else { format!("{}--{}", start, end) }
+            }
+        };
+        let link = if e.label.is_empty() || e.style == Style::Invisible {
+            link
+        } else {
+            format!("{}|{}|", link, text(&e.label))
+        };
+        if e.style == Style::Invisible && !e.label.is_empty() {
+            self.untranslated(&element, "label", &e.label);
+        }
+
+        // other subgraphs are not in the flowchart, their nodes are linked
+        let linkable = |p: &Endpoint| match p {
+            Endpoint::Node(_) => true,
+            Endpoint::Subgraph { name, .. } => self.clusters.contains(name),
+        };
+        let chains: Vec<Vec<String>> = if e.endpoints.iter().all(linkable) {
+            let chain = e.endpoints
+                .iter()
+                .map(|p| match p {
+                    Endpoint::Node(name) => self.ids[name].clone(),
+                    Endpoint::Subgraph { name, .. } => name.clone(),
+                })
+                .collect();
+            vec![chain]
+        } else {
+            e.node_pairs().into_iter().map(|(from, to)| vec![self.ids[&from].clone(), self.ids[&to].clone()]).collect()
+        };
+        let first = self.link_count;
+        for chain in chains {
+            self.lines.push(format!("{}{}", indent, chain.join(&format!(" {} ", link))));
+            self.link_count += chain.len() - 1;
+        }
+
+        let links: Vec<String> = (first..self.link_count).map(|i| i.to_string()).collect();
+        if let Some(c) = &e.color {
+            self.styles.push(format!("    linkStyle {} stroke:{}", links.join(","), c));
+        }
+
+        if !e.url.is_empty() {
+            self.untranslated(&element, "URL", &e.url);
+        }
+        if !e.label_url.is_empty() {
+            self.untranslated(&element, "labelURL", &e.label_url);
+        }
+        if let Some(p) = e.penwidth {
+            self.untranslated(&element, "penwidth", &number(p));
+        }
+        if let Some(l) = &e.lhead {
+            self.untranslated(&element, "lhead", l);
+        }
+        if let Some(l) = &e.ltail {
+            self.untranslated(&element, "ltail", l);
+        }
+    }
+
+    /// The Mermaid arrow end for `arrow`, `default` being used for the
+    /// default arrow and `normal` for a normal one.
+    fn arrow(&mut self, arrow: &Arrow, default: &str, normal: &str, element: &str, attribute: &str) -> String {
+        if arrow.is_default() {
+            return String::from(default);
+        }
+        match arrow.arrows.as_slice() {
+            [ArrowShape::NoArrow] => String::new(),
+            [ArrowShape::Normal(_, _)] => String::from(normal),
+            [ArrowShape::Dot(_)] => String::from("o"),
+            _ => {
+                self.untranslated(element, attribute, &arrow.to_dot_string());
+                String::from(default)
+            }
+        }
+    }
+}
+
+/// Quotes a label, using Mermaid entity codes for quotes and line breaks
+/// for the DOT escapes `\n`, `\l` and `\r`.
+fn text(label: &str) -> String {
+    let mut s = label.replace('"', "#quot;");
+    for escape in ["\\n", "\\l", "\\r"] {
+        s = s.replace(escape, "<br>");
+    }
+    let s = s.trim_end_matches("<br>").replace('\n', "");
+    format!("\"{}\"", s)
+}
+
+/// Opening and closing delimiters of the Mermaid shape closest to a
+/// Graphviz one.
+fn node_shape(shape: &str) -> Option<(&'static str, &'static str)> {
+    let delimiters = match shape {
+        "box" | "rect" | "rectangle" | "square" | "plaintext" | "plain" | "none" | "note" | "tab" | "folder" => ("[", "]"),
+        "ellipse" | "oval" | "egg" => ("([", "])"),
+        "circle" | "point" => ("((", "))"),
+        "doublecircle" => ("(((", ")))"),
+        "diamond" | "Mdiamond" => ("{", "}"),
+        "hexagon" | "octagon" | "doubleoctagon" | "tripleoctagon" => ("{{", "}}"),
+        "parallelogram" => ("[/", "/]"),
+        "trapezium" => ("[/", "\\]"),
+        "invtrapezium" => ("[\\", "/]"),
+        "cylinder" => ("[(", ")]"),
+        "cds" | "rarrow" => (">", "]"),
+        "Msquare" | "component" => ("[[", "]]"),
+        _ => return None,
+    };
+    Some(delimiters)
+}
+
+/// CSS for a node or subgraph style.
+fn style_css(style: Style, element: &str, untranslated: &mut Vec<Untranslated>) -> Vec<String> {
+    match style {
+        Style::None | Style::Solid | Style::Filled => vec![],
+        Style::Dashed => vec![String::from("stroke-dasharray:5 5")],
+        Style::Dotted => vec![String::from("stroke-dasharray:2 2")],
+        Style::Bold => vec![String::from("stroke-width:3px")],
+        Style::Invisible => vec![String::from("display:none")],
+        other => {
+            untranslated.push(Untranslated {
+                element: String::from(element),
+                attribute: String::from("style"),
+                value: String::from(other.as_slice()),
+            });
+            vec![]
+        }
+    }
+}
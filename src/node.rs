@@ -1,4 +1,4 @@
-/// each node is an index in a vector in the graph.
+// each node is an index in a vector in the graph.
 // pub type Node = usize;
 
 use crate::{
@@ -7,19 +7,33 @@ use crate::{
 };
 
 /// `Graph`'s node
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone)]
 pub struct Node {
     pub name: String,
-    label: String,
-    style: Style,
-    color: Option<String>,
-    shape: Option<String>,
-    url: String
+    pub(crate) label: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) style: Style,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) color: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) shape: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) url: String,
+    /// The position computed by a layout, see `Graph::apply_layout`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) pos: Option<String>
 }
 
 impl Node {
     pub fn new(name: &str) -> Self {
-        Node { name: new_name(name), label: String::from(name), style: Style::None, color: None, shape: None, url: Default::default() }
+        Node { name: new_name(name), label: String::from(name), style: Style::None, color: None, shape: None, url: Default::default(), pos: None }
+    }
+
+    /// The node an edge implies when `name` is not declared, like
+    /// `Node::new` but without checking the name, as `Edge::new` does not.
+    pub(crate) fn implicit(name: &str) -> Self {
+        Node { name: String::from(name), label: String::from(name), style: Style::None, color: None, shape: None, url: Default::default(), pos: None }
     }
 
     pub fn label(&self, label: &str) -> Self {
@@ -45,10 +59,7 @@ impl Node {
 
     pub fn color(&self, color: Option<&str>) -> Self {
         let mut node = self.clone();
-        node.color = match color {
-            Some(c) => Some(String::from(c)),
-            None => None
-        };
+        node.color = color.map(String::from);
         node
     }
 
@@ -97,8 +108,16 @@ impl Node {
             text.push("\"]");
         }
 
+        let pos: String;
+        if let Some(p) = &self.pos {
+            pos = quote_string(p.clone());
+            text.push("[pos=");
+            text.push(&pos);
+            text.push("]");
+        }
+
         text.push(";");
-        return text.into_iter().collect();
+        text.into_iter().collect()
     }
 
 }
@@ -118,16 +137,6 @@ impl Node {
 /// Passing an invalid string (containing spaces, brackets,
 /// quotes, ...) will cause panic.
 fn new_name(name: &str) -> String {
-    let mut chars = name.chars();
-    match chars.next() {
-        Some(c) if is_letter_or_underscore_or_dot(c) => {}
-        _ => panic!("The name of the node should start with a letter or underscore or dot"),
-    }
-    if !chars.all(is_constituent) {
-        panic!("The name of the node should only contain letter/number/underscore/dot")
-    }
-        return String::from(name);
-
     fn is_letter_or_underscore_or_dot(c: char) -> bool {
         in_range('a', c, 'z') || in_range('A', c, 'Z') || c == '_' || c == '.'
     }
@@ -137,4 +146,21 @@ fn new_name(name: &str) -> String {
     fn in_range(low: char, c: char, high: char) -> bool {
         low as usize <= c as usize && c as usize <= high as usize
     }
-}
\ No newline at end of file
+
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if is_letter_or_underscore_or_dot(c) => {}
+        _ => panic!("The name of the node should start with a letter or underscore or dot"),
+    }
+    if !chars.all(is_constituent) {
+        panic!("The name of the node should only contain letter/number/underscore/dot")
+    }
+    String::from(name)
+}
+
+/// Whether `name` would be accepted by `Node::new`.
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '.')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
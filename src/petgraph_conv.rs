@@ -0,0 +1,148 @@
+//! Conversions between `Graph` and the graph types of
+//! [petgraph](https://docs.rs/petgraph), enabled by the `petgraph` feature.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use petgraph::{
+    graph::{IndexType, NodeIndex},
+    graphmap::{GraphMap, NodeTrait},
+    stable_graph::StableGraph,
+    visit::{EdgeRef, GraphProp, IntoEdgeReferences, IntoNodeReferences, NodeIndexable, NodeRef},
+    EdgeType,
+};
+
+use crate::{
+    edge::{Edge, Endpoint},
+    graph::{Graph, Kind},
+    node::Node,
+};
+
+impl Graph {
+    /// Builds a `Graph` from any petgraph graph (`Graph`, `StableGraph`,
+    /// `GraphMap`, ...).
+    ///
+    /// Node `i` is named `N{i}` after its petgraph index, and `Kind` follows
+    /// the directedness of the input. `node_attr` and `edge_attr` receive
+    /// each weight together with the default `Node`/`Edge`, and return it
+    /// with the attributes to render, e.g.
+    /// `|w, node| node.label(w).color(Some("red"))`.
+    pub fn from_petgraph<G, FN, FE>(name: &str, graph: G, node_attr: FN, edge_attr: FE) -> Graph
+    where
+        G: IntoNodeReferences + IntoEdgeReferences + NodeIndexable + GraphProp,
+        FN: Fn(&G::NodeWeight, Node) -> Node,
+        FE: Fn(&G::EdgeWeight, Edge) -> Edge,
+    {
+        let kind = if graph.is_directed() { Kind::Digraph } else { Kind::Graph };
+        let mut result = Graph::new(name, kind);
+
+        for n in graph.node_references() {
+            let node = Node::new(&node_name(graph.to_index(n.id())));
+            result.add_node(node_attr(n.weight(), node));
+        }
+
+        for e in graph.edge_references() {
+            let from = node_name(graph.to_index(e.source()));
+            let to = node_name(graph.to_index(e.target()));
+            result.add_edge(edge_attr(e.weight(), Edge::new(&from, &to, "")));
+        }
+
+        result
+    }
+}
+
+fn node_name(index: usize) -> String {
+    format!("N{}", index)
+}
+
+fn display_node<N: Display>(weight: &N, node: Node) -> Node {
+    node.label(&weight.to_string())
+}
+
+fn display_edge<E: Display>(weight: &E, mut edge: Edge) -> Edge {
+    edge.label(&weight.to_string())
+}
+
+impl<N: Display, E: Display, Ty: EdgeType, Ix: IndexType> From<&petgraph::Graph<N, E, Ty, Ix>> for Graph {
+    /// Converts with node and edge weights as labels.
+    fn from(graph: &petgraph::Graph<N, E, Ty, Ix>) -> Self {
+        Graph::from_petgraph("G", graph, display_node, display_edge)
+    }
+}
+
+impl<N: Display, E: Display, Ty: EdgeType, Ix: IndexType> From<&StableGraph<N, E, Ty, Ix>> for Graph {
+    /// Converts with node and edge weights as labels.
+    fn from(graph: &StableGraph<N, E, Ty, Ix>) -> Self {
+        Graph::from_petgraph("G", graph, display_node, display_edge)
+    }
+}
+
+impl<N: NodeTrait + Display, E: Display, Ty: EdgeType> From<&GraphMap<N, E, Ty>> for Graph {
+    /// Converts with node and edge weights as labels.
+    fn from(graph: &GraphMap<N, E, Ty>) -> Self {
+        Graph::from_petgraph("G", graph, display_node, display_edge)
+    }
+}
+
+impl<Ty: EdgeType> From<&Graph> for petgraph::Graph<Node, Edge, Ty> {
+    /// Converts into a petgraph graph whose node weights are the `Node`s,
+    /// identified by their `name`.
+    ///
+    /// Nodes only referenced by edges get a default `Node`. Edge chains and
+    /// edges to subgraphs are split into one petgraph edge per node pair.
+    fn from(graph: &Graph) -> Self {
+        let mut result = petgraph::Graph::default();
+        let mut indices: HashMap<String, NodeIndex> = HashMap::new();
+
+        let declared: HashMap<&str, &Node> = graph
+            .all_nodes()
+            .into_iter()
+            .map(|n| (n.name.as_str(), n))
+            .collect();
+        for name in graph.node_names() {
+            let node = match declared.get(name.as_str()) {
+                Some(n) => (*n).clone(),
+                None => Node::implicit(&name),
+            };
+            indices.insert(name, result.add_node(node));
+        }
+
+        for e in graph.all_edges() {
+            for (from, to) in e.node_pairs() {
+                result.add_edge(indices[&from], indices[&to], e.with_endpoints(&from, &to));
+            }
+        }
+
+        result
+    }
+}
+
+impl<'a, Ty: EdgeType> From<&'a Graph> for GraphMap<&'a str, Edge, Ty> {
+    /// Converts into a petgraph `GraphMap` keyed by node name.
+    ///
+    /// As `GraphMap` does not allow parallel edges, the last edge between
+    /// two nodes wins.
+    fn from(graph: &'a Graph) -> Self {
+        let mut result = GraphMap::new();
+        for n in graph.all_nodes() {
+            result.add_node(n.name.as_str());
+        }
+        for e in graph.all_edges() {
+            for segment in e.endpoints.windows(2) {
+                for from in endpoint_names(&segment[0]) {
+                    for to in endpoint_names(&segment[1]) {
+                        result.add_edge(from, to, e.with_endpoints(from, to));
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+fn endpoint_names(endpoint: &Endpoint) -> Vec<&str> {
+    match endpoint {
+        Endpoint::Node(name) => vec![name.as_str()],
+        Endpoint::Subgraph { nodes, .. } => nodes.iter().map(|n| n.as_str()).collect(),
+    }
+}
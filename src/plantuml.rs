@@ -0,0 +1,182 @@
+//! Export to [PlantUML](https://plantuml.com/) documents.
+//!
+//! ```rust
+//! use dot_graph::{Graph, Kind, Node, Edge};
+//!
+//! let mut graph = Graph::new("G", Kind::Digraph);
+//! graph.add_node(Node::new("db").shape(Some("cylinder")));
+//! graph.add_edge(Edge::new("app", "db", "reads"));
+//!
+//! assert_eq!(graph.to_plantuml(),
+//! r#"@startuml G
+//! database "db" as db
+//! rectangle "app" as app
+//! app --> db : reads
+//! @enduml
+//! "#);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+    arrow::{Arrow, ArrowShape, Fill},
+    edge::{Edge, Endpoint},
+    graph::{Graph, Kind},
+    node::Node,
+    style::Style,
+    utils::safe_ids,
+};
+
+impl Graph {
+    /// Renders the graph as a PlantUML document.
+    ///
+    /// Nodes become `rectangle`s (or the element closest to their shape),
+    /// clusters become `package`s, or `rectangle`s when they have no label.
+    /// Arrows are mapped to relationships: `normal` to `-->`, `empty`
+    /// (`onormal`) to `--|>`, `diamond` to `--*` and `odiamond` to `--o`;
+    /// other arrows fall back to `-->`. Edge styles and colors go into the
+    /// brackets of the link, like `-[#red,dashed]->`, and edge chains are
+    /// split into one link per pair. Edges to a subgraph which is not a
+    /// cluster of the graph, like `a -> {b c}`, link to each of its nodes.
+    pub fn to_plantuml(&self) -> String {
+        let ids = safe_ids(&self.node_names(), &[]);
+        let mut lines = vec![format!("@startuml {}", self.name)];
+
+        let mut declared: Vec<&str> = vec![];
+        for s in self.subgraph.iter() {
+            let keyword = if s.label.is_empty() { "rectangle" } else { "package" };
+            let mut line = format!("{} {} as {}", keyword, text(&s.label), s.name);
+            if let Some(c) = &s.color {
+                line.push_str(&format!(" {}", color(c)));
+            }
+            if !s.url.is_empty() {
+                line.push_str(&format!(" [[{}]]", s.url));
+            }
+            line.push_str(" {");
+            lines.push(line);
+            for n in s.nodes.iter() {
+                lines.push(format!("  {}", node(n, &ids)));
+                declared.push(&n.name);
+            }
+            lines.push(String::from("}"));
+        }
+        for n in self.nodes.iter() {
+            lines.push(node(n, &ids));
+            declared.push(&n.name);
+        }
+        for name in self.node_names() {
+            if !declared.contains(&name.as_str()) {
+                lines.push(node(&Node::implicit(&name), &ids));
+            }
+        }
+
+        // other subgraphs are not in the document, their nodes are linked
+        let ends = |p: &Endpoint| match p {
+            Endpoint::Subgraph { name, .. } if !self.subgraph.iter().any(|s| &s.name == name) => {
+                p.node_names().into_iter().map(Endpoint::Node).collect()
+            }
+            p => vec![p.clone()],
+        };
+        for e in self.all_edges() {
+            for segment in e.endpoints.windows(2) {
+                for from in ends(&segment[0]) {
+                    for to in ends(&segment[1]) {
+                        lines.push(link(self.kind, e, &from, &to, &ids));
+                    }
+                }
+            }
+        }
+
+        lines.push(String::from("@enduml"));
+        let mut text = lines.join("\n");
+        text.push('\n');
+        text
+    }
+}
+
+fn node(n: &Node, ids: &HashMap<String, String>) -> String {
+    let keyword = match n.shape.as_deref() {
+        Some("ellipse") | Some("oval") => "usecase",
+        Some("circle") | Some("point") => "circle",
+        Some("cylinder") => "database",
+        Some("component") => "component",
+        Some("folder") | Some("tab") => "folder",
+        Some("note") => "card",
+        Some("hexagon") => "hexagon",
+        _ => "rectangle",
+    };
+    let mut line = format!("{} {} as {}", keyword, text(&n.label), ids[&n.name]);
+    if let Some(c) = &n.color {
+        line.push_str(&format!(" {}", color(c)));
+    }
+    if !n.url.is_empty() {
+        line.push_str(&format!(" [[{}]]", n.url));
+    }
+    line
+}
+
+fn link(kind: Kind, e: &Edge, from: &Endpoint, to: &Endpoint, ids: &HashMap<String, String>) -> String {
+    let id = |endpoint: &Endpoint| match endpoint {
+        Endpoint::Node(name) => ids[name].clone(),
+        Endpoint::Subgraph { name, .. } => name.clone(),
+    };
+
+    let mut options = vec![];
+    if let Some(c) = &e.color {
+        options.push(color(c));
+    }
+    match e.style {
+        Style::Dashed => options.push(String::from("dashed")),
+        Style::Dotted => options.push(String::from("dotted")),
+        Style::Bold => options.push(String::from("bold")),
+        Style::Invisible => options.push(String::from("hidden")),
+        _ => {}
+    }
+    let body = if options.is_empty() {
+        String::from("--")
+    } else {
+        format!("-[{}]-", options.join(","))
+    };
+
+    let default_head = if kind == Kind::Digraph { ">" } else { "" };
+    let tail = head(&e.start_arrow, "", true);
+    let head = head(&e.end_arrow, default_head, false);
+
+    let mut line = format!("{} {}{}{} {}", id(from), tail, body, head, id(to));
+    if !e.label.is_empty() {
+        line.push_str(&format!(" : {}", label(&e.label)));
+    }
+    line
+}
+
+/// The PlantUML relationship end for `arrow`; `reversed` is used for the
+/// start of the link, where `>` is written `<`.
+fn head(arrow: &Arrow, default: &str, reversed: bool) -> String {
+    if arrow.is_default() {
+        return String::from(default);
+    }
+    let head = match arrow.arrows.as_slice() {
+        [ArrowShape::NoArrow] => "",
+        [ArrowShape::Normal(Fill::Open, _)] => if reversed { "<|" } else { "|>" },
+        [ArrowShape::Diamond(Fill::Filled, _)] => "*",
+        [ArrowShape::Diamond(Fill::Open, _)] => "o",
+        _ => if reversed { "<" } else { ">" },
+    };
+    String::from(head)
+}
+
+/// PlantUML colors start with `#`, like `#red` or `#FF0000`.
+fn color(c: &str) -> String {
+    if c.starts_with('#') { String::from(c) } else { format!("#{}", c) }
+}
+
+/// A relationship label, with the DOT line breaks `\l` and `\r` turned
+/// into `\n`.
+fn label(l: &str) -> String {
+    l.replace("\\l", "\\n").replace("\\r", "\\n").replace('\n', "")
+}
+
+/// A quoted element name; PlantUML has no escape for `"`.
+fn text(l: &str) -> String {
+    format!("\"{}\"", label(l).replace('"', "'"))
+}
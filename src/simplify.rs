@@ -0,0 +1,211 @@
+//! Fewer edges for the same picture: transitive reduction and merging of
+//! parallel edges.
+//!
+//! ```rust
+//! use dot_graph::{simplify::Multiplicity, Graph, Kind, Edge};
+//!
+//! let mut graph = Graph::new("deps", Kind::Digraph);
+//! graph.add_edge(Edge::chain(&["app", "http", "core"], ""));
+//! graph.add_edge(Edge::new("app", "core", ""));
+//! graph.add_edge(Edge::new("app", "http", ""));
+//! graph.transitive_reduction().unwrap();
+//! graph.merge_parallel_edges(Multiplicity::Penwidth);
+//! assert_eq!(graph.to_dot_string().unwrap(),
+//! r#"digraph deps {
+//!     "app" -> "http"[label=""][penwidth=2];
+//!     "http" -> "core"[label=""];
+//! }
+//! "#);
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    algo::Adjacency,
+    edge::Edge,
+    graph::{Graph, Kind},
+};
+
+/// How `Graph::merge_parallel_edges` shows the number of edges merged.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Multiplicity {
+    /// The different labels joined by `", "`, or the number of edges when
+    /// none had a label.
+    Label,
+    /// The sum of the `penwidth` of the edges, counting 1 for those
+    /// without one.
+    Penwidth,
+}
+
+impl Graph {
+    /// Removes the edges implied by a longer path, like `a -> c` next to
+    /// `a -> b -> c`, and fails with the nodes of a cycle (see
+    /// `algo::find_cycle`) when the graph is not acyclic.
+    ///
+    /// Edges are read as written, also in a `Kind::Graph`. Parallel edges
+    /// stay, see `Graph::merge_parallel_edges`; an edge with more node
+    /// pairs is split when only some of them go.
+    pub fn transitive_reduction(&mut self) -> Result<(), Vec<String>> {
+        let adjacency = Adjacency::new(self, false);
+        if let Some(cycle) = adjacency.cycle(false) {
+            return Err(adjacency.names(cycle));
+        }
+        self.reduce(&adjacency);
+        Ok(())
+    }
+
+    /// Like `Graph::transitive_reduction`, over the strongly connected
+    /// components: an edge between two components goes when another path
+    /// links them, while the edges inside a component all stay. On an
+    /// acyclic graph both are the same.
+    pub fn transitive_reduction_condensed(&mut self) {
+        let adjacency = Adjacency::new(self, false);
+        self.reduce(&adjacency);
+    }
+
+    /// Merges the edges between the same nodes into the first of them, in
+    /// either direction for a `Kind::Graph`, showing their number as
+    /// `multiplicity` says. An edge with more node pairs is split when one
+    /// of them has parallel edges.
+    pub fn merge_parallel_edges(&mut self, multiplicity: Multiplicity) {
+        let undirected = self.kind == Kind::Graph;
+        let key = |from: String, to: String| if undirected && to < from { (to, from) } else { (from, to) };
+        let pairs: Vec<(String, String)> =
+            self.all_edges().into_iter().flat_map(|e| e.node_pairs()).map(|(from, to)| key(from, to)).collect();
+        let mut count: HashMap<&(String, String), usize> = HashMap::new();
+        for pair in pairs.iter() {
+            *count.entry(pair).or_default() += 1;
+        }
+        let split: Vec<bool> = pairs.iter().map(|p| count[p] > 1).collect();
+        self.rewrite_pairs(&vec![true; pairs.len()], &split);
+
+        // the first edge of each pair, by its index once the others are
+        // gone, with the labels and the summed penwidth of all
+        let mut first: HashMap<(String, String), (usize, Vec<String>, f64)> = HashMap::new();
+        let mut kept = 0;
+        for edges in self.subgraph.iter_mut().map(|s| &mut s.edges).chain(std::iter::once(&mut self.edges)) {
+            edges.retain(|e| {
+                let keep = match e.node_pairs().as_slice() {
+                    [(from, to)] => {
+                        let merged = first.entry(key(from.clone(), to.clone())).or_insert((kept, vec![], 0.0));
+                        merged.1.push(e.label.clone());
+                        merged.2 += e.penwidth.unwrap_or(1.0);
+                        merged.0 == kept
+                    }
+                    _ => true,
+                };
+                kept += keep as usize;
+                keep
+            });
+        }
+        let merged: HashMap<usize, (Vec<String>, f64)> =
+            first.into_values().filter(|(_, labels, _)| labels.len() > 1).map(|(i, labels, penwidth)| (i, (labels, penwidth))).collect();
+        let mut index = 0;
+        for edges in self.subgraph.iter_mut().map(|s| &mut s.edges).chain(std::iter::once(&mut self.edges)) {
+            for e in edges.iter_mut() {
+                if let Some((labels, penwidth)) = merged.get(&index) {
+                    match multiplicity {
+                        Multiplicity::Label => {
+                            let mut distinct: Vec<&str> = vec![];
+                            for label in labels.iter().filter(|l| !l.is_empty()) {
+                                if !distinct.contains(&label.as_str()) {
+                                    distinct.push(label);
+                                }
+                            }
+                            e.label = if distinct.is_empty() { labels.len().to_string() } else { distinct.join(", ") };
+                        }
+                        Multiplicity::Penwidth => e.penwidth = Some(*penwidth),
+                    }
+                }
+                index += 1;
+            }
+        }
+    }
+
+    /// Drops the node pairs implied by a path through other strongly
+    /// connected components of `adjacency`.
+    fn reduce(&mut self, adjacency: &Adjacency) {
+        let mut component = vec![0; adjacency.names.len()];
+        let components = adjacency.components();
+        for (i, members) in components.iter().enumerate() {
+            for &member in members {
+                component[member] = i;
+            }
+        }
+        let mut next: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); components.len()];
+        for (node, edges) in adjacency.next.iter().enumerate() {
+            for &(to, _) in edges {
+                if component[node] != component[to] {
+                    next[component[node]].insert(component[to]);
+                }
+            }
+        }
+        // the components each component leads to, as rows of bits filled
+        // in post order, so the rows of the components it leads to are
+        // done before its own
+        let words = components.len().div_ceil(64);
+        let mut reached = vec![0u64; components.len() * words];
+        let mut done = vec![false; components.len()];
+        for start in 0..components.len() {
+            if done[start] {
+                continue;
+            }
+            done[start] = true;
+            let mut calls = vec![(start, next[start].iter())];
+            while let Some((c, to)) = calls.last_mut() {
+                let c = *c;
+                if let Some(&to) = to.next() {
+                    if !done[to] {
+                        done[to] = true;
+                        calls.push((to, next[to].iter()));
+                    }
+                    continue;
+                }
+                calls.pop();
+                for &to in next[c].iter() {
+                    reached[c * words + to / 64] |= 1 << (to % 64);
+                    for word in 0..words {
+                        reached[c * words + word] |= reached[to * words + word];
+                    }
+                }
+            }
+        }
+        let leads = |from: usize, to: usize| reached[from * words + to / 64] & (1 << (to % 64)) != 0;
+
+        let keep: Vec<bool> = self
+            .all_edges()
+            .into_iter()
+            .flat_map(|e| e.node_pairs())
+            .map(|(from, to)| {
+                let (from, to) = (component[adjacency.index[&from]], component[adjacency.index[&to]]);
+                from == to || !next[from].iter().any(|&via| via != to && leads(via, to))
+            })
+            .collect();
+        self.rewrite_pairs(&keep, &vec![false; keep.len()]);
+    }
+
+    /// Keeps the node pairs of `keep`, in `Graph::all_edges` order, in the
+    /// edges they are in, or as edges of their own when an edge loses some
+    /// of its pairs or has one to `split`.
+    pub(crate) fn rewrite_pairs(&mut self, keep: &[bool], split: &[bool]) {
+        let mut pair = 0;
+        for edges in self.subgraph.iter_mut().map(|s| &mut s.edges).chain(std::iter::once(&mut self.edges)) {
+            let mut rewritten: Vec<Edge> = vec![];
+            for edge in edges.drain(..) {
+                let pairs = edge.node_pairs();
+                let range = pair..pair + pairs.len();
+                pair += pairs.len();
+                if keep[range.clone()].iter().all(|k| *k) && (pairs.len() == 1 || !split[range.clone()].iter().any(|s| *s)) {
+                    rewritten.push(edge);
+                    continue;
+                }
+                for ((from, to), keep) in pairs.iter().zip(&keep[range]) {
+                    if *keep {
+                        rewritten.push(edge.with_endpoints(from, to));
+                    }
+                }
+            }
+            *edges = rewritten;
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Counts and metrics of a graph, see `Graph::stats`.
+//!
+//! ```rust
+//! use dot_graph::{Graph, Kind, Edge};
+//!
+//! let mut graph = Graph::new("G", Kind::Digraph);
+//! graph.add_edge(Edge::chain(&["a", "b", "c"], ""));
+//! graph.add_edge(Edge::new("a", "c", ""));
+//! let stats = graph.stats();
+//! assert_eq!(stats.edges, 3);
+//! assert_eq!(stats.longest_path.as_deref(), Some(&["a", "b", "c"].map(String::from)[..]));
+//! assert!(stats.to_string().starts_with("nodes: 3\nedges: 3\n"));
+//! ```
+
+use std::fmt;
+
+use crate::{
+    algo::Adjacency,
+    graph::{Graph, Kind},
+    json::Json,
+};
+
+/// How many nodes the fan-in and fan-out lists of `Graph::stats` have at
+/// most.
+pub const TOP: usize = 10;
+
+/// What `Graph::stats` found. Edges are counted per node pair, so
+/// `a -> b -> c` is two edges.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct Stats {
+    /// All nodes, the ones only referenced by edges too.
+    pub nodes: usize,
+    pub edges: usize,
+    pub clusters: usize,
+    /// How many nodes have each degree, by increasing degree. A degree
+    /// counts the edges at a node, a self loop twice.
+    pub degrees: Vec<(usize, usize)>,
+    /// The edges over the number of edges a simple graph could have, 0
+    /// below two nodes; parallel edges can make it more than 1.
+    pub density: f64,
+    /// How deep subgraphs are nested: 0 or 1, as a `Graph` only holds
+    /// clusters directly and `dot::read` puts nested clusters next to their
+    /// parent. It is kept so that reports compare with tools which nest.
+    pub nesting_depth: usize,
+    /// A path with the most edges, `None` when the graph has a cycle.
+    /// Edges are read as written, also in a `Kind::Graph`.
+    pub longest_path: Option<Vec<String>>,
+    /// The nodes without edges.
+    pub isolated: Vec<String>,
+    /// The nodes with the most incoming edges, as many as asked for, without
+    /// the ones with none. For a `Kind::Graph` it is the degree.
+    pub fan_in: Vec<(String, usize)>,
+    /// The nodes with the most outgoing edges, like `fan_in`.
+    pub fan_out: Vec<(String, usize)>,
+}
+
+impl Graph {
+    /// Counts the nodes, edges and clusters and measures the graph, across
+    /// its subgraphs, with the `TOP` nodes by fan-in and fan-out.
+    pub fn stats(&self) -> Stats {
+        self.stats_with(TOP)
+    }
+
+    /// Like `Graph::stats`, with the `top` nodes by fan-in and fan-out.
+    pub fn stats_with(&self, top: usize) -> Stats {
+        let adjacency = Adjacency::new(self, false);
+        let undirected = self.kind == Kind::Graph;
+        let count = adjacency.names.len();
+        let edges: usize = adjacency.next.iter().map(Vec::len).sum();
+        let degree: Vec<usize> = (0..count).map(|i| adjacency.next[i].len() + adjacency.previous[i].len()).collect();
+
+        let mut degrees: Vec<(usize, usize)> = vec![];
+        let mut sorted = degree.clone();
+        sorted.sort();
+        for d in sorted {
+            match degrees.last_mut() {
+                Some((last, n)) if *last == d => *n += 1,
+                _ => degrees.push((d, 1)),
+            }
+        }
+
+        let pairs = (count * count.saturating_sub(1)) as f64;
+        let density = match (count < 2, undirected) {
+            (true, _) => 0.0,
+            (false, true) => 2.0 * edges as f64 / pairs,
+            (false, false) => edges as f64 / pairs,
+        };
+
+        let most = |by: &dyn Fn(usize) -> usize| -> Vec<(String, usize)> {
+            let mut nodes: Vec<(String, usize)> =
+                (0..count).map(|i| (adjacency.names[i].clone(), by(i))).filter(|(_, n)| *n > 0).collect();
+            // stable, so ties keep the node order
+            nodes.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+            nodes.truncate(top);
+            nodes
+        };
+        let (fan_in, fan_out) = if undirected {
+            (most(&|i| degree[i]), most(&|i| degree[i]))
+        } else {
+            (most(&|i| adjacency.previous[i].len()), most(&|i| adjacency.next[i].len()))
+        };
+
+        Stats {
+            nodes: count,
+            edges,
+            clusters: self.subgraph.len(),
+            degrees,
+            density,
+            nesting_depth: usize::from(!self.subgraph.is_empty()),
+            longest_path: longest_path(self, &adjacency),
+            isolated: adjacency.names((0..count).filter(|&i| degree[i] == 0)),
+            fan_in,
+            fan_out,
+        }
+    }
+}
+
+/// The longest path over a topological order, the earliest one on ties.
+fn longest_path(graph: &Graph, adjacency: &Adjacency) -> Option<Vec<String>> {
+    let order = crate::algo::toposort(graph).ok()?;
+    let count = adjacency.names.len();
+    // the edges of the longest path ending at each node, and where it
+    // comes from
+    let mut length = vec![0; count];
+    let mut from: Vec<Option<usize>> = vec![None; count];
+    for name in order.iter() {
+        let node = adjacency.index[name];
+        for &(to, _) in adjacency.next[node].iter() {
+            if length[node] + 1 > length[to] {
+                length[to] = length[node] + 1;
+                from[to] = Some(node);
+            }
+        }
+    }
+    let Some(end) = (0..count).rev().max_by_key(|&i| length[i]) else {
+        return Some(vec![]);
+    };
+    let mut path = vec![end];
+    while let Some(node) = from[*path.last().unwrap()] {
+        path.push(node);
+    }
+    path.reverse();
+    Some(adjacency.names(path))
+}
+
+impl Stats {
+    /// The stats as a JSON object with the field names, pairs being
+    /// `[degree, count]` or `[name, count]` arrays.
+    pub fn to_json(&self) -> String {
+        let pairs = |pairs: &[(usize, usize)]| {
+            Json::Array(pairs.iter().map(|(a, b)| Json::Array(vec![Json::Number(*a as f64), Json::Number(*b as f64)])).collect())
+        };
+        let named = |pairs: &[(String, usize)]| {
+            Json::Array(pairs.iter().map(|(a, b)| Json::Array(vec![Json::str(a), Json::Number(*b as f64)])).collect())
+        };
+        let names = |names: &[String]| Json::Array(names.iter().map(|n| Json::str(n)).collect());
+        Json::Object(vec![
+            (String::from("nodes"), Json::Number(self.nodes as f64)),
+            (String::from("edges"), Json::Number(self.edges as f64)),
+            (String::from("clusters"), Json::Number(self.clusters as f64)),
+            (String::from("degrees"), pairs(&self.degrees)),
+            (String::from("density"), Json::Number(self.density)),
+            (String::from("nesting_depth"), Json::Number(self.nesting_depth as f64)),
+            (String::from("longest_path"), self.longest_path.as_deref().map_or(Json::Null, names)),
+            (String::from("isolated"), names(&self.isolated)),
+            (String::from("fan_in"), named(&self.fan_in)),
+            (String::from("fan_out"), named(&self.fan_out)),
+        ])
+        .to_pretty_string()
+    }
+}
+
+/// One `name: value` line per field, lists joined by `, ` or `(none)`
+/// when empty.
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let list = |items: Vec<String>| if items.is_empty() { String::from("(none)") } else { items.join(", ") };
+        let named = |pairs: &[(String, usize)]| list(pairs.iter().map(|(n, c)| format!("{} {}", n, c)).collect());
+        writeln!(f, "nodes: {}", self.nodes)?;
+        writeln!(f, "edges: {}", self.edges)?;
+        writeln!(f, "clusters: {}", self.clusters)?;
+        writeln!(f, "degrees: {}", list(self.degrees.iter().map(|(d, n)| format!("{}: {}", d, n)).collect()))?;
+        writeln!(f, "density: {}", significant(self.density))?;
+        writeln!(f, "nesting depth: {}", self.nesting_depth)?;
+        match &self.longest_path {
+            Some(path) if path.is_empty() => writeln!(f, "longest path: (none)")?,
+            Some(path) => writeln!(f, "longest path: {} ({} edges)", path.join(" -> "), path.len() - 1)?,
+            None => writeln!(f, "longest path: (cyclic)")?,
+        }
+        writeln!(f, "isolated: {}", list(self.isolated.clone()))?;
+        writeln!(f, "fan-in: {}", named(&self.fan_in))?;
+        writeln!(f, "fan-out: {}", named(&self.fan_out))
+    }
+}
+
+/// A number with three significant digits, so that the density of a
+/// sparse graph does not show as 0.
+fn significant(n: f64) -> String {
+    if n == 0.0 || !n.is_finite() {
+        return format!("{}", n);
+    }
+    let decimals = (2 - n.abs().log10().floor() as i32).max(0) as usize;
+    let s = format!("{:.*}", decimals, n);
+    let s = if s.contains('.') { s.trim_end_matches('0').trim_end_matches('.') } else { &s };
+    String::from(s)
+}
@@ -1,9 +1,13 @@
 /// The style for a node or edge.
 /// See <http://graphviz.org/doc/info/attrs.html#k:style> for descriptions.
 /// Note that some of these are not valid for edges.
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
 pub enum Style {
+    #[default]
     None,
+    #[cfg_attr(feature = "serde", serde(rename = "invis"))]
     Invisible,
     Solid,
     Dashed,
@@ -32,4 +36,23 @@ impl Style {
             Style::Wedged => "wedged",
         }
     }
-}
\ No newline at end of file
+
+    /// The inverse of `as_slice`; returns `None` for unknown styles.
+    pub fn from_slice(s: &str) -> Option<Style> {
+        let style = match s {
+            "" => Style::None,
+            "invis" => Style::Invisible,
+            "solid" => Style::Solid,
+            "dashed" => Style::Dashed,
+            "dotted" => Style::Dotted,
+            "bold" => Style::Bold,
+            "rounded" => Style::Rounded,
+            "diagonals" => Style::Diagonals,
+            "filled" => Style::Filled,
+            "striped" => Style::Striped,
+            "wedged" => Style::Wedged,
+            _ => return None,
+        };
+        Some(style)
+    }
+}
@@ -6,35 +6,60 @@ use crate::{
 };
 
 /// `Graph`'s subgraph
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone)]
 pub struct Subgraph {
     pub name: String,
-    nodes: Vec<Node>,
-    edges: Vec<Edge>,
-    label: String,
-    style: Style,
-    color: Option<String>,
-    edgeop: String,
-    url: String
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) nodes: Vec<Node>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) edges: Vec<Edge>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) label: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) style: Style,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) color: Option<String>,
+    /// The edge operator of the graph it was added to, not serialized.
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_edgeop"))]
+    pub(crate) edgeop: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) url: String,
+    /// The bounding box computed by a layout, see `Graph::apply_layout`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) bb: Option<String>
+}
+
+#[cfg(feature = "serde")]
+fn default_edgeop() -> String {
+    String::from(Kind::Digraph.edgeop())
 }
 
 impl Subgraph {
     pub fn new(name: &str) -> Self {
-        Subgraph { name: new_name(name), nodes: vec![], edges: vec![], label: String::new(), style: Style::None, color: None, edgeop: String::from(Kind::Digraph.edgeop()), url: Default::default() }
+        Subgraph { name: new_name(name), nodes: vec![], edges: vec![], label: String::new(), style: Style::None, color: None, edgeop: String::from(Kind::Digraph.edgeop()), url: Default::default(), bb: None }
     }
 
-    pub fn add_node(&mut self, node: Node) -> () {
+    pub fn add_node(&mut self, node: Node) {
         self.nodes.push(node);
     }
 
-    pub fn add_nodes(&mut self, nodes: Vec<Node>) -> () {
+    pub fn add_nodes(&mut self, nodes: Vec<Node>) {
         self.nodes.append(&mut nodes.clone());
     }
 
-    pub fn add_edge(&mut self, edge: Edge) -> () {
+    pub fn add_edge(&mut self, edge: Edge) {
         self.edges.push(edge);
     }
 
+    pub(crate) fn node_names(&self) -> Vec<String> {
+        self.nodes.iter().map(|n| n.name.clone()).collect()
+    }
+
+    pub(crate) fn has_compound_edges(&self) -> bool {
+        self.edges.iter().any(|e| e.is_compound())
+    }
+
     pub fn label(&self, label: &str) -> Self {
         let mut subg = self.clone();
         subg.label = String::from(label);
@@ -49,10 +74,7 @@ impl Subgraph {
 
     pub fn color(&self, color: Option<&str>) -> Self {
         let mut subg = self.clone();
-        subg.color = match color {
-            Some(c) => Some(String::from(c)),
-            None => None
-        };
+        subg.color = color.map(String::from);
         subg
     }
 
@@ -72,7 +94,7 @@ impl Subgraph {
         let mut text = vec!["subgraph ", self.name.as_str(), " {\n        "];
 
         let escaped_url: String;
-        if !self.url.is_empty(){
+        if !self.url.is_empty() {
             escaped_url = quote_string(self.url.clone());
             text.push("URL=");
             text.push(escaped_url.as_str());
@@ -97,13 +119,21 @@ impl Subgraph {
             text.push(";\n    ");
         }
 
+        let bb: String;
+        if let Some(b) = &self.bb {
+            bb = quote_string(b.to_string());
+            text.push("    bb=");
+            text.push(&bb);
+            text.push(";\n    ");
+        }
+
         let subgraph_node_names = self.nodes
             .iter()
             .map(|n| n.to_dot_string())
             .collect::<Vec<String>>()
             .join("\n        ");
         // in case push extra change line
-        if self.nodes.len() > 0 {
+        if !self.nodes.is_empty() {
             text.push("    ");
             text.push(&subgraph_node_names);
             text.push("\n    ");
@@ -113,21 +143,18 @@ impl Subgraph {
         let edge_symbol = &self.edgeop;
         let subgraph_edge_strs = self.edges
             .iter()
-            .map(|e| e.to_dot_string(&edge_symbol))
+            .map(|e| e.to_dot_string(edge_symbol))
             .collect::<Vec<String>>()
             .join("\n        ");
         // in case push extra change line
-        if self.edges.len() > 0 {
+        if !self.edges.is_empty() {
             text.push("    ");
             text.push(&subgraph_edge_strs);
             text.push("\n    ");
         }
 
         text.push("}");
-        
-
-        
-        return text.into_iter().collect();
+        text.into_iter().collect()
     }
 }
 
@@ -146,6 +173,16 @@ impl Subgraph {
 /// Passing an invalid string (containing spaces, brackets,
 /// quotes, ...) will cause panic.
 fn new_name(name: &str) -> String {
+    fn is_letter_or_underscore(c: char) -> bool {
+        in_range('a', c, 'z') || in_range('A', c, 'Z') || c == '_'
+    }
+    fn is_constituent(c: char) -> bool {
+        is_letter_or_underscore(c) || in_range('0', c, '9')
+    }
+    fn in_range(low: char, c: char, high: char) -> bool {
+        low as usize <= c as usize && c as usize <= high as usize
+    }
+
     let mut chars = name.chars();
     match chars.next() {
         Some(c) if is_letter_or_underscore(c) => {}
@@ -158,15 +195,10 @@ fn new_name(name: &str) -> String {
     if !name.starts_with("cluster_") {
         panic!("The name of the subgraph should start with \"cluster_\"")
     }
-        return String::from(name);
+    String::from(name)
+}
 
-    fn is_letter_or_underscore(c: char) -> bool {
-        in_range('a', c, 'z') || in_range('A', c, 'Z') || c == '_'
-    }
-    fn is_constituent(c: char) -> bool {
-        is_letter_or_underscore(c) || in_range('0', c, '9')
-    }
-    fn in_range(low: char, c: char, high: char) -> bool {
-        low as usize <= c as usize && c as usize <= high as usize
-    }
-}
\ No newline at end of file
+/// Whether `name` would be accepted by `Subgraph::new`.
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    name.starts_with("cluster_") && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
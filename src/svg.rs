@@ -0,0 +1,479 @@
+//! Drawing a laid out graph as SVG.
+//!
+//! The document has the structure of the SVG output of Graphviz: a `graph`
+//! group holding one group per cluster, node and edge with the classes
+//! `cluster`, `node` and `edge`, a `<title>` with the name of each and
+//! `<a>` elements for URLs. Stylesheets written for `dot -Tsvg` output
+//! apply to it as well.
+//!
+//! ```rust
+//! use dot_graph::{Graph, Kind, Edge, layout::LayoutOptions};
+//!
+//! let mut graph = Graph::new("G", Kind::Digraph);
+//! graph.add_edge(Edge::new("a", "b", ""));
+//! let svg = graph.to_svg(&graph.layered_layout(&LayoutOptions::default()));
+//! assert!(svg.contains(r#"<g id="node1" class="node">"#));
+//! assert!(svg.contains("<title>a&#45;&gt;b</title>"));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+    arrow::{Arrow, ArrowShape, Fill, Side},
+    edge::Edge,
+    graph::{Graph, Kind},
+    layout::{label_lines, LayoutResult, Point},
+    node::Node,
+    style::Style,
+    utils::number as num,
+    xml::escape,
+};
+
+/// The length of an arrow, the diamond and the dot differ.
+const ARROW_LENGTH: f64 = 10.0;
+/// Half the width of an arrow.
+const ARROW_WIDTH: f64 = 3.5;
+/// The font attributes of all text.
+const FONT: &str = "font-family=\"Times,serif\" font-size=\"14.00\"";
+
+impl Graph {
+    /// Draws the graph at the positions of `layout`, as computed by
+    /// `Graph::layered_layout` or `Graph::force_layout`.
+    ///
+    /// Nodes are drawn in their shape, with `style` deciding on the
+    /// outline, filling with `color` (or light grey) and rounded corners;
+    /// invisible elements keep their group but draw nothing. Arrows follow
+    /// the rules of Graphviz: digraphs draw the head, graphs none, and both
+    /// ends are drawn when both are set. The tooltip of a link is the
+    /// label, like in Graphviz when no `tooltip` is given. Edges of the
+    /// layout which are not in the graph, as it was edited since, are
+    /// left out.
+    pub fn to_svg(&self, layout: &LayoutResult) -> String {
+        let declared: HashMap<&str, &Node> = self.all_nodes().into_iter().map(|n| (n.name.as_str(), n)).collect();
+        let edges = self.all_edges();
+        let (width, height) = (num(layout.width), num(layout.height));
+
+        let mut out = vec![
+            String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>"),
+            String::from("<!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\""),
+            String::from(" \"http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd\">"),
+            format!("<svg width=\"{}pt\" height=\"{}pt\"", width, height),
+            format!(
+                " viewBox=\"0.00 0.00 {:.2} {:.2}\" xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\">",
+                layout.width, layout.height
+            ),
+            String::from("<g id=\"graph0\" class=\"graph\">"),
+            format!("<title>{}</title>", title(&self.name)),
+        ];
+        let background = format!(
+            "<polygon fill=\"white\" stroke=\"none\" points=\"0,0 {w},0 {w},{h} 0,{h} 0,0\"/>",
+            w = width, h = height
+        );
+        push_linked(&mut out, "graph0", &self.url, &self.name, vec![background]);
+
+        for (i, c) in layout.clusters.iter().enumerate() {
+            let Some(s) = self.subgraph.iter().find(|s| s.name == c.name) else { continue };
+            let id = format!("clust{}", i + 1);
+            out.push(format!("<!-- {} -->", title(&s.name)));
+            out.push(format!("<g id=\"{}\" class=\"cluster\">", id));
+            out.push(format!("<title>{}</title>", title(&s.name)));
+            let mut body = vec![];
+            if s.style != Style::Invisible {
+                let (left, top) = (c.x - c.width / 2.0, c.y - c.height / 2.0);
+                let (right, bottom) = (left + c.width, top + c.height);
+                let corners = [Point::new(left, top), Point::new(right, top), Point::new(right, bottom), Point::new(left, bottom)];
+                let paint = paint(s.style, &s.color, "black");
+                if s.style == Style::Rounded {
+                    body.push(format!("<path {} d=\"{}\"/>", paint, rounded(&corners)));
+                } else {
+                    body.push(format!("<polygon {} points=\"{}\"/>", paint, points(&corners, true)));
+                }
+                if !s.label.is_empty() {
+                    body.extend(text(&s.label, Point::new(c.x, top + 12.0), c.width));
+                }
+            }
+            push_linked(&mut out, &id, &s.url, &s.label, body);
+            out.push(String::from("</g>"));
+        }
+
+        for (i, n) in layout.nodes.iter().enumerate() {
+            let implicit = Node::implicit(&n.name);
+            let node = declared.get(n.name.as_str()).copied().unwrap_or(&implicit);
+            let id = format!("node{}", i + 1);
+            out.push(format!("<!-- {} -->", title(&n.name)));
+            out.push(format!("<g id=\"{}\" class=\"node\">", id));
+            out.push(format!("<title>{}</title>", title(&n.name)));
+            let mut body = vec![];
+            if node.style != Style::Invisible {
+                let center = Point::new(n.x, n.y);
+                body.extend(shape(node, center, n.width, n.height));
+                if node.shape.as_deref() != Some("point") {
+                    let top = n.y - label_lines(&node.label).len() as f64 * 9.0 + 9.0;
+                    body.extend(text(&node.label, Point::new(n.x, top), n.width));
+                }
+            }
+            push_linked(&mut out, &id, &node.url, &node.label, body);
+            out.push(String::from("</g>"));
+        }
+
+        for (i, e) in layout.edges.iter().enumerate() {
+            let matches = |edge: &&Edge| edge.node_pairs().iter().any(|(from, to)| {
+                (*from == e.from && *to == e.to) || (*from == e.to && *to == e.from)
+            });
+            let Some(edge) = edges.get(e.edge).copied().filter(matches) else {
+                continue;
+            };
+            let name = format!("{}{}{}", e.from, self.kind.edgeop(), e.to);
+            let id = format!("edge{}", i + 1);
+            out.push(format!("<!-- {} -->", title(&name)));
+            out.push(format!("<g id=\"{}\" class=\"edge\">", id));
+            out.push(format!("<title>{}</title>", title(&name)));
+            if edge.style != Style::Invisible {
+                push_linked(&mut out, &id, &edge.url, &edge.label, edge_body(self.kind, edge, &e.points, e.spline));
+                if let Some(pos) = e.label_pos {
+                    let label = text(&edge.label, Point::new(pos.x, pos.y), 0.0);
+                    push_linked(&mut out, &format!("{}-label", id), &edge.label_url, &edge.label, label);
+                }
+            }
+            out.push(String::from("</g>"));
+        }
+
+        out.push(String::from("</g>"));
+        out.push(String::from("</svg>"));
+        let mut svg = out.join("\n");
+        svg.push('\n');
+        svg
+    }
+}
+
+/// Adds `body`, inside `<g id="a_{id}"><a>` if there is a `url`.
+fn push_linked(out: &mut Vec<String>, id: &str, url: &str, tooltip: &str, body: Vec<String>) {
+    if url.is_empty() {
+        out.extend(body);
+        return;
+    }
+    out.push(format!("<g id=\"a_{}\"><a xlink:href=\"{}\" xlink:title=\"{}\">", id, escape(url), escape(tooltip)));
+    out.extend(body);
+    out.push(String::from("</a>"));
+    out.push(String::from("</g>"));
+}
+
+fn point(p: Point) -> String {
+    format!("{},{}", num(p.x), num(p.y))
+}
+
+/// The `points` of a polygon, repeating the first point at the end when
+/// `closed` like Graphviz does.
+fn points(ps: &[Point], closed: bool) -> String {
+    let mut all: Vec<String> = ps.iter().map(|p| point(*p)).collect();
+    if closed && !ps.is_empty() {
+        all.push(point(ps[0]));
+    }
+    all.join(" ")
+}
+
+/// Escapes a title like Graphviz, which also escapes `-`.
+fn title(s: &str) -> String {
+    escape(s).replace('-', "&#45;")
+}
+
+/// The `fill`, `stroke` and stroke attributes for an outline with `style`
+/// and `color`.
+fn paint(style: Style, color: &Option<String>, default: &str) -> String {
+    let stroke = color.as_deref().unwrap_or(default);
+    let fill = match style {
+        Style::Filled | Style::Striped | Style::Wedged => color.as_deref().unwrap_or("lightgrey"),
+        _ => "none",
+    };
+    let mut attributes = format!("fill=\"{}\" stroke=\"{}\"", escape(fill), escape(stroke));
+    attributes.push_str(&stroke_style(style));
+    attributes
+}
+
+fn stroke_style(style: Style) -> String {
+    match style {
+        Style::Dashed => String::from(" stroke-dasharray=\"5,2\""),
+        Style::Dotted => String::from(" stroke-dasharray=\"1,5\""),
+        Style::Bold => String::from(" stroke-width=\"2\""),
+        _ => String::new(),
+    }
+}
+
+/// The lines of `label`, with the first baseline near `top`.
+fn text(label: &str, top: Point, width: f64) -> Vec<String> {
+    label_lines(label)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (line, align))| {
+            let y = top.y + i as f64 * 18.0 + 4.2;
+            let (anchor, x) = match align {
+                'l' => ("start", top.x - width / 2.0 + 8.0),
+                'r' => ("end", top.x + width / 2.0 - 8.0),
+                _ => ("middle", top.x),
+            };
+            format!("<text text-anchor=\"{}\" x=\"{}\" y=\"{}\" {}>{}</text>", anchor, num(x), num(y), FONT, escape(&line))
+        })
+        .collect()
+}
+
+/// The corners of `shape` in a box from -1 to 1, `None` for ellipses and
+/// shapes without an outline.
+fn polygon(shape: &str) -> Option<Vec<(f64, f64)>> {
+    let corners = match shape {
+        "box" | "rect" | "rectangle" | "square" | "Msquare" | "record" | "Mrecord" | "note" | "tab" | "folder"
+        | "component" | "box3d" => vec![(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)],
+        "diamond" | "Mdiamond" => vec![(0.0, -1.0), (1.0, 0.0), (0.0, 1.0), (-1.0, 0.0)],
+        "triangle" => vec![(0.0, -1.0), (1.0, 1.0), (-1.0, 1.0)],
+        "invtriangle" => vec![(-1.0, -1.0), (1.0, -1.0), (0.0, 1.0)],
+        "pentagon" => vec![(0.0, -1.0), (1.0, -0.24), (0.62, 1.0), (-0.62, 1.0), (-1.0, -0.24)],
+        "hexagon" => vec![(-0.5, -1.0), (0.5, -1.0), (1.0, 0.0), (0.5, 1.0), (-0.5, 1.0), (-1.0, 0.0)],
+        "septagon" => vec![(0.0, -1.0), (0.78, -0.62), (0.97, 0.22), (0.43, 1.0), (-0.43, 1.0), (-0.97, 0.22), (-0.78, -0.62)],
+        "octagon" | "doubleoctagon" | "tripleoctagon" => vec![
+            (-0.4, -1.0), (0.4, -1.0), (1.0, -0.4), (1.0, 0.4), (0.4, 1.0), (-0.4, 1.0), (-1.0, 0.4), (-1.0, -0.4),
+        ],
+        "parallelogram" => vec![(-0.6, -1.0), (1.0, -1.0), (0.6, 1.0), (-1.0, 1.0)],
+        "trapezium" => vec![(-0.6, -1.0), (0.6, -1.0), (1.0, 1.0), (-1.0, 1.0)],
+        "invtrapezium" => vec![(-1.0, -1.0), (1.0, -1.0), (0.6, 1.0), (-0.6, 1.0)],
+        "house" => vec![(0.0, -1.0), (1.0, -0.3), (1.0, 1.0), (-1.0, 1.0), (-1.0, -0.3)],
+        "invhouse" => vec![(-1.0, -1.0), (1.0, -1.0), (1.0, 0.3), (0.0, 1.0), (-1.0, 0.3)],
+        "cds" | "rarrow" => vec![(-1.0, -1.0), (0.6, -1.0), (1.0, 0.0), (0.6, 1.0), (-1.0, 1.0)],
+        "larrow" => vec![(-0.6, -1.0), (1.0, -1.0), (1.0, 1.0), (-0.6, 1.0), (-1.0, 0.0)],
+        "star" => vec![
+            (0.0, -1.0), (0.22, -0.31), (0.95, -0.31), (0.36, 0.12), (0.59, 0.81),
+            (0.0, 0.38), (-0.59, 0.81), (-0.36, 0.12), (-0.95, -0.31), (-0.22, -0.31),
+        ],
+        _ => return None,
+    };
+    Some(corners)
+}
+
+/// The outline of a node.
+fn shape(node: &Node, center: Point, width: f64, height: f64) -> Vec<String> {
+    let shape = node.shape.as_deref().unwrap_or("ellipse");
+    let paint = paint(node.style, &node.color, "black");
+    let (a, b) = (width / 2.0, height / 2.0);
+    let at = |corners: &[(f64, f64)], inset: f64| -> Vec<Point> {
+        corners
+            .iter()
+            .map(|(x, y)| Point::new(center.x + x * (a + inset), center.y + y * (b + inset)))
+            .collect()
+    };
+
+    if let Some(corners) = polygon(shape) {
+        let rounded_corners = node.style == Style::Rounded || shape == "Mrecord";
+        let mut outlines = vec![];
+        let copies = match shape {
+            "doubleoctagon" => 2,
+            "tripleoctagon" => 3,
+            _ => 1,
+        };
+        for copy in 0..copies {
+            let ps = at(&corners, -4.0 * copy as f64);
+            let p = if copy == 0 { paint.clone() } else { String::from("fill=\"none\" stroke=\"") + &escape(node.color.as_deref().unwrap_or("black")) + "\"" };
+            if rounded_corners && corners.len() == 4 {
+                outlines.push(format!("<path {} d=\"{}\"/>", p, rounded(&ps)));
+            } else {
+                outlines.push(format!("<polygon {} points=\"{}\"/>", p, points(&ps, true)));
+            }
+        }
+        return outlines;
+    }
+    match shape {
+        "plaintext" | "plain" | "none" => vec![],
+        "underline" => {
+            let ps = at(&[(-1.0, 1.0), (1.0, 1.0)], 0.0);
+            vec![format!("<polyline {} points=\"{}\"/>", paint, points(&ps, false))]
+        }
+        "point" => {
+            let fill = escape(node.color.as_deref().unwrap_or("black"));
+            vec![format!(
+                "<ellipse fill=\"{}\" stroke=\"{}\" cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\"/>",
+                fill, fill, num(center.x), num(center.y), num(a), num(b)
+            )]
+        }
+        "cylinder" => {
+            let r = (b / 5.0).min(8.0);
+            let (left, right, top, bottom) = (center.x - a, center.x + a, center.y - b + r, center.y + b - r);
+            vec![format!(
+                "<path {} d=\"M{l},{t}C{l},{t2} {r},{t2} {r},{t}C{r},{t3} {l},{t3} {l},{t}L{l},{bo}C{l},{b2} {r},{b2} {r},{bo}L{r},{t}\"/>",
+                paint,
+                l = num(left), r = num(right), t = num(top), bo = num(bottom),
+                t2 = num(top - r * 1.33), t3 = num(top + r * 1.33), b2 = num(bottom + r * 1.33)
+            )]
+        }
+        _ => {
+            let mut outlines = vec![format!(
+                "<ellipse {} cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\"/>",
+                paint, num(center.x), num(center.y), num(a), num(b)
+            )];
+            if shape == "doublecircle" {
+                outlines.push(format!(
+                    "<ellipse fill=\"none\" stroke=\"{}\" cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\"/>",
+                    escape(node.color.as_deref().unwrap_or("black")), num(center.x), num(center.y), num(a - 4.0), num(b - 4.0)
+                ));
+            }
+            outlines
+        }
+    }
+}
+
+/// A path around the four `corners` of a box with rounded corners.
+fn rounded(corners: &[Point]) -> String {
+    let r = ((corners[1].x - corners[0].x).abs().min((corners[2].y - corners[1].y).abs()) / 4.0).min(12.0);
+    let (left, top, right, bottom) = (corners[0].x, corners[0].y, corners[2].x, corners[2].y);
+    format!(
+        "M{},{}L{},{}Q{},{} {},{}L{},{}Q{},{} {},{}L{},{}Q{},{} {},{}L{},{}Q{},{} {},{}Z",
+        num(left + r), num(top), num(right - r), num(top), num(right), num(top), num(right), num(top + r),
+        num(right), num(bottom - r), num(right), num(bottom), num(right - r), num(bottom),
+        num(left + r), num(bottom), num(left), num(bottom), num(left), num(bottom - r),
+        num(left), num(top + r), num(left), num(top), num(left + r), num(top)
+    )
+}
+
+/// Which ends of an edge get an arrow, like the `dir` Graphviz derives for
+/// the DOT written by this crate.
+pub(crate) fn arrows(kind: Kind, edge: &Edge) -> (Option<Arrow>, Option<Arrow>) {
+    let both = !edge.start_arrow.is_default() && !edge.end_arrow.is_default();
+    let head = match kind {
+        Kind::Digraph if edge.end_arrow.is_default() => Some(Arrow::normal()),
+        Kind::Digraph => Some(edge.end_arrow.clone()),
+        Kind::Graph if both => Some(edge.end_arrow.clone()),
+        Kind::Graph => None,
+    };
+    let tail = if both { Some(edge.start_arrow.clone()) } else { None };
+    (tail, head)
+}
+
+/// The length an arrow takes from the edge.
+fn arrow_length(arrow: &Arrow) -> f64 {
+    if arrow.arrows.iter().all(|a| *a == ArrowShape::NoArrow) {
+        return 0.0;
+    }
+    arrow.arrows.iter().map(shape_length).sum()
+}
+
+fn shape_length(shape: &ArrowShape) -> f64 {
+    match shape {
+        ArrowShape::Diamond(_, _) => 12.0,
+        ArrowShape::Dot(_) => 8.0,
+        _ => ARROW_LENGTH,
+    }
+}
+
+/// The path and arrows of an edge.
+fn edge_body(kind: Kind, edge: &Edge, path: &[Point], spline: bool) -> Vec<String> {
+    let color = escape(edge.color.as_deref().unwrap_or("black"));
+    let (tail, head) = arrows(kind, edge);
+    let mut path = path.to_vec();
+    if path.is_empty() {
+        return vec![];
+    }
+    let mut body = vec![];
+    let mut drawn = vec![];
+
+    // shorten the path to leave room for the arrows
+    let last = path.len() - 1;
+    for (arrow, end, next) in [(tail, 0, 1.min(last)), (head, last, last.saturating_sub(1))] {
+        let Some(arrow) = arrow else { continue };
+        let length = arrow_length(&arrow);
+        let tip = path[end];
+        let (dx, dy) = (tip.x - path[next].x, tip.y - path[next].y);
+        let distance = (dx * dx + dy * dy).sqrt();
+        if length == 0.0 || distance == 0.0 {
+            continue;
+        }
+        let (ux, uy) = (dx / distance, dy / distance);
+        let moved = length.min(distance * 0.9);
+        path[end] = Point::new(tip.x - ux * moved, tip.y - uy * moved);
+        if spline && path.len() > 2 {
+            path[next] = Point::new(path[next].x - ux * moved, path[next].y - uy * moved);
+        }
+        drawn.push((arrow, tip, Point::new(ux, uy)));
+    }
+
+    let d = if spline && path.len() > 2 {
+        format!("M{}C{}", point(path[0]), points(&path[1..], false))
+    } else {
+        format!("M{}L{}", point(path[0]), points(&path[1..], false))
+    };
+    body.push(format!("<path fill=\"none\" stroke=\"{}\"{} d=\"{}\"/>", color, stroke_style(edge.style), d));
+    for (arrow, tip, direction) in drawn {
+        body.extend(arrow_shapes(&arrow, tip, direction, &color, edge.style));
+    }
+    body
+}
+
+/// Draws the shapes of `arrow` from `tip` backwards against `direction`,
+/// the first shape at the tip.
+fn arrow_shapes(arrow: &Arrow, tip: Point, direction: Point, color: &str, style: Style) -> Vec<String> {
+    let mut out = vec![];
+    let mut offset = 0.0;
+    let stroke = if style == Style::Bold { " stroke-width=\"2\"" } else { "" };
+    for shape in arrow.arrows.iter() {
+        let length = shape_length(shape);
+        // local coordinates: `s` back along the edge, `t` to the left
+        let at = |s: f64, t: f64| {
+            let s = s + offset;
+            Point::new(tip.x - direction.x * s + direction.y * t, tip.y - direction.y * s - direction.x * t)
+        };
+        let fill = |f: Fill| if f == Fill::Open { "none" } else { color };
+        let sided = |side: Side, corners: Vec<(f64, f64)>| -> Vec<Point> {
+            corners
+                .into_iter()
+                .map(|(s, t)| match side {
+                    Side::Left => at(s, t.max(0.0)),
+                    Side::Right => at(s, t.min(0.0)),
+                    Side::Both => at(s, t),
+                })
+                .collect()
+        };
+        let polygon = |f: &str, ps: Vec<Point>| format!("<polygon fill=\"{}\" stroke=\"{}\"{} points=\"{}\"/>", f, color, stroke, points(&ps, true));
+        let line = |from: Point, to: Point| format!("<polyline fill=\"none\" stroke=\"{}\"{} points=\"{}\"/>", color, stroke, points(&[from, to], false));
+        let (w, l) = (ARROW_WIDTH, length);
+        match *shape {
+            ArrowShape::NoArrow => {}
+            ArrowShape::Normal(f, side) => out.push(polygon(fill(f), sided(side, vec![(0.0, 0.0), (l, w), (l, -w)]))),
+            ArrowShape::Inv(f, side) => out.push(polygon(fill(f), sided(side, vec![(0.0, w), (l, 0.0), (0.0, -w)]))),
+            ArrowShape::Box(f, side) => {
+                out.push(polygon(fill(f), sided(side, vec![(0.0, w), (2.0 * w, w), (2.0 * w, -w), (0.0, -w)])));
+                out.push(line(at(2.0 * w, 0.0), at(l, 0.0)));
+            }
+            ArrowShape::Diamond(f, side) => {
+                out.push(polygon(fill(f), sided(side, vec![(0.0, 0.0), (l / 2.0, w), (l, 0.0), (l / 2.0, -w)])))
+            }
+            ArrowShape::Dot(f) => {
+                let c = at(l / 2.0, 0.0);
+                out.push(format!(
+                    "<ellipse fill=\"{}\" stroke=\"{}\"{} cx=\"{}\" cy=\"{}\" rx=\"4\" ry=\"4\"/>",
+                    fill(f), color, stroke, num(c.x), num(c.y)
+                ));
+            }
+            ArrowShape::Vee(side) => out.push(polygon(color, sided(side, vec![(0.0, 0.0), (l, w), (0.6 * l, 0.0), (l, -w)]))),
+            ArrowShape::Crow(side) => {
+                out.push(polygon(color, sided(side, vec![(l, 0.0), (0.0, w), (0.4 * l, 0.0), (0.0, -w)])));
+                out.push(line(at(0.0, 0.0), at(l, 0.0)));
+            }
+            ArrowShape::Tee(side) => {
+                out.push(polygon(color, sided(side, vec![(1.0, 1.5 * w), (3.0, 1.5 * w), (3.0, -1.5 * w), (1.0, -1.5 * w)])));
+                out.push(line(at(0.0, 0.0), at(l, 0.0)));
+            }
+            ArrowShape::Curve(side) | ArrowShape::ICurve(_, side) => {
+                let s = if matches!(shape, ArrowShape::Curve(_)) { 2.0 } else { l - 2.0 };
+                let bulge = if matches!(shape, ArrowShape::Curve(_)) { -4.0 } else { l + 2.0 };
+                let (from, to) = match side {
+                    Side::Left => (at(s - (s - bulge) / 2.0, 0.0), at(s, 1.5 * w)),
+                    Side::Right => (at(s - (s - bulge) / 2.0, 0.0), at(s, -1.5 * w)),
+                    Side::Both => (at(s, -1.5 * w), at(s, 1.5 * w)),
+                };
+                let control = at(bulge, 0.0);
+                out.push(line(at(0.0, 0.0), at(l, 0.0)));
+                out.push(format!(
+                    "<path fill=\"none\" stroke=\"{}\"{} d=\"M{}Q{} {}\"/>",
+                    color, stroke, point(from), point(control), point(to)
+                ));
+            }
+        }
+        offset += length;
+    }
+    out
+}
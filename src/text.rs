@@ -0,0 +1,912 @@
+//! Drawing graphs as text, for terminals and logs.
+//!
+//! `Graph::to_text` puts the nodes of `Graph::layered_layout` into boxes,
+//! draws edges as lines with arrows between the ranks and clusters as
+//! frames around their nodes.
+//!
+//! ```rust
+//! use dot_graph::{Graph, Kind, Edge, text::TextOptions};
+//!
+//! let mut graph = Graph::new("G", Kind::Digraph);
+//! graph.add_edge(Edge::new("a", "b", ""));
+//! graph.add_edge(Edge::new("a", "c", ""));
+//!
+//! assert_eq!(graph.to_text(&TextOptions::default()),
+//! r#"    ╭───╮
+//!     │ a │
+//!     ╰┬─┬╯
+//!   ┌──┘ └──┐
+//!   ▼       ▼
+//! ╭───╮   ╭───╮
+//! │ b │   │ c │
+//! ╰───╯   ╰───╯
+//! "#);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+    arrow::{Arrow, ArrowShape},
+    graph::Graph,
+    layout::{is_boxed, label_lines, LayoutOptions, LayoutResult},
+    node::Node,
+    style::Style,
+    svg::arrows,
+};
+
+/// Settings of `Graph::to_text`.
+#[derive(Clone, Debug)]
+pub struct TextOptions {
+    /// The number of columns the drawing should fit in. Node labels are
+    /// shortened to fit; when even that is not enough, as with too many
+    /// nodes on one rank, the drawing is wider and labels are kept.
+    pub max_width: usize,
+    /// Draw with `+`, `-`, `|` and `v` instead of box-drawing characters,
+    /// for terminals without Unicode.
+    pub ascii: bool,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions { max_width: 80, ascii: false }
+    }
+}
+
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+/// The characters of a box: the corners (top left, top right, bottom
+/// left, bottom right), the lines, and the junctions where an edge leaves
+/// it downwards, upwards, to the right and to the left.
+#[derive(Copy, Clone)]
+struct Border {
+    corners: [char; 4],
+    horizontal: char,
+    vertical: char,
+    junctions: [char; 4],
+}
+
+impl Border {
+    fn new(chars: &str) -> Border {
+        let c: Vec<char> = chars.chars().collect();
+        Border { corners: [c[0], c[1], c[2], c[3]], horizontal: c[4], vertical: c[5], junctions: [c[6], c[7], c[8], c[9]] }
+    }
+
+    fn of(style: Style, boxed: bool, ascii: bool) -> Option<Border> {
+        let chars = match style {
+            Style::Invisible => return None,
+            _ if ascii => "++++-|++++",
+            Style::Bold => "┏┓┗┛━┃┰┸┠┨",
+            Style::Dashed | Style::Dotted => "┌┐└┘╌╎┬┴├┤",
+            Style::Rounded => "╭╮╰╯─│┬┴├┤",
+            _ if boxed => "┌┐└┘─│┬┴├┤",
+            _ => "╭╮╰╯─│┬┴├┤",
+        };
+        Some(Border::new(chars))
+    }
+}
+
+/// A node, or a point where an edge crosses a rank.
+struct Item {
+    x: f64,
+    rank: usize,
+    /// The label, empty for the points of edges.
+    lines: Vec<(String, char)>,
+    cluster: Option<usize>,
+    node: bool,
+    border: Option<Border>,
+}
+
+impl Item {
+    /// The width in columns, with labels cut to `cap` characters.
+    fn width(&self, cap: usize) -> i64 {
+        if !self.node {
+            return 1;
+        }
+        let chars = self.lines.iter().map(|(l, _)| l.chars().count()).max().unwrap_or(0).max(1);
+        chars.min(cap) as i64 + 4
+    }
+}
+
+/// Where an edge meets an item.
+#[derive(Copy, Clone)]
+struct End {
+    item: usize,
+    col: i64,
+    arrow: bool,
+}
+
+/// The part of an edge between two ranks, in the gap above rank `gap`.
+/// The first end is on the upper rank, except for edges within a rank
+/// (`flat`), which leave and enter the rank from above.
+struct Hop {
+    gap: usize,
+    ends: [End; 2],
+    flat: bool,
+    track: Option<usize>,
+}
+
+impl Hop {
+    /// The columns of lines going up from the track, and down from it.
+    fn verticals(&self) -> (Vec<i64>, Vec<i64>) {
+        if self.flat {
+            (vec![], vec![self.ends[0].col, self.ends[1].col])
+        } else {
+            (vec![self.ends[0].col], vec![self.ends[1].col])
+        }
+    }
+}
+
+/// The rows of the gap above a rank.
+#[derive(Default)]
+struct Gap {
+    start: usize,
+    end: usize,
+    up: bool,
+    frame_bottom: Option<usize>,
+    tracks: usize,
+    frame_top: Option<usize>,
+}
+
+/// Where the label of an edge goes.
+enum Anchor {
+    Hops(Vec<usize>),
+    Beside(usize, usize),
+    Loop(usize),
+}
+
+/// The ranks of a layout, by the `y` of their nodes.
+struct Ranks(Vec<f64>);
+
+impl Ranks {
+    fn new(layout: &LayoutResult) -> Ranks {
+        let mut levels: Vec<f64> = layout.nodes.iter().map(|n| n.y).collect();
+        levels.sort_by(f64::total_cmp);
+        levels.dedup_by(|a, b| (*a - *b).abs() < 0.5);
+        Ranks(levels)
+    }
+
+    /// The rank closest to `y`.
+    fn of(&self, y: f64) -> usize {
+        let levels = &self.0;
+        (0..levels.len()).min_by(|&a, &b| (levels[a] - y).abs().total_cmp(&(levels[b] - y).abs())).unwrap_or(0)
+    }
+
+    fn count(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// The edges split up by how they are drawn.
+#[derive(Default)]
+struct Routes {
+    hops: Vec<Hop>,
+    /// Lines between neighbors on a rank: the left and the right item, and
+    /// whether there is an arrow on each side.
+    besides: Vec<(usize, usize, bool, bool)>,
+    /// Nodes with an edge to themselves, and whether it has an arrow.
+    loops: Vec<(usize, bool)>,
+    labels: Vec<(String, Anchor)>,
+}
+
+/// The columns of the items, once `Graph::to_text` shortened the labels
+/// it had to.
+struct Columns {
+    lefts: Vec<i64>,
+    widths: Vec<i64>,
+    /// The columns the items need.
+    width: i64,
+}
+
+impl Columns {
+    fn center(&self, item: usize) -> i64 {
+        self.lefts[item] + self.widths[item] / 2
+    }
+
+    fn right(&self, item: usize) -> i64 {
+        self.lefts[item] + self.widths[item] - 1
+    }
+}
+
+/// The frame of a cluster, from its first to its last rank.
+struct Frame {
+    cluster: usize,
+    first: usize,
+    last: usize,
+    left: i64,
+    right: i64,
+}
+
+/// The rows of the ranks.
+struct Rows {
+    tops: Vec<usize>,
+    heights: Vec<usize>,
+    count: usize,
+}
+
+impl Graph {
+    /// Draws the graph as text, top down like `Graph::layered_layout`,
+    /// with nodes as boxes (rounded for ellipses), edges as lines and
+    /// clusters as frames. Arrows follow the rules of `Graph::to_svg`,
+    /// edge labels are written next to their edge where there is room.
+    pub fn to_text(&self, options: &TextOptions) -> String {
+        let layout = self.layered_layout(&LayoutOptions { splines: false, ..Default::default() });
+        if layout.nodes.is_empty() {
+            return String::new();
+        }
+        let ranks = Ranks::new(&layout);
+        let mut items = node_items(self, &layout, &ranks, options.ascii);
+        let mut routes = route(self, &layout, &ranks, &mut items);
+        let columns = columns(self, &items, &routes.loops, ranks.count(), options.max_width);
+        attach(&mut routes.hops, &items, &columns);
+        let mut gaps = tracks(&mut routes.hops, ranks.count());
+        let frames = frames(self, &layout, &items, &columns);
+        let rows = rows(&mut gaps, &items, &routes.hops, &frames, ranks.count());
+
+        let drawing = Drawing { graph: self, items, routes, columns, gaps, frames, rows, ascii: options.ascii };
+        let width = drawing.columns.width.max(options.max_width as i64) as usize;
+        let mut canvas = Canvas { cells: vec![vec![Cell::default(); width]; drawing.rows.count] };
+        drawing.frames(&mut canvas);
+        drawing.nodes(&mut canvas);
+        drawing.hops(&mut canvas);
+        drawing.besides(&mut canvas);
+        drawing.loops(&mut canvas);
+        drawing.cluster_labels(&mut canvas);
+        drawing.edge_labels(&mut canvas);
+        canvas.render(options.ascii)
+    }
+}
+
+/// The nodes of `layout` as items, in the same order.
+fn node_items(graph: &Graph, layout: &LayoutResult, ranks: &Ranks, ascii: bool) -> Vec<Item> {
+    let declared: HashMap<&str, &Node> = graph.all_nodes().into_iter().map(|n| (n.name.as_str(), n)).collect();
+    let mut cluster_of: HashMap<&str, usize> = HashMap::new();
+    for (c, s) in graph.subgraph.iter().enumerate() {
+        for n in s.nodes.iter() {
+            cluster_of.entry(n.name.as_str()).or_insert(c);
+        }
+    }
+    layout
+        .nodes
+        .iter()
+        .map(|n| {
+            let (label, style, shape) = match declared.get(n.name.as_str()) {
+                Some(d) => (d.label.as_str(), d.style, d.shape.as_deref()),
+                None => (n.name.as_str(), Style::None, None),
+            };
+            Item {
+                x: n.x,
+                rank: ranks.of(n.y),
+                lines: label_lines(label),
+                cluster: cluster_of.get(n.name.as_str()).copied(),
+                node: true,
+                border: Border::of(style, is_boxed(shape), ascii),
+            }
+        })
+        .collect()
+}
+
+/// Splits the edges of `layout` into hops between ranks, adding an item
+/// where an edge crosses a rank, lines between neighbors and loops.
+fn route(graph: &Graph, layout: &LayoutResult, ranks: &Ranks, items: &mut Vec<Item>) -> Routes {
+    let all_edges = graph.all_edges();
+    let index: HashMap<&str, usize> = layout.nodes.iter().enumerate().map(|(i, n)| (n.name.as_str(), i)).collect();
+    let mut routes = Routes::default();
+    let mut flats = vec![];
+    for e in layout.edges.iter() {
+        let edge = all_edges[e.edge];
+        if edge.style == Style::Invisible {
+            continue;
+        }
+        let (tail, head) = arrows(graph.kind, edge);
+        let (tail, head) = (drawn(tail), drawn(head));
+        let (from, to) = (index[e.from.as_str()], index[e.to.as_str()]);
+        let label: Vec<String> = label_lines(&edge.label).into_iter().map(|(l, _)| l).collect();
+        let label = label.join(" ");
+        if from == to {
+            routes.loops.push((from, head || tail));
+            routes.labels.push((label, Anchor::Loop(from)));
+        } else if items[from].rank == items[to].rank {
+            flats.push((from, to, tail, head, label));
+        } else {
+            let cluster = if items[from].cluster == items[to].cluster { items[from].cluster } else { None };
+            let mut path = vec![from];
+            for p in e.points[1..e.points.len() - 1].iter() {
+                items.push(Item { x: p.x, rank: ranks.of(p.y), lines: vec![], cluster, node: false, border: None });
+                path.push(items.len() - 1);
+            }
+            path.push(to);
+            let first = routes.hops.len();
+            for (i, w) in path.windows(2).enumerate() {
+                let a = End { item: w[0], col: 0, arrow: i == 0 && tail };
+                let b = End { item: w[1], col: 0, arrow: i == path.len() - 2 && head };
+                let ends = if items[w[0]].rank < items[w[1]].rank { [a, b] } else { [b, a] };
+                routes.hops.push(Hop { gap: items[ends[1].item].rank, ends, flat: false, track: None });
+            }
+            routes.labels.push((label, Anchor::Hops((first..routes.hops.len()).collect())));
+        }
+    }
+    // edges within a rank go straight to a neighbor, and over the top of
+    // the nodes in between otherwise
+    for (from, to, tail, head, label) in flats {
+        let (low, high) = (items[from].x.min(items[to].x), items[from].x.max(items[to].x));
+        let between = items.iter().any(|i| i.rank == items[from].rank && i.x > low && i.x < high);
+        if between {
+            let ends = [End { item: from, col: 0, arrow: tail }, End { item: to, col: 0, arrow: head }];
+            routes.hops.push(Hop { gap: items[from].rank, ends, flat: true, track: None });
+            routes.labels.push((label, Anchor::Hops(vec![routes.hops.len() - 1])));
+        } else {
+            // edges between the same neighbors share one line
+            let (l, r, left, right) = if items[from].x < items[to].x { (from, to, tail, head) } else { (to, from, head, tail) };
+            match routes.besides.iter_mut().find(|b| b.0 == l && b.1 == r) {
+                Some(b) => {
+                    b.2 |= left;
+                    b.3 |= right;
+                }
+                None => routes.besides.push((l, r, left, right)),
+            }
+            routes.labels.push((label, Anchor::Beside(l, r)));
+        }
+    }
+    routes
+}
+
+/// Scales the points of the layout to columns, keeping the items of a rank
+/// apart and nodes outside of the frames of other clusters. Labels are
+/// only cut when that makes the drawing fit in `max_width`.
+fn columns(graph: &Graph, items: &[Item], loops: &[(usize, bool)], rank_count: usize, max_width: usize) -> Columns {
+    let mut pairs: Vec<(usize, usize, f64)> = vec![];
+    for r in 0..rank_count {
+        let mut rank: Vec<usize> = (0..items.len()).filter(|&i| items[i].rank == r).collect();
+        rank.sort_by(|&a, &b| items[a].x.total_cmp(&items[b].x));
+        for w in rank.windows(2) {
+            let (a, b) = (&items[w[0]], &items[w[1]]);
+            let frames = if a.cluster != b.cluster { a.cluster.is_some() as u8 + b.cluster.is_some() as u8 } else { 0 };
+            pairs.push((w[0], w[1], 3.0 + 2.0 * frames as f64));
+        }
+    }
+    for c in 0..graph.subgraph.len() {
+        let members: Vec<usize> = (0..items.len()).filter(|&i| items[i].node && items[i].cluster == Some(c)).collect();
+        let Some(first) = members.iter().map(|&m| items[m].rank).min() else { continue };
+        let last = members.iter().map(|&m| items[m].rank).max().unwrap_or(first);
+        for (n, item) in items.iter().enumerate() {
+            if !item.node || item.cluster == Some(c) || item.rank < first || item.rank > last {
+                continue;
+            }
+            if members.iter().all(|&m| items[m].x > item.x) {
+                pairs.extend(members.iter().map(|&m| (n, m, 5.0)));
+            } else if members.iter().all(|&m| items[m].x < item.x) {
+                pairs.extend(members.iter().map(|&m| (m, n, 5.0)));
+            }
+        }
+    }
+    let place = |cap: usize| -> (Vec<i64>, i64, i64) {
+        let scale = pairs
+            .iter()
+            .filter(|(a, b, _)| items[*b].x - items[*a].x > 0.01)
+            .map(|&(a, b, extra)| ((items[a].width(cap) + items[b].width(cap)) as f64 / 2.0 + extra) / (items[b].x - items[a].x))
+            .fold(0.0, f64::max);
+        let lefts: Vec<i64> = items.iter().map(|i| (i.x * scale).round() as i64 - i.width(cap) / 2).collect();
+        let (mut low, mut high) = (i64::MAX, i64::MIN);
+        for (i, item) in items.iter().enumerate() {
+            let margin = if item.cluster.is_some() && item.node { 2 } else { 0 };
+            low = low.min(lefts[i] - margin);
+            high = high.max(lefts[i] + item.width(cap) - 1 + margin);
+        }
+        for &(n, _) in loops.iter() {
+            high = high.max(lefts[n] + items[n].width(cap) + 2);
+        }
+        (lefts, low, high)
+    };
+    let longest = items.iter().map(|i| i.width(usize::MAX) as usize).max().unwrap_or(5) - 4;
+    let fits = |cap: usize| {
+        let (_, low, high) = place(cap);
+        high - low < max_width as i64
+    };
+    let cap = if fits(longest) || !fits(1) {
+        longest
+    } else {
+        let (mut low, mut high) = (1, longest);
+        while low < high {
+            let middle = (low + high).div_ceil(2);
+            if fits(middle) { low = middle } else { high = middle - 1 }
+        }
+        low
+    };
+    let (lefts, low, high) = place(cap);
+    Columns {
+        lefts: lefts.iter().map(|l| l - low).collect(),
+        widths: items.iter().map(|i| i.width(cap)).collect(),
+        width: high - low + 1,
+    }
+}
+
+/// Spreads the ends of the hops over the borders of the nodes, by the
+/// position of their other end.
+fn attach(hops: &mut [Hop], items: &[Item], columns: &Columns) {
+    let mut attached: HashMap<(usize, bool), Vec<(usize, usize)>> = HashMap::new();
+    for (h, hop) in hops.iter().enumerate() {
+        for (e, end) in hop.ends.iter().enumerate() {
+            attached.entry((end.item, hop.flat || e == 1)).or_default().push((h, e));
+        }
+    }
+    for ((item, _), mut ends) in attached {
+        let other = |&(h, e): &(usize, usize)| items[hops[h].ends[1 - e].item].x;
+        ends.sort_by(|a, b| other(a).total_cmp(&other(b)));
+        let inner = columns.widths[item] - 2;
+        let count = ends.len() as i64;
+        for (i, (h, e)) in ends.into_iter().enumerate() {
+            hops[h].ends[e].col = if items[item].node {
+                columns.lefts[item] + 1 + (2 * i as i64 + 1) * inner / (2 * count)
+            } else {
+                columns.center(item)
+            };
+        }
+    }
+}
+
+/// Puts the horizontal lines of the hops on tracks in their gap; a hop
+/// goes above another where its lines would cross that one's horizontal
+/// line.
+fn tracks(hops: &mut [Hop], rank_count: usize) -> Vec<Gap> {
+    let mut gaps: Vec<Gap> = (0..=rank_count).map(|_| Gap::default()).collect();
+    for (g, gap) in gaps.iter_mut().enumerate() {
+        let mut waiting: Vec<usize> = (0..hops.len())
+            .filter(|&h| hops[h].gap == g && (hops[h].flat || hops[h].ends[0].col != hops[h].ends[1].col))
+            .collect();
+        waiting.sort_by_key(|&h| hops[h].ends[0].col.min(hops[h].ends[1].col));
+        let mut tracks: Vec<Vec<(i64, i64)>> = vec![];
+        while !waiting.is_empty() {
+            let above = |a: usize, b: usize| {
+                let span = |h: &Hop| (h.ends[0].col.min(h.ends[1].col), h.ends[0].col.max(h.ends[1].col));
+                let ((a_low, a_high), (b_low, b_high)) = (span(&hops[a]), span(&hops[b]));
+                let (up, _) = hops[a].verticals();
+                let (_, down) = hops[b].verticals();
+                a != b
+                    && (up.iter().any(|&c| b_low <= c && c <= b_high) || down.iter().any(|&c| a_low <= c && c <= a_high))
+            };
+            let next = waiting.iter().position(|&b| !waiting.iter().any(|&a| above(a, b))).unwrap_or(0);
+            let h = waiting.remove(next);
+            let lowest = (0..hops.len())
+                .filter(|&a| hops[a].gap == g && above(a, h))
+                .filter_map(|a| hops[a].track.map(|t| t + 1))
+                .max()
+                .unwrap_or(0);
+            let (lo, hi) = (hops[h].ends[0].col.min(hops[h].ends[1].col), hops[h].ends[0].col.max(hops[h].ends[1].col));
+            let track = (lowest..)
+                .find(|&t| t >= tracks.len() || tracks[t].iter().all(|&(l, r)| r + 1 < lo || hi + 1 < l))
+                .unwrap_or(lowest);
+            if track >= tracks.len() {
+                tracks.resize(track + 1, vec![]);
+            }
+            tracks[track].push((lo, hi));
+            hops[h].track = Some(track);
+        }
+        gap.tracks = tracks.len();
+        gap.up = hops.iter().any(|h| h.gap == g && !h.flat && h.ends[0].arrow);
+    }
+    gaps
+}
+
+/// The frames of the clusters of `layout` with nodes.
+fn frames(graph: &Graph, layout: &LayoutResult, items: &[Item], columns: &Columns) -> Vec<Frame> {
+    layout
+        .clusters
+        .iter()
+        .filter_map(|l| {
+            let cluster = graph.subgraph.iter().position(|s| s.name == l.name)?;
+            let members: Vec<usize> = (0..items.len()).filter(|&i| items[i].node && items[i].cluster == Some(cluster)).collect();
+            let first = members.iter().map(|&m| items[m].rank).min()?;
+            let last = members.iter().map(|&m| items[m].rank).max()?;
+            let left = members.iter().map(|&m| columns.lefts[m]).min()? - 2;
+            let right = members.iter().map(|&m| columns.right(m)).max()? + 2;
+            Some(Frame { cluster, first, last, left, right })
+        })
+        .collect()
+}
+
+/// Gives the ranks and the rows of the gaps between them their rows: the
+/// arrows going up, the bottoms of frames, the tracks, the tops of frames
+/// and the arrows going down.
+fn rows(gaps: &mut [Gap], items: &[Item], hops: &[Hop], frames: &[Frame], rank_count: usize) -> Rows {
+    let heights: Vec<usize> = (0..rank_count)
+        .map(|r| items.iter().filter(|i| i.node && i.rank == r).map(|i| i.lines.len() + 2).max().unwrap_or(3))
+        .collect();
+    let mut tops = vec![0; rank_count];
+    let mut row = 0;
+    for (g, gap) in gaps.iter_mut().enumerate() {
+        gap.start = row;
+        row += gap.up as usize;
+        if g > 0 && frames.iter().any(|f| f.last == g - 1) {
+            gap.frame_bottom = Some(row);
+            row += 1;
+        }
+        row += gap.tracks;
+        if frames.iter().any(|f| f.first == g) {
+            gap.frame_top = Some(row);
+            row += 1;
+        }
+        if hops.iter().any(|h| h.gap == g) || (g > 0 && g < rank_count) {
+            row += 1;
+        }
+        gap.end = row;
+        if g < rank_count {
+            tops[g] = row;
+            row += heights[g];
+        }
+    }
+    Rows { tops, heights, count: row }
+}
+
+/// Everything placed, to be drawn on a `Canvas`.
+struct Drawing<'a> {
+    graph: &'a Graph,
+    items: Vec<Item>,
+    routes: Routes,
+    columns: Columns,
+    gaps: Vec<Gap>,
+    frames: Vec<Frame>,
+    rows: Rows,
+    ascii: bool,
+}
+
+impl Drawing<'_> {
+    /// The arrows pointing down, up, right and left.
+    fn arrows(&self) -> [char; 4] {
+        if self.ascii { ['v', '^', '>', '<'] } else { ['▼', '▲', '►', '◄'] }
+    }
+
+    /// The row of the horizontal line of `hop`, if it has one.
+    fn track(&self, hop: &Hop) -> Option<usize> {
+        let gap = &self.gaps[hop.gap];
+        hop.track.map(|t| gap.start + gap.up as usize + gap.frame_bottom.is_some() as usize + t)
+    }
+
+    /// The row in the middle of the rank of `item`.
+    fn middle(&self, item: usize) -> usize {
+        let rank = self.items[item].rank;
+        self.rows.tops[rank] + self.rows.heights[rank] / 2
+    }
+
+    /// Ends a line at the `border` row of an item, with an arrow at `row`
+    /// or a junction on the border. `side` is 0 for the bottom and 1 for
+    /// the top.
+    fn arrive(&self, canvas: &mut Canvas, end: End, row: usize, border: usize, side: usize, arrow: char) {
+        if end.arrow {
+            canvas.text(row, end.col, arrow);
+        } else if let Some(b) = self.items[end.item].border {
+            canvas.text(border, end.col, b.junctions[side]);
+        }
+    }
+
+    fn frames(&self, canvas: &mut Canvas) {
+        for f in self.frames.iter() {
+            let s = &self.graph.subgraph[f.cluster];
+            let (Some(top), Some(bottom)) = (self.gaps[f.first].frame_top, self.gaps[f.last + 1].frame_bottom) else { continue };
+            let Some(border) = Border::of(s.style, true, self.ascii) else { continue };
+            for col in f.left..=f.right {
+                canvas.frame(top, col, border.horizontal);
+                canvas.frame(bottom, col, border.horizontal);
+            }
+            for r in top..=bottom {
+                canvas.frame(r, f.left, border.vertical);
+                canvas.frame(r, f.right, border.vertical);
+            }
+            canvas.frame(top, f.left, border.corners[0]);
+            canvas.frame(top, f.right, border.corners[1]);
+            canvas.frame(bottom, f.left, border.corners[2]);
+            canvas.frame(bottom, f.right, border.corners[3]);
+        }
+    }
+
+    /// The boxes of the nodes with their labels, and the lines of edges
+    /// crossing a rank.
+    fn nodes(&self, canvas: &mut Canvas) {
+        let (lefts, widths) = (&self.columns.lefts, &self.columns.widths);
+        for (i, item) in self.items.iter().enumerate() {
+            let (top, height) = (self.rows.tops[item.rank], self.rows.heights[item.rank]);
+            if !item.node {
+                for r in top..top + height {
+                    canvas.line(r, self.columns.center(i), UP | DOWN);
+                }
+                continue;
+            }
+            let Some(border) = item.border else { continue };
+            let (left, right, bottom) = (lefts[i], self.columns.right(i), top + height - 1);
+            for col in left..=right {
+                canvas.text(top, col, border.horizontal);
+                canvas.text(bottom, col, border.horizontal);
+                for r in top + 1..bottom {
+                    canvas.text(r, col, ' ');
+                }
+            }
+            for r in top + 1..bottom {
+                canvas.text(r, left, border.vertical);
+                canvas.text(r, right, border.vertical);
+            }
+            canvas.text(top, left, border.corners[0]);
+            canvas.text(top, right, border.corners[1]);
+            canvas.text(bottom, left, border.corners[2]);
+            canvas.text(bottom, right, border.corners[3]);
+            let first = top + 1 + (height - 2 - item.lines.len()) / 2;
+            for (r, (line, align)) in item.lines.iter().enumerate() {
+                let line = cut(line, (widths[i] - 4) as usize, self.ascii);
+                let length = line.chars().count() as i64;
+                let col = match align {
+                    'l' => left + 2,
+                    'r' => right - 1 - length,
+                    _ => left + (widths[i] - length) / 2,
+                };
+                canvas.write(first + r, col, &line);
+            }
+        }
+    }
+
+    /// The lines of edges between ranks, and over a rank.
+    fn hops(&self, canvas: &mut Canvas) {
+        let arrow = self.arrows();
+        for hop in self.routes.hops.iter() {
+            let gap = &self.gaps[hop.gap];
+            let track = self.track(hop);
+            let [a, b] = hop.ends;
+            let (toward_b, toward_a) = if a.col < b.col { (RIGHT, LEFT) } else { (LEFT, RIGHT) };
+            if hop.flat {
+                let track = track.unwrap_or(gap.start);
+                canvas.line(track, a.col, DOWN | toward_b);
+                canvas.line(track, b.col, DOWN | toward_a);
+                for col in a.col.min(b.col) + 1..a.col.max(b.col) {
+                    canvas.line(track, col, LEFT | RIGHT);
+                }
+                for end in [a, b] {
+                    for r in track + 1..gap.end {
+                        canvas.line(r, end.col, UP | DOWN);
+                    }
+                    self.arrive(canvas, end, gap.end - 1, self.rows.tops[hop.gap], 1, arrow[0]);
+                }
+                continue;
+            }
+            match track {
+                Some(track) => {
+                    for r in gap.start..track {
+                        canvas.line(r, a.col, UP | DOWN);
+                    }
+                    canvas.line(track, a.col, UP | toward_b);
+                    for col in a.col.min(b.col) + 1..a.col.max(b.col) {
+                        canvas.line(track, col, LEFT | RIGHT);
+                    }
+                    canvas.line(track, b.col, DOWN | toward_a);
+                    for r in track + 1..gap.end {
+                        canvas.line(r, b.col, UP | DOWN);
+                    }
+                }
+                None => {
+                    for r in gap.start..gap.end {
+                        canvas.line(r, a.col, UP | DOWN);
+                    }
+                }
+            }
+            let upper = self.items[a.item].rank;
+            self.arrive(canvas, a, gap.start, self.rows.tops[upper] + self.rows.heights[upper] - 1, 0, arrow[1]);
+            self.arrive(canvas, b, gap.end - 1, self.rows.tops[hop.gap], 1, arrow[0]);
+        }
+    }
+
+    /// The lines between neighbors on a rank.
+    fn besides(&self, canvas: &mut Canvas) {
+        let arrow = self.arrows();
+        for &(l, r, left_arrow, right_arrow) in self.routes.besides.iter() {
+            let row = self.middle(l);
+            let (from, to) = (self.columns.right(l) + 1, self.columns.lefts[r] - 1);
+            if from > to {
+                continue;
+            }
+            for col in from..=to {
+                canvas.line(row, col, LEFT | RIGHT);
+            }
+            if left_arrow {
+                canvas.text(row, from, arrow[3]);
+            } else if let Some(border) = self.items[l].border {
+                canvas.text(row, from - 1, border.junctions[2]);
+            }
+            if right_arrow {
+                canvas.text(row, to, arrow[2]);
+            } else if let Some(border) = self.items[r].border {
+                canvas.text(row, to + 1, border.junctions[3]);
+            }
+        }
+    }
+
+    /// The edges of nodes to themselves, on their top right corner.
+    fn loops(&self, canvas: &mut Canvas) {
+        let arrow = self.arrows();
+        for &(n, with_arrow) in self.routes.loops.iter() {
+            let (top, right) = (self.rows.tops[self.items[n].rank], self.columns.right(n));
+            if let Some(border) = self.items[n].border {
+                canvas.text(top + 1, right, border.junctions[2]);
+            }
+            canvas.line(top + 1, right + 1, LEFT | RIGHT);
+            canvas.line(top + 1, right + 2, UP | LEFT);
+            canvas.line(top, right + 2, DOWN | LEFT);
+            if with_arrow {
+                canvas.text(top, right + 1, arrow[3]);
+            } else {
+                canvas.line(top, right + 1, LEFT | RIGHT);
+            }
+        }
+    }
+
+    /// The labels of clusters, in their frame where no edge crosses it.
+    fn cluster_labels(&self, canvas: &mut Canvas) {
+        for f in self.frames.iter() {
+            let Some(top) = self.gaps[f.first].frame_top else { continue };
+            let s = &self.graph.subgraph[f.cluster];
+            let label: Vec<String> = label_lines(&s.label).into_iter().map(|(l, _)| l).collect();
+            let label = cut(&label.join(" "), (f.right - f.left - 5).max(0) as usize, self.ascii);
+            if label.is_empty() || s.style == Style::Invisible {
+                continue;
+            }
+            let length = label.chars().count() as i64;
+            let col = (f.left + 2..f.right - length - 1)
+                .find(|&col| (col..col + length + 2).all(|c| canvas.cell(top, c).is_some_and(|c| c.lines == 0)))
+                .unwrap_or(f.left + 2);
+            canvas.frame(top, col, ' ');
+            canvas.frame(top, col + length + 1, ' ');
+            for (i, ch) in label.chars().enumerate() {
+                canvas.frame(top, col + 1 + i as i64, ch);
+            }
+        }
+    }
+
+    /// The labels of edges, at the first place next to their edge with
+    /// room for them.
+    fn edge_labels(&self, canvas: &mut Canvas) {
+        for (label, anchor) in self.routes.labels.iter() {
+            if label.is_empty() {
+                continue;
+            }
+            let length = label.chars().count() as i64;
+            let mut places: Vec<(usize, i64)> = vec![];
+            match anchor {
+                Anchor::Hops(hs) => {
+                    for &h in hs {
+                        let hop = &self.routes.hops[h];
+                        let gap = &self.gaps[hop.gap];
+                        let track = self.track(hop);
+                        for r in gap.start..gap.end {
+                            let col = match track {
+                                Some(t) if r == t => continue,
+                                Some(t) if r < t && !hop.flat => hop.ends[0].col,
+                                Some(t) if r < t => continue,
+                                _ => hop.ends[1].col,
+                            };
+                            places.push((r, col + 2));
+                            places.push((r, col - 1 - length));
+                        }
+                    }
+                }
+                &Anchor::Beside(l, r) => {
+                    let row = self.middle(l);
+                    let (from, to) = (self.columns.right(l) + 1, self.columns.lefts[r] - 1);
+                    let col = from + (to - from + 1 - length) / 2;
+                    places.push((row - 1, col));
+                    places.push((row + 1, col));
+                }
+                &Anchor::Loop(n) => {
+                    let (top, right) = (self.rows.tops[self.items[n].rank], self.columns.right(n));
+                    places.push((top + 1, right + 4));
+                    places.push((top, right + 4));
+                }
+            }
+            if let Some(&(r, col)) = places.iter().find(|&&(r, col)| (col - 1..=col + length).all(|c| canvas.is_free(r, c))) {
+                canvas.write(r, col, label);
+            }
+        }
+    }
+}
+
+/// Whether an arrow shows anything.
+fn drawn(arrow: Option<Arrow>) -> bool {
+    arrow.is_some_and(|a| a.arrows.iter().any(|s| *s != ArrowShape::NoArrow))
+}
+
+/// `text` cut to `length` characters, ending with an ellipsis if it was
+/// longer.
+fn cut(text: &str, length: usize, ascii: bool) -> String {
+    if text.chars().count() <= length {
+        return String::from(text);
+    }
+    if length == 0 {
+        return String::new();
+    }
+    let mut cut: String = text.chars().take(length - 1).collect();
+    cut.push(if ascii { '~' } else { '…' });
+    cut
+}
+
+#[derive(Copy, Clone, Default)]
+struct Cell {
+    /// The directions of the lines through the cell.
+    lines: u8,
+    text: Option<char>,
+    frame: Option<char>,
+}
+
+/// The drawing, in which text covers lines and lines cover frames.
+struct Canvas {
+    cells: Vec<Vec<Cell>>,
+}
+
+impl Canvas {
+    fn cell(&mut self, row: usize, col: i64) -> Option<&mut Cell> {
+        self.cells.get_mut(row)?.get_mut(usize::try_from(col).ok()?)
+    }
+
+    fn line(&mut self, row: usize, col: i64, directions: u8) {
+        if let Some(cell) = self.cell(row, col) {
+            cell.lines |= directions;
+        }
+    }
+
+    fn text(&mut self, row: usize, col: i64, c: char) {
+        if let Some(cell) = self.cell(row, col) {
+            cell.text = Some(c);
+        }
+    }
+
+    fn write(&mut self, row: usize, col: i64, text: &str) {
+        for (i, c) in text.chars().enumerate() {
+            self.text(row, col + i as i64, c);
+        }
+    }
+
+    fn frame(&mut self, row: usize, col: i64, c: char) {
+        if let Some(cell) = self.cell(row, col) {
+            cell.frame = Some(c);
+        }
+    }
+
+    fn is_free(&mut self, row: usize, col: i64) -> bool {
+        self.cell(row, col).is_some_and(|c| c.lines == 0 && c.text.is_none() && c.frame.is_none())
+    }
+
+    fn render(&self, ascii: bool) -> String {
+        let mut out = String::new();
+        for row in self.cells.iter() {
+            let line: String = row
+                .iter()
+                .map(|cell| match (cell.text, cell.lines, cell.frame) {
+                    (Some(c), _, _) => c,
+                    (None, 0, Some(c)) => c,
+                    (None, 0, None) => ' ',
+                    (None, lines, _) => line_char(lines, ascii),
+                })
+                .collect();
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn line_char(lines: u8, ascii: bool) -> char {
+    let vertical = lines & (LEFT | RIGHT) == 0;
+    let horizontal = lines & (UP | DOWN) == 0;
+    if ascii {
+        return if vertical { '|' } else if horizontal { '-' } else { '+' };
+    }
+    match lines {
+        _ if vertical => '│',
+        _ if horizontal => '─',
+        l if l == DOWN | RIGHT => '┌',
+        l if l == DOWN | LEFT => '┐',
+        l if l == UP | RIGHT => '└',
+        l if l == UP | LEFT => '┘',
+        l if l == UP | DOWN | RIGHT => '├',
+        l if l == UP | DOWN | LEFT => '┤',
+        l if l == DOWN | LEFT | RIGHT => '┬',
+        l if l == UP | LEFT | RIGHT => '┴',
+        _ => '┼',
+    }
+}
@@ -1,3 +1,164 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::layout::label_lines;
+
 pub fn quote_string(s: String) -> String {
     format!("\"{}\"", s)
-}
\ No newline at end of file
+}
+
+/// Plain text escaped the way the model holds labels, as in DOT: `"` and
+/// `\` are escaped and line breaks become `\n`.
+pub fn escape_label(text: &str) -> String {
+    let mut label = String::new();
+    for c in text.chars() {
+        match c {
+            '"' | '\\' => {
+                label.push('\\');
+                label.push(c);
+            }
+            '\n' => label.push_str("\\n"),
+            c => label.push(c),
+        }
+    }
+    label
+}
+
+/// The plain text of a label, with a line break for each of `\n`, `\l`
+/// and `\r`, for formats without the escapes of DOT.
+pub fn unescape_label(label: &str) -> String {
+    let lines: Vec<String> = label_lines(label).into_iter().map(|(line, _)| line).collect();
+    lines.join("\n")
+}
+
+/// DOT-escaped text with the quotes that are not escaped yet escaped, and
+/// a backslash at the end too, so that it can be quoted.
+pub fn escape_quotes(text: &str) -> String {
+    let mut escaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                escaped.push(c);
+                escaped.push(chars.next().unwrap_or('\\'));
+            }
+            '"' => escaped.push_str("\\\""),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// DOT-escaped text with `\"` unescaped, the only escape Graphviz takes
+/// out of the attributes it reads; the others stay.
+pub fn unescape_quotes(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('"') => unescaped.push('"'),
+            next => {
+                unescaped.push(c);
+                unescaped.extend(next);
+            }
+        }
+    }
+    unescaped
+}
+
+/// A number the way Graphviz writes them, with at most two decimals.
+pub fn number(n: f64) -> String {
+    let s = format!("{:.2}", n);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { String::from("0") } else { String::from(s) }
+}
+
+/// Ids for node names in formats which only allow letters, digits and `_`
+/// in ids or have `reserved` keywords: other characters, like `.` or
+/// spaces, are replaced by `_` and a suffix is added where needed to keep
+/// the ids unique.
+pub fn safe_ids(names: &[String], reserved: &[&str]) -> HashMap<String, String> {
+    let mut ids = HashMap::new();
+    let mut taken: HashSet<String> = names.iter().cloned().collect();
+    for name in names {
+        let is_reserved = reserved.contains(&name.as_str());
+        let is_safe = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        if name.chars().all(is_safe) && !name.is_empty() && !is_reserved {
+            ids.insert(name.clone(), name.clone());
+            continue;
+        }
+        let base = if is_reserved { format!("{}_", name) } else { name.replace(|c: char| !is_safe(c), "_") };
+        let mut id = base.clone();
+        let mut i = 1;
+        while taken.contains(&id) {
+            id = format!("{}{}", base, i);
+            i += 1;
+        }
+        taken.insert(id.clone());
+        ids.insert(name.clone(), id);
+    }
+    ids
+}
+
+/// The red, green and blue components of a Graphviz color: `#rrggbb`,
+/// `#rrggbbaa`, `H,S,V` with values from 0 to 1, or one of the common
+/// color names. Other names give `None`.
+pub fn rgb(color: &str) -> Option<(u8, u8, u8)> {
+    let color = color.trim();
+    if let Some(hex) = color.strip_prefix('#') {
+        if hex.len() != 6 && hex.len() != 8 {
+            return None;
+        }
+        let component = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some((component(0)?, component(2)?, component(4)?));
+    }
+    let hsv: Vec<&str> = color.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty()).collect();
+    if hsv.len() == 3 {
+        let hsv: Vec<f64> = hsv.iter().map(|s| s.parse::<f64>().ok()).collect::<Option<_>>()?;
+        return Some(hsv_to_rgb(hsv[0], hsv[1], hsv[2]));
+    }
+    let rgb = match color.to_lowercase().as_str() {
+        "black" => (0, 0, 0),
+        "white" => (255, 255, 255),
+        "red" => (255, 0, 0),
+        "green" => (0, 255, 0),
+        "blue" => (0, 0, 255),
+        "yellow" => (255, 255, 0),
+        "cyan" => (0, 255, 255),
+        "magenta" => (255, 0, 255),
+        "orange" => (255, 165, 0),
+        "purple" => (160, 32, 240),
+        "pink" => (255, 192, 203),
+        "brown" => (165, 42, 42),
+        "gold" => (255, 215, 0),
+        "navy" => (0, 0, 128),
+        "gray" | "grey" => (192, 192, 192),
+        "lightgray" | "lightgrey" => (211, 211, 211),
+        "darkgray" | "darkgrey" => (169, 169, 169),
+        "lightblue" => (173, 216, 230),
+        "darkgreen" => (0, 100, 0),
+        "lightyellow" => (255, 255, 224),
+        _ => return None,
+    };
+    Some(rgb)
+}
+
+fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
+    let h = (h.clamp(0.0, 1.0) * 6.0) % 6.0;
+    let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+    let f = h - h.floor();
+    let (p, q, t) = (v * (1.0 - s), v * (1.0 - s * f), v * (1.0 - s * (1.0 - f)));
+    let (r, g, b) = match h as u8 {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    };
+    let byte = |x: f64| (x * 255.0).round() as u8;
+    (byte(r), byte(g), byte(b))
+}
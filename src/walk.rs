@@ -0,0 +1,181 @@
+//! Rendering of user data structures without copying them into a `Graph`,
+//! in the same spirit as `Labeller`/`GraphWalk` of dot-rust.
+
+use std::borrow::Cow;
+use std::io;
+use std::io::prelude::*;
+
+use crate::{
+    arrow::Arrow,
+    edge::Edge,
+    graph::{indent, writeln, Kind},
+    node::Node,
+    style::Style,
+    subgraph::Subgraph,
+};
+
+/// Nodes of a `GraphWalk`, either borrowed from or built by the walker.
+pub type Nodes<'a, N> = Cow<'a, [N]>;
+
+/// Edges of a `GraphWalk`, either borrowed from or built by the walker.
+pub type Edges<'a, E> = Cow<'a, [E]>;
+
+/// Describes how the nodes `N` and edges `E` of a graph are rendered.
+///
+/// Only `graph_id` and `node_id` are required, every other method falls
+/// back to what `Node::new`/`Edge::new` would render. The ids follow the
+/// same rules as `Node::new`, and cluster names the ones of `Subgraph::new`.
+pub trait Labeller<N, E> {
+    /// The name of the graph.
+    fn graph_id(&self) -> String;
+
+    /// Whether `digraph` or `graph` is rendered.
+    fn kind(&self) -> Kind {
+        Kind::Digraph
+    }
+
+    /// The name of node `n`, which must be unique in the graph.
+    fn node_id(&self, n: &N) -> String;
+
+    fn node_label(&self, n: &N) -> String {
+        self.node_id(n)
+    }
+
+    fn node_style(&self, _n: &N) -> Style {
+        Style::None
+    }
+
+    fn node_color(&self, _n: &N) -> Option<String> {
+        None
+    }
+
+    fn node_shape(&self, _n: &N) -> Option<String> {
+        None
+    }
+
+    fn node_url(&self, _n: &N) -> String {
+        String::new()
+    }
+
+    /// The name of the cluster node `n` is drawn in, if any.
+    fn node_cluster(&self, _n: &N) -> Option<String> {
+        None
+    }
+
+    fn edge_label(&self, _e: &E) -> String {
+        String::new()
+    }
+
+    fn edge_style(&self, _e: &E) -> Style {
+        Style::None
+    }
+
+    fn edge_color(&self, _e: &E) -> Option<String> {
+        None
+    }
+
+    fn edge_start_arrow(&self, _e: &E) -> Arrow {
+        Arrow::default()
+    }
+
+    fn edge_end_arrow(&self, _e: &E) -> Arrow {
+        Arrow::default()
+    }
+
+    fn edge_url(&self, _e: &E) -> String {
+        String::new()
+    }
+
+    fn cluster_label(&self, _cluster: &str) -> String {
+        String::new()
+    }
+
+    fn cluster_style(&self, _cluster: &str) -> Style {
+        Style::None
+    }
+
+    fn cluster_color(&self, _cluster: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Lists the nodes and edges of a graph, and the endpoints of each edge.
+pub trait GraphWalk<'a, N: Clone + 'a, E: Clone + 'a> {
+    fn nodes(&'a self) -> Nodes<'a, N>;
+
+    fn edges(&'a self) -> Edges<'a, E>;
+
+    fn source(&'a self, edge: &E) -> N;
+
+    fn target(&'a self, edge: &E) -> N;
+}
+
+/// Renders `g` into the writer `w` in DOT syntax, exactly as the `Graph`
+/// built from the same nodes, edges and clusters would be rendered.
+///
+/// Clusters come first in order of first appearance, then the nodes
+/// outside any cluster, then the edges; nodes and edges are written one
+/// by one as they are walked.
+pub fn render_walk<'a, N, E, G, W>(g: &'a G, w: &mut W) -> io::Result<()>
+where
+    N: Clone + 'a,
+    E: Clone + 'a,
+    G: Labeller<N, E> + GraphWalk<'a, N, E>,
+    W: Write,
+{
+    let kind = g.kind();
+    writeln(w, &[kind.keyword(), " ", g.graph_id().as_str(), " {"])?;
+
+    let nodes = g.nodes();
+    let mut clusters: Vec<(String, Vec<&N>)> = vec![];
+    let mut unclustered: Vec<&N> = vec![];
+    for n in nodes.iter() {
+        match g.node_cluster(n) {
+            Some(name) => match clusters.iter_mut().find(|(c, _)| *c == name) {
+                Some((_, members)) => members.push(n),
+                None => clusters.push((name, vec![n])),
+            },
+            None => unclustered.push(n),
+        }
+    }
+
+    for (name, members) in clusters.iter() {
+        let mut subgraph = Subgraph::new(name)
+            .label(&g.cluster_label(name))
+            .style(g.cluster_style(name))
+            .color(g.cluster_color(name).as_deref())
+            .edgeop(kind.edgeop());
+        for n in members {
+            subgraph.add_node(walk_node(g, n));
+        }
+        indent(w)?;
+        writeln(w, &[subgraph.to_dot_string().as_str()])?;
+    }
+
+    for n in unclustered {
+        indent(w)?;
+        writeln(w, &[walk_node(g, n).to_dot_string().as_str()])?;
+    }
+
+    for e in g.edges().iter() {
+        let edge = Edge::new(&g.node_id(&g.source(e)), &g.node_id(&g.target(e)), &g.edge_label(e))
+            .style(g.edge_style(e))
+            .color(g.edge_color(e).as_deref())
+            .start_arrow(g.edge_start_arrow(e))
+            .end_arrow(g.edge_end_arrow(e))
+            .url(g.edge_url(e));
+        indent(w)?;
+        writeln(w, &[edge.to_dot_string(kind.edgeop()).as_str()])?;
+    }
+
+    writeln(w, &["}"])
+}
+
+fn walk_node<N, E, G: Labeller<N, E>>(g: &G, n: &N) -> Node {
+    Node::new(&g.node_id(n))
+        .label(&g.node_label(n))
+        .style(g.node_style(n))
+        .color(g.node_color(n).as_deref())
+        .shape(g.node_shape(n).as_deref())
+        .url(g.node_url(n))
+}
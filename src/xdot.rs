@@ -0,0 +1,317 @@
+//! Reading the positions Graphviz computed from its `plain` and `xdot`
+//! output, and writing them to the graph as `pos` and `bb`.
+
+use std::{collections::HashMap, io};
+
+use crate::{
+    dot::{self, Attribute, Operand, Statement, Target},
+    graph::{Graph, Kind},
+    layout::{ClusterLayout, EdgeLayout, LayoutResult, NodeLayout, Point},
+    node::Node,
+    utils::number,
+};
+
+/// Points per inch, the unit of sizes in Graphviz.
+const POINTS: f64 = 72.0;
+
+impl Graph {
+    /// Reads the output of `dot -Tplain` (or `-Tplain-ext`) for this graph.
+    ///
+    /// The result uses the coordinates of `LayoutResult`, in points from
+    /// the top left corner. Edges are matched to `Graph::all_edges` by their
+    /// nodes in order; their `points` are the spline control points of
+    /// Graphviz, which end at the arrowheads. `plain` has no clusters.
+    pub fn layout_from_plain(&self, plain: &str) -> io::Result<LayoutResult> {
+        let mut pairs = Pairs::new(self);
+        let mut height = 0.0;
+        let mut result = LayoutResult { width: 0.0, height: 0.0, nodes: vec![], edges: vec![], clusters: vec![] };
+        for (i, line) in plain.lines().enumerate() {
+            let fields = fields(line).ok_or_else(|| invalid(&format!("unterminated string in line {}", i + 1)))?;
+            let at = |k: usize| -> io::Result<f64> {
+                let field = fields.get(k).ok_or_else(|| invalid(&format!("missing field in line {}", i + 1)))?;
+                field.parse().map_err(|_| invalid(&format!("invalid number {:?} in line {}", field, i + 1)))
+            };
+            match fields.first().map(String::as_str) {
+                Some("graph") => {
+                    result.width = at(2)? * POINTS;
+                    height = at(3)?;
+                    result.height = height * POINTS;
+                }
+                Some("node") => result.nodes.push(NodeLayout {
+                    name: fields.get(1).cloned().ok_or_else(|| invalid(&format!("missing field in line {}", i + 1)))?,
+                    x: at(2)? * POINTS,
+                    y: (height - at(3)?) * POINTS,
+                    width: at(4)? * POINTS,
+                    height: at(5)? * POINTS,
+                }),
+                Some("edge") => {
+                    let (from, to) = (fields.get(1).cloned().unwrap_or_default(), fields.get(2).cloned().unwrap_or_default());
+                    let count = at(3)? as usize;
+                    let mut points = vec![];
+                    for k in 0..count {
+                        points.push(Point::new(at(4 + 2 * k)? * POINTS, (height - at(5 + 2 * k)?) * POINTS));
+                    }
+                    let rest = 4 + 2 * count;
+                    // the label comes before style and color, which are the last two
+                    let label_pos = if fields.len() >= rest + 5 {
+                        Some(Point::new(at(fields.len() - 4)? * POINTS, (height - at(fields.len() - 3)?) * POINTS))
+                    } else {
+                        None
+                    };
+                    let edge = pairs.take(&from, &to)?;
+                    result.edges.push(EdgeLayout { edge, from, to, points, spline: true, label_pos });
+                }
+                Some("stop") => break,
+                _ => {}
+            }
+        }
+        Ok(result)
+    }
+
+    /// Reads the output of `dot -Txdot` (or `-Tdot`) for this graph, like
+    /// `Graph::layout_from_plain`, with the boxes of the clusters from their
+    /// `bb`. Edge paths run from tip to tip of the arrows.
+    pub fn layout_from_xdot(&self, xdot: &str) -> io::Result<LayoutResult> {
+        let dot = Positions::read(xdot)?;
+        let bb = numbers(dot.attributes.get("bb").map(String::as_str).unwrap_or("0,0,0,0"))?;
+        if bb.len() != 4 {
+            return Err(invalid("bb of the graph should have four numbers"));
+        }
+        let top = bb[3];
+        let flip = |x: f64, y: f64| Point::new(x - bb[0], top - y);
+        let point = |text: &str| -> io::Result<Point> {
+            match numbers(text)?.as_slice() {
+                [x, y] => Ok(flip(*x, *y)),
+                _ => Err(invalid(&format!("invalid point {:?}", text))),
+            }
+        };
+
+        let mut result = LayoutResult { width: bb[2] - bb[0], height: bb[3] - bb[1], nodes: vec![], edges: vec![], clusters: vec![] };
+        for (name, attributes) in dot.nodes.iter() {
+            let Some(pos) = attributes.get("pos") else { continue };
+            let center = point(pos.trim_end_matches('!'))?;
+            let size = |key: &str| -> io::Result<f64> {
+                attributes.get(key).map_or(Ok(0.0), |s| s.parse::<f64>().map(|v| v * POINTS).map_err(|_| invalid(&format!("invalid {} of {}", key, name))))
+            };
+            result.nodes.push(NodeLayout { name: name.clone(), x: center.x, y: center.y, width: size("width")?, height: size("height")? });
+        }
+
+        let mut pairs = Pairs::new(self);
+        for (from, to, attributes) in dot.edges.iter() {
+            let mut points = vec![];
+            let (mut start, mut end) = (None, None);
+            for part in attributes.get("pos").map(String::as_str).unwrap_or("").split_whitespace() {
+                if let Some(p) = part.strip_prefix("s,") {
+                    start = Some(point(p)?);
+                } else if let Some(p) = part.strip_prefix("e,") {
+                    end = Some(point(p)?);
+                } else {
+                    points.push(point(part)?);
+                }
+            }
+            // straight segments out to the arrow tips
+            if let (Some(s), Some(&first)) = (start, points.first()) {
+                points.splice(0..0, [s, s, first]);
+            }
+            if let (Some(e), Some(&last)) = (end, points.last()) {
+                points.extend([last, e, e]);
+            }
+            let label_pos = attributes.get("lp").map(|lp| point(lp)).transpose()?;
+            let edge = pairs.take(from, to)?;
+            result.edges.push(EdgeLayout { edge, from: from.clone(), to: to.clone(), points, spline: true, label_pos });
+        }
+
+        for (name, attributes) in dot.subgraphs.iter() {
+            let Some(bb) = attributes.get("bb") else { continue };
+            let Ok([left, bottom, right, up]) = <[f64; 4]>::try_from(numbers(bb)?) else {
+                return Err(invalid(&format!("bb of {} should have four numbers", name)));
+            };
+            let corner = flip(left, up);
+            result.clusters.push(ClusterLayout {
+                name: name.clone(),
+                x: corner.x + (right - left) / 2.0,
+                y: corner.y + (up - bottom) / 2.0,
+                width: right - left,
+                height: up - bottom,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Writes the positions of `layout` to the graph: `pos` of the nodes,
+    /// declaring the ones only referenced by edges, `pos` of the edges
+    /// between two nodes (chains have no single spline), and `bb` of the
+    /// graph and its clusters. Coordinates are converted to the ones of
+    /// Graphviz, with `y` growing upwards, so the graph can be drawn with
+    /// `neato -n`.
+    pub fn apply_layout(&mut self, layout: &LayoutResult) {
+        let y = |y: f64| layout.height - y;
+        let pos = |p: Point| format!("{},{}", number(p.x), number(y(p.y)));
+
+        for n in layout.nodes.iter() {
+            let p = Some(pos(Point::new(n.x, n.y)));
+            let declared = self.subgraph.iter_mut().flat_map(|s| s.nodes.iter_mut()).chain(self.nodes.iter_mut()).find(|d| d.name == n.name);
+            match declared {
+                Some(node) => node.pos = p,
+                None => {
+                    let mut node = Node::implicit(&n.name);
+                    node.pos = p;
+                    self.nodes.push(node);
+                }
+            }
+        }
+
+        let mut edges: Vec<_> = self.subgraph.iter_mut().flat_map(|s| s.edges.iter_mut()).chain(self.edges.iter_mut()).collect();
+        for e in layout.edges.iter() {
+            let Some(edge) = edges.get_mut(e.edge) else { continue };
+            if edge.endpoints.len() != 2 || edge.node_pairs().len() != 1 || e.points.is_empty() {
+                continue;
+            }
+            // Graphviz wants the control points of Bézier curves
+            let mut points = vec![e.points[0]];
+            if e.spline {
+                points.extend(e.points[1..].iter().copied());
+            } else {
+                for w in e.points.windows(2) {
+                    points.extend([w[0], w[1], w[1]]);
+                }
+            }
+            edge.pos = Some(points.into_iter().map(pos).collect::<Vec<String>>().join(" "));
+        }
+
+        self.bb = Some(format!("0,0,{},{}", number(layout.width), number(layout.height)));
+        for c in layout.clusters.iter() {
+            if let Some(s) = self.subgraph.iter_mut().find(|s| s.name == c.name) {
+                let (left, top) = (c.x - c.width / 2.0, c.y - c.height / 2.0);
+                s.bb = Some(format!("{},{},{},{}", number(left), number(y(top + c.height)), number(left + c.width), number(y(top))));
+            }
+        }
+    }
+}
+
+/// The node pairs of the edges of a graph, taken in order by the edges
+/// Graphviz wrote.
+struct Pairs {
+    pairs: Vec<(usize, String, String)>,
+    undirected: bool,
+}
+
+impl Pairs {
+    fn new(graph: &Graph) -> Pairs {
+        let pairs = graph
+            .all_edges()
+            .iter()
+            .enumerate()
+            .flat_map(|(i, e)| e.node_pairs().into_iter().map(move |(f, t)| (i, f, t)))
+            .collect();
+        Pairs { pairs, undirected: graph.kind == Kind::Graph }
+    }
+
+    /// The index of the first edge from `from` to `to` not taken yet.
+    fn take(&mut self, from: &str, to: &str) -> io::Result<usize> {
+        let found = self
+            .pairs
+            .iter()
+            .position(|(_, f, t)| (f == from && t == to) || (self.undirected && f == to && t == from))
+            .ok_or_else(|| invalid(&format!("no edge between {} and {} in the graph", from, to)))?;
+        Ok(self.pairs.remove(found).0)
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// The comma separated numbers of a point or box.
+fn numbers(text: &str) -> io::Result<Vec<f64>> {
+    text.split(',')
+        .map(|n| n.trim().parse().map_err(|_| invalid(&format!("invalid number {:?}", n))))
+        .collect()
+}
+
+/// The fields of a line of `plain`, where strings with spaces are quoted.
+fn fields(line: &str) -> Option<Vec<String>> {
+    let mut fields = vec![];
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut field = String::new();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => field.push(chars.next()?),
+                    c => field.push(c),
+                }
+            }
+            fields.push(field);
+        } else {
+            let mut field = String::new();
+            while let Some(&c) = chars.peek().filter(|c| !c.is_whitespace()) {
+                field.push(c);
+                chars.next();
+            }
+            fields.push(field);
+        }
+    }
+    Some(fields)
+}
+
+/// The parts of a DOT document needed for the positions: attributes of
+/// the graph, nodes, edges and subgraphs with their attributes.
+#[derive(Default)]
+struct Positions {
+    attributes: HashMap<String, String>,
+    nodes: Vec<(String, HashMap<String, String>)>,
+    edges: Vec<(String, String, HashMap<String, String>)>,
+    subgraphs: Vec<(String, HashMap<String, String>)>,
+}
+
+impl Positions {
+    fn read(xdot: &str) -> io::Result<Positions> {
+        let document = dot::parse(xdot)?;
+        let mut positions = Positions::default();
+        let mut attributes = HashMap::new();
+        positions.statements(&document.statements, &mut attributes)?;
+        positions.attributes = attributes;
+        Ok(positions)
+    }
+
+    /// Collects the statements of a block, with the attributes of the
+    /// (sub)graph into `attributes`.
+    fn statements(&mut self, statements: &[Statement], attributes: &mut HashMap<String, String>) -> io::Result<()> {
+        let map = |list: &[Attribute]| -> HashMap<String, String> { list.iter().map(|a| (a.key.clone(), a.value.clone())).collect() };
+        for statement in statements {
+            match statement {
+                Statement::Defaults(Target::Graph, list) => attributes.extend(map(list)),
+                Statement::Assign(a) => {
+                    attributes.insert(a.key.clone(), a.value.clone());
+                }
+                Statement::Defaults(..) => {}
+                Statement::Node(id, list) => self.nodes.push((id.name.clone(), map(list))),
+                Statement::Edge(operands, list) => {
+                    let mut names = vec![];
+                    for operand in operands {
+                        match operand {
+                            Operand::Node(id) => names.push(id.name.clone()),
+                            Operand::Subgraph(block) => {
+                                return Err(invalid(&format!("line {}: edges to subgraphs are not supported", block.line)))
+                            }
+                        }
+                    }
+                    for w in names.windows(2) {
+                        self.edges.push((w[0].clone(), w[1].clone(), map(list)));
+                    }
+                }
+                Statement::Subgraph(block) => {
+                    let mut inner = HashMap::new();
+                    self.statements(&block.statements, &mut inner)?;
+                    self.subgraphs.push((block.name.clone().unwrap_or_default(), inner));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+//! A minimal XML reader and escaping for the XML based formats of this
+//! crate, without pulling in a dependency.
+
+use std::io;
+
+/// An XML element with its attributes, child elements and the text
+/// directly inside it.
+#[derive(Clone, Debug)]
+pub(crate) struct Element {
+    pub(crate) name: String,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) children: Vec<Element>,
+    pub(crate) text: String,
+}
+
+impl Element {
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    /// Child elements called `name`, ignoring namespace prefixes.
+    pub(crate) fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |c| local_name(&c.name) == name)
+    }
+
+    pub(crate) fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| local_name(&c.name) == name)
+    }
+
+    /// Parses a document and returns its root element.
+    pub(crate) fn parse(text: &str) -> io::Result<Element> {
+        let mut parser = Parser { chars: text.chars().collect(), pos: 0 };
+        parser.misc()?;
+        let root = parser.element()?;
+        parser.misc()?;
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("content after the root element"));
+        }
+        Ok(root)
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    match name.rfind(':') {
+        Some(i) => &name[i + 1..],
+        None => name,
+    }
+}
+
+/// Escapes `s` for text content and attribute values.
+pub(crate) fn escape(s: &str) -> String {
+    let mut out = String::new();
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, msg: &str) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, format!("invalid XML at {}: {}", self.pos, msg))
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars().enumerate().all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    fn skip_until(&mut self, end: &str) -> io::Result<String> {
+        let start = self.pos;
+        while self.pos < self.chars.len() {
+            if self.starts_with(end) {
+                let skipped = self.chars[start..self.pos].iter().collect();
+                self.pos += end.chars().count();
+                return Ok(skipped);
+            }
+            self.pos += 1;
+        }
+        Err(self.error(&format!("missing '{}'", end)))
+    }
+
+    fn whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips whitespace, comments, processing instructions and doctypes.
+    fn misc(&mut self) -> io::Result<()> {
+        loop {
+            self.whitespace();
+            if self.starts_with("<?") {
+                self.skip_until("?>")?;
+            } else if self.starts_with("<!--") {
+                self.skip_until("-->")?;
+            } else if self.starts_with("<!DOCTYPE") {
+                self.skip_until(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> io::Result<String> {
+        let start = self.pos;
+        while let Some(c) = self.chars.get(self.pos) {
+            if c.is_alphanumeric() || "_-.:".contains(*c) {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        if start == self.pos {
+            return Err(self.error("expected a name"));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn element(&mut self) -> io::Result<Element> {
+        if !self.starts_with("<") {
+            return Err(self.error("expected an element"));
+        }
+        self.pos += 1;
+        let name = self.name()?;
+        let mut element = Element { name, attributes: vec![], children: vec![], text: String::new() };
+
+        loop {
+            self.whitespace();
+            if self.starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if self.starts_with(">") {
+                self.pos += 1;
+                break;
+            }
+            let key = self.name()?;
+            self.whitespace();
+            if !self.starts_with("=") {
+                return Err(self.error("expected '='"));
+            }
+            self.pos += 1;
+            self.whitespace();
+            let quote = match self.chars.get(self.pos) {
+                Some(q) if *q == '"' || *q == '\'' => *q,
+                _ => return Err(self.error("expected a quoted value")),
+            };
+            self.pos += 1;
+            let raw = self.skip_until(&quote.to_string())?;
+            element.attributes.push((key, self.unescape(&raw)?));
+        }
+
+        loop {
+            if self.pos >= self.chars.len() {
+                return Err(self.error(&format!("unclosed element '{}'", element.name)));
+            }
+            if self.starts_with("</") {
+                self.pos += 2;
+                let end = self.name()?;
+                if end != element.name {
+                    return Err(self.error(&format!("'{}' closed by '{}'", element.name, end)));
+                }
+                self.whitespace();
+                if !self.starts_with(">") {
+                    return Err(self.error("expected '>'"));
+                }
+                self.pos += 1;
+                return Ok(element);
+            } else if self.starts_with("<!--") {
+                self.skip_until("-->")?;
+            } else if self.starts_with("<![CDATA[") {
+                self.pos += 9;
+                let text = self.skip_until("]]>")?;
+                element.text.push_str(&text);
+            } else if self.starts_with("<?") {
+                self.skip_until("?>")?;
+            } else if self.starts_with("<") {
+                element.children.push(self.element()?);
+            } else {
+                let start = self.pos;
+                while self.pos < self.chars.len() && self.chars[self.pos] != '<' {
+                    self.pos += 1;
+                }
+                let raw: String = self.chars[start..self.pos].iter().collect();
+                let text = self.unescape(&raw)?;
+                element.text.push_str(&text);
+            }
+        }
+    }
+
+    fn unescape(&self, raw: &str) -> io::Result<String> {
+        let mut out = String::new();
+        let mut rest = raw;
+        while let Some(i) = rest.find('&') {
+            out.push_str(&rest[..i]);
+            let end = rest[i..].find(';').ok_or_else(|| self.error("unterminated entity"))? + i;
+            let entity = &rest[i + 1..end];
+            let c = match entity {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                _ => {
+                    let code = if let Some(hex) = entity.strip_prefix("#x") {
+                        u32::from_str_radix(hex, 16).ok()
+                    } else if let Some(dec) = entity.strip_prefix('#') {
+                        dec.parse::<u32>().ok()
+                    } else {
+                        None
+                    };
+                    code.and_then(char::from_u32)
+                        .ok_or_else(|| self.error(&format!("unknown entity '&{};'", entity)))?
+                }
+            };
+            out.push(c);
+            rest = &rest[end + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
@@ -0,0 +1,73 @@
+use dot_graph::{algo, Edge, Endpoint, Graph, Kind, Node, Subgraph};
+
+/// Crates of a workspace, across two clusters.
+fn workspace() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut libs = Subgraph::new("cluster_libs");
+    libs.add_node(Node::new("util"));
+    libs.add_node(Node::new("core"));
+    libs.add_edge(Edge::new("util", "core", ""));
+    graph.add_subgraph(libs);
+    let mut apps = Subgraph::new("cluster_apps");
+    apps.add_node(Node::new("cli"));
+    apps.add_node(Node::new("web"));
+    graph.add_subgraph(apps);
+    graph.add_edge(Edge::new("core", Endpoint::Subgraph { name: String::new(), nodes: vec![String::from("cli"), String::from("web")] }, ""));
+    graph.add_edge(Edge::new("web", "docs", ""));
+    graph.add_node(Node::new("lonely"));
+    graph
+}
+
+#[test]
+fn algo_order() {
+    let mut graph = workspace();
+    assert_eq!(algo::toposort(&graph).unwrap(), ["util", "core", "cli", "web", "lonely", "docs"]);
+    assert_eq!(algo::find_cycle(&graph), None);
+
+    graph.add_edge(Edge::new("docs", "core", ""));
+    assert_eq!(algo::find_cycle(&graph).unwrap(), ["core", "web", "docs", "core"]);
+    assert_eq!(algo::toposort(&graph).unwrap_err(), ["core", "web", "docs", "core"]);
+    graph.add_edge(Edge::new("lonely", "lonely", ""));
+    assert_eq!(algo::strongly_connected_components(&graph), [vec!["util"], vec!["core", "web", "docs"], vec!["cli"], vec!["lonely"]]);
+}
+
+#[test]
+fn algo_reach() {
+    let graph = workspace();
+    assert_eq!(algo::descendants(&graph, "core"), ["cli", "web", "docs"]);
+    assert_eq!(algo::ancestors(&graph, "docs"), ["util", "core", "web"]);
+    assert!(algo::descendants(&graph, "missing").is_empty());
+    assert!(algo::is_reachable(&graph, "util", "docs"));
+    assert!(!algo::is_reachable(&graph, "docs", "util"));
+    assert!(algo::is_reachable(&graph, "lonely", "lonely"));
+    assert_eq!(algo::shortest_path(&graph, "util", "docs").unwrap(), ["util", "core", "web", "docs"]);
+    assert_eq!(algo::shortest_path(&graph, "cli", "docs"), None);
+    assert_eq!(algo::connected_components(&graph), [vec!["util", "core", "cli", "web", "docs"], vec!["lonely"]]);
+}
+
+#[test]
+fn algo_undirected() {
+    let mut graph = Graph::new("G", Kind::Graph);
+    graph.add_edge(Edge::chain(&["a", "b", "c"], ""));
+    graph.add_edge(Edge::new("d", "e", ""));
+    assert_eq!(algo::find_cycle(&graph), None);
+    assert_eq!(algo::connected_components(&graph), [vec!["a", "b", "c"], vec!["d", "e"]]);
+    assert_eq!(algo::strongly_connected_components(&graph), algo::connected_components(&graph));
+    assert_eq!(algo::shortest_path(&graph, "c", "a").unwrap(), ["c", "b", "a"]);
+    assert_eq!(algo::toposort(&graph).unwrap(), ["a", "b", "c", "d", "e"]);
+
+    // many isolated nodes, one component each
+    for i in 0..20_000 {
+        graph.add_node(Node::new(&format!("n{}", i)));
+    }
+    let components = algo::connected_components(&graph);
+    assert_eq!(components.len(), 20_002);
+    assert_eq!(components[0], ["n0"]);
+
+    graph.add_edge(Edge::new("c", "a", ""));
+    assert_eq!(algo::find_cycle(&graph).unwrap(), ["a", "b", "c", "a"]);
+    let mut parallel = Graph::new("G", Kind::Graph);
+    parallel.add_edge(Edge::new("a", "b", "1"));
+    parallel.add_edge(Edge::new("b", "a", "2"));
+    assert_eq!(algo::find_cycle(&parallel).unwrap(), ["a", "b", "a"]);
+}
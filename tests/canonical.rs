@@ -0,0 +1,66 @@
+use dot_graph::{Edge, Endpoint, Graph, Kind, Node, Subgraph};
+
+fn build(reversed: bool) -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut cluster_a = Subgraph::new("cluster_a");
+    let mut members = vec![Node::new("p"), Node::new("o")];
+    let mut nodes = vec![Node::new("x"), Node::new("y").color(Some("red"))];
+    let mut edges = vec![
+        Edge::new("x", "y", "1"),
+        Edge::new("x", "y", "0"),
+        Edge::new("w", Endpoint::Subgraph { name: String::new(), nodes: vec![String::from("y"), String::from("x")] }, ""),
+    ];
+    let mut ranks = vec![vec!["y", "x"], vec!["w"]];
+    if reversed {
+        members.reverse();
+        nodes.reverse();
+        edges.reverse();
+        ranks.reverse();
+    }
+    cluster_a.add_nodes(members);
+    let mut clusters = vec![cluster_a, Subgraph::new("cluster_b")];
+    if reversed {
+        clusters.reverse();
+    }
+    for s in clusters {
+        graph.add_subgraph(s);
+    }
+    for n in nodes {
+        graph.add_node(n);
+    }
+    for e in edges {
+        graph.add_edge(e);
+    }
+    for names in ranks {
+        graph.same_rank(&names);
+    }
+    graph
+}
+
+#[test]
+fn canonical_order() {
+    assert_ne!(build(false).to_dot_string().unwrap(), build(true).to_dot_string().unwrap());
+    assert_eq!(build(false).to_canonical_dot_string().unwrap(),
+r#"digraph G {
+    subgraph cluster_a {
+        label="";
+        "o"[label="o"];
+        "p"[label="p"];
+    }
+    subgraph cluster_b {
+        label="";
+    }
+    {rank=same; "w"; }
+    {rank=same; "x"; "y"; }
+    "x"[label="x"];
+    "y"[label="y"][color="red"];
+    "w" -> {"x" "y"}[label=""];
+    "x" -> "y"[label="0"];
+    "x" -> "y"[label="1"];
+}
+"#);
+
+    let mut graph = build(true);
+    graph.canonicalize();
+    assert_eq!(graph.to_dot_string().unwrap(), build(false).to_canonical_dot_string().unwrap());
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// Runs the tool, returning its exit code, stdout and stderr.
+fn run(args: &[&str], stdin: &str) -> (i32, String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_dot_graph"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // the tool may exit without reading its input
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    let output = child.wait_with_output().unwrap();
+    let text = |bytes: Vec<u8>| String::from_utf8(bytes).unwrap();
+    (output.status.code().unwrap(), text(output.stdout), text(output.stderr))
+}
+
+fn file(name: &str, text: &str) -> String {
+    let path = format!("{}/{}", env!("CARGO_TARGET_TMPDIR"), name);
+    std::fs::write(&path, text).unwrap();
+    path
+}
+
+#[test]
+fn cli_fmt() {
+    let (code, out, err) = run(&["fmt"], "digraph G { a -> b [color=red, weight=2] }");
+    assert_eq!(code, 0);
+    assert_eq!(out, "digraph G {\n    \"a\" -> \"b\"[label=\"\"][color=\"red\"];\n}\n");
+    assert_eq!(err, "<stdin>:1: warning: attribute weight of edge a -> b is not supported\n");
+
+    let (code, out, _) = run(&["fmt", "--canonical"], "digraph G { c; b -> c; a -> b }");
+    assert_eq!(code, 0);
+    assert_eq!(out, "digraph G {\n    \"c\"[label=\"c\"];\n    \"a\" -> \"b\"[label=\"\"];\n    \"b\" -> \"c\"[label=\"\"];\n}\n");
+
+    let (code, _, err) = run(&["fmt"], "digraph G { a -> }");
+    assert_eq!(code, 2);
+    assert_eq!(err, "dot_graph: <stdin>: line 1: expected an id\n");
+}
+
+#[test]
+fn cli_check() {
+    let good = file("good.dot", "graph G { a -- b }");
+    let warned = file("warned.dot", "graph G {\n  a [fontsize=9]\n}");
+    let broken = file("broken.dot", "graph G {\n  a -- ;\n}");
+
+    assert_eq!(run(&["check", &good], ""), (0, String::new(), String::new()));
+    let (code, out, _) = run(&["check", &good, &warned], "");
+    assert_eq!(code, 0);
+    assert_eq!(out, format!("{}:2: warning: attribute fontsize of node a is not supported\n", warned));
+    assert_eq!(run(&["check", "--strict", &warned], "").0, 1);
+    let strict = file("strict.dot", "strict graph G { a -- b }");
+    let (code, out, _) = run(&["check", "--strict", &strict], "");
+    assert_eq!(code, 1);
+    assert_eq!(out, format!("{}:1: warning: strict is not supported, duplicate edges are kept\n", strict));
+    let (code, out, _) = run(&["check", &broken], "");
+    assert_eq!(code, 1);
+    assert_eq!(out, format!("{}:2: error: expected an id\n", broken));
+
+    let (code, out, _) = run(&["check"], "digraph G { 1 -> 2 }");
+    assert_eq!(code, 1);
+    assert!(out.starts_with("<stdin>:1: error: node name \"1\" is not supported"), "{}", out);
+    let (_, help, _) = run(&["help"], "");
+    assert!(help.contains("not DOT syntax errors"));
+
+    assert_eq!(run(&["check", "/nonexistent.dot"], "").0, 2);
+    assert_eq!(run(&["check", "--fix"], "").0, 2);
+    assert_eq!(run(&[], "").0, 2);
+}
+
+#[test]
+fn cli_convert() {
+    let dot = "digraph G {\n    \"a\"[label=\"A\"];\n    \"b\"[label=\"b\"];\n    \"a\" -> \"b\"[label=\"\"];\n}\n";
+    let (code, json, _) = run(&["convert", "--to", "json"], dot);
+    assert_eq!(code, 0);
+    let path = file("graph.json", &json);
+    assert_eq!(run(&["convert", "--to", "dot", &path], ""), (0, String::from(dot), String::new()));
+
+    let (_, graphml, _) = run(&["convert", "--to", "graphml"], dot);
+    assert_eq!(run(&["convert", "--from", "graphml", "--to", "dot"], &graphml).1, dot);
+
+    let (code, mermaid, _) = run(&["convert", "--to", "mermaid"], dot);
+    assert_eq!(code, 0);
+    assert!(mermaid.starts_with("flowchart TD\n"));
+
+    assert_eq!(run(&["convert", "--to", "png"], dot).0, 2);
+    assert_eq!(run(&["convert"], dot).0, 2);
+}
+
+#[test]
+fn cli_stats_and_diff() {
+    let (code, out, _) = run(&["stats"], "digraph G { subgraph cluster_0 { a } a -> {b c} }");
+    assert_eq!(code, 0);
+    assert_eq!(out, "nodes: 3\nedges: 2\nclusters: 1\ndegrees: 1: 2, 2: 1\ndensity: 0.333\nnesting depth: 1\n\
+        longest path: a -> b (1 edges)\nisolated: (none)\nfan-in: b 1, c 1\nfan-out: a 2\n");
+    let (code, out, _) = run(&["stats", "--top", "1"], "digraph G { a -> {b c} }");
+    assert_eq!(code, 0);
+    assert!(out.contains("fan-in: b 1\n"), "{}", out);
+    assert_eq!(run(&["stats", "--top", "x"], "").0, 2);
+    let (code, out, _) = run(&["stats", "--json"], "graph G { a }");
+    assert_eq!(code, 0);
+    assert!(out.contains("\"isolated\": [\"a\"]"), "{}", out);
+
+    let old = file("old.dot", "digraph G { a -> b; b -> c }");
+    let new = file("new.dot", "digraph G { a -> b; a -> d }");
+    let (code, out, _) = run(&["diff", &old, &new], "");
+    assert_eq!(code, 1);
+    assert_eq!(out, "- node c\n+ node d\n- edge b -> c\n+ edge a -> d\n");
+    assert_eq!(run(&["diff", &old, &old], "").0, 0);
+    let (code, out, _) = run(&["diff", "--dot", &old, &new], "");
+    assert_eq!(code, 1);
+    assert!(out.contains("\"a\" -> \"d\"[label=\"\"][color=\"green\"];"));
+    assert_eq!(run(&["diff", &old], "").0, 2);
+}
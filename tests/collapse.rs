@@ -0,0 +1,78 @@
+use dot_graph::{CollapseError, Edge, Graph, Kind, Node, Subgraph};
+
+fn architecture() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut net = Subgraph::new("cluster_net").label("network").color(Some("blue"));
+    net.add_node(Node::new("http"));
+    net.add_node(Node::new("tls"));
+    net.add_edge(Edge::new("http", "tls", ""));
+    net.add_edge(Edge::new("tls", "crypto", "tls"));
+    let mut store = Subgraph::new("cluster_store");
+    store.add_node(Node::new("db"));
+    store.add_edge(Edge::new("db", "tls", ""));
+    graph.add_edge(Edge::new("app", "http", "").lhead(&net));
+    graph.add_subgraph(net);
+    graph.add_subgraph(store);
+    graph.add_node(Node::new("app"));
+    graph.add_edge(Edge::chain(&["app", "tls", "db"], ""));
+    graph.add_edge(Edge::new("app", "db", ""));
+    graph.same_rank(&["http", "tls", "app"]);
+    graph
+}
+
+#[test]
+fn collapse_subgraph() {
+    let mut graph = architecture();
+    graph.collapse_subgraph("cluster_net").unwrap();
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    subgraph cluster_store {
+        label="";
+        "db"[label="db"];
+    }
+    {rank=same; "cluster_net"; "app"; }
+    "app"[label="app"];
+    "cluster_net"[label="network (2)"][color="blue"][shape="box3d"];
+    "app" -> "db"[label=""];
+    "db" -> "cluster_net"[label=""];
+    "app" -> "cluster_net"[label=""];
+    "cluster_net" -> "db"[label=""];
+    "cluster_net" -> "crypto"[label="tls"];
+}
+"#);
+
+    graph.expand("cluster_net").unwrap();
+    assert_eq!(graph.to_dot_string().unwrap(), architecture().to_dot_string().unwrap());
+}
+
+#[test]
+fn collapse_errors() {
+    let mut graph = architecture();
+    assert_eq!(graph.collapse_subgraph("cluster_none").unwrap_err(), CollapseError::UnknownSubgraph(String::from("cluster_none")));
+    assert_eq!(graph.expand("cluster_net").unwrap_err().to_string(), "no collapsed subgraph cluster_net");
+    graph.add_node(Node::new("cluster_store"));
+    assert_eq!(graph.collapse_subgraph("cluster_store").unwrap_err(), CollapseError::NameTaken(String::from("cluster_store")));
+
+    // both collapsed, expanded in reverse order
+    let mut graph = architecture();
+    graph.collapse_subgraph("cluster_net").unwrap();
+    graph.collapse_subgraph("cluster_store").unwrap();
+    assert!(graph.to_dot_string().unwrap().contains("\"cluster_store\"[label=\"store (1)\"][shape=\"box3d\"];"));
+    graph.expand("cluster_store").unwrap();
+    graph.expand("cluster_net").unwrap();
+    assert_eq!(graph.to_dot_string().unwrap(), architecture().to_dot_string().unwrap());
+}
+
+#[test]
+fn collapse_keeps_compound_for_other_clusters() {
+    let mut graph = architecture();
+    let store = Subgraph::new("cluster_store");
+    graph.add_edge(Edge::new("app", "db", "").lhead(&store));
+    graph.collapse_subgraph("cluster_net").unwrap();
+    assert!(graph.to_dot_string().unwrap().contains("compound=true"));
+    graph.collapse_subgraph("cluster_store").unwrap();
+    assert!(!graph.to_dot_string().unwrap().contains("compound=true"));
+    graph.expand("cluster_store").unwrap();
+    graph.expand("cluster_net").unwrap();
+    assert!(graph.to_dot_string().unwrap().contains("compound=true"));
+}
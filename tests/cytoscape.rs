@@ -0,0 +1,121 @@
+use dot_graph::{Arrow, ArrowShape, Edge, Fill, Graph, Kind, Node, Side, Style, Subgraph};
+
+#[test]
+fn cytoscape_elements() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").label("process").style(Style::Filled).color(Some("lightgrey"));
+    c0.add_node(Node::new("a0").shape(Some("box")));
+    graph.add_subgraph(c0.clone());
+    graph.add_node(Node::new("start").label("\"start\"").color(Some("red")).shape(Some("Mdiamond")));
+    graph.add_edge(Edge::new("start", "a0", "go").color(Some("blue")).style(Style::Dashed)
+        .end_arrow(Arrow::from_arrow(ArrowShape::Diamond(Fill::Open, Side::Both))));
+    graph.add_edge(Edge::new("end", &c0, "").start_arrow(Arrow::normal()).end_arrow(Arrow::none()));
+    assert_eq!(graph.to_cytoscape_json(),
+r#"[
+  {
+    "group": "nodes",
+    "data": {
+      "id": "cluster_0",
+      "label": "process"
+    },
+    "style": {
+      "label": "process",
+      "border-color": "lightgrey",
+      "background-color": "lightgrey"
+    }
+  },
+  {
+    "group": "nodes",
+    "data": {
+      "id": "a0",
+      "label": "a0",
+      "parent": "cluster_0"
+    },
+    "style": {
+      "label": "a0",
+      "shape": "rectangle"
+    }
+  },
+  {
+    "group": "nodes",
+    "data": {
+      "id": "start",
+      "label": "\"start\""
+    },
+    "style": {
+      "label": "\"start\"",
+      "border-color": "red",
+      "shape": "diamond"
+    }
+  },
+  {
+    "group": "nodes",
+    "data": {
+      "id": "end",
+      "label": "end"
+    },
+    "style": {
+      "label": "end"
+    }
+  },
+  {
+    "group": "edges",
+    "data": {
+      "id": "e0",
+      "source": "start",
+      "target": "a0",
+      "label": "go"
+    },
+    "style": {
+      "label": "go",
+      "line-color": "blue",
+      "source-arrow-color": "blue",
+      "target-arrow-color": "blue",
+      "line-style": "dashed",
+      "target-arrow-shape": "diamond",
+      "target-arrow-fill": "hollow"
+    }
+  },
+  {
+    "group": "edges",
+    "data": {
+      "id": "e1",
+      "source": "end",
+      "target": "cluster_0",
+      "label": ""
+    },
+    "style": {
+      "label": "",
+      "source-arrow-shape": "triangle",
+      "target-arrow-shape": "none"
+    }
+  }
+]
+"#);
+}
+
+#[test]
+fn cytoscape_undirected() {
+    let mut graph = Graph::new("g", Kind::Graph);
+    graph.add_edge(Edge::new("a", "b", ""));
+    let json = graph.to_cytoscape_json();
+    assert!(!json.contains("arrow-shape"));
+}
+
+#[test]
+fn cytoscape_undeclared_names() {
+    let mut graph = Graph::new("g", Kind::Graph);
+    graph.add_edge(Edge::new("a", "x y", ""));
+    let json = graph.to_cytoscape_json();
+    assert!(json.contains(r#""id": "x y""#), "{}", json);
+}
+
+#[test]
+fn cytoscape_labels() {
+    let mut graph = Graph::new("g", Kind::Digraph);
+    graph.add_node(Node::new("a").label(r#"say \"hi\"\nbye"#));
+    graph.add_edge(Edge::new("a", "b", r"x\\y"));
+    let json = graph.to_cytoscape_json();
+    assert!(json.contains(r#""label": "say \"hi\"\nbye""#), "{}", json);
+    assert!(json.contains(r#""label": "x\\y""#));
+}
@@ -0,0 +1,115 @@
+use dot_graph::{diff::{Change, Element}, Edge, Graph, Kind, Node, Style, Subgraph};
+
+fn old() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").label("zero");
+    c0.add_node(Node::new("a"));
+    c0.add_node(Node::new("b"));
+    graph.add_subgraph(c0);
+    let mut gone = Subgraph::new("cluster_gone");
+    gone.add_node(Node::new("x"));
+    graph.add_subgraph(gone);
+    graph.add_node(Node::new("c").shape(Some("box")));
+    graph.add_edge(Edge::chain(&["a", "b", "c"], ""));
+    graph.add_edge(Edge::new("a", "c", "one"));
+    graph.add_edge(Edge::new("a", "c", "two"));
+    graph.add_edge(Edge::new("x", "a", ""));
+    graph
+}
+
+fn new() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").label("zero").style(Style::Dashed);
+    c0.add_node(Node::new("a"));
+    graph.add_subgraph(c0);
+    graph.add_node(Node::new("b"));
+    graph.add_node(Node::new("c").shape(Some("box")));
+    graph.add_edge(Edge::chain(&["a", "b", "c"], ""));
+    graph.add_edge(Edge::new("a", "c", "one"));
+    graph.add_edge(Edge::new("a", "c", "2"));
+    graph.add_edge(Edge::new("c", "d", ""));
+    graph
+}
+
+#[test]
+fn diff_changes() {
+    let diff = old().diff(&new());
+    assert_eq!(diff.to_string(),
+r#"- subgraph cluster_gone
+~ subgraph cluster_0 style: (none) -> dashed
+- node x
++ node d
+~ node b subgraph: cluster_0 -> (none)
+- edge x -> a
++ edge c -> d
+~ edge a -> c label: two -> 2
+"#);
+    assert_eq!(diff.changes[2], Change::Removed(Element::Node(String::from("x"))));
+    assert_eq!(diff.changes[4], Change::Changed {
+        element: Element::Node(String::from("b")),
+        attribute: String::from("subgraph"),
+        old: Some(String::from("cluster_0")),
+        new: None,
+    });
+
+    assert!(new().diff(&new()).is_empty());
+    assert_eq!(new().diff(&new()).to_dot_string().unwrap(), new().to_dot_string().unwrap());
+}
+
+#[test]
+fn diff_merged() {
+    assert_eq!(old().diff(&new()).to_dot_string().unwrap(),
+r#"digraph G {
+    subgraph cluster_0 {
+        label="zero";
+        style="dashed";
+        color="orange";
+        "a"[label="a"];
+    }
+    subgraph cluster_gone {
+        label="";
+        color="red";
+        "x"[label="x"][color="red"];
+    }
+    "b"[label="b"][color="orange"];
+    "c"[label="c"][shape="box"];
+    "d"[label="d"][color="green"];
+    "a" -> "b" -> "c"[label=""];
+    "a" -> "c"[label="one"];
+    "a" -> "c"[label="2"][color="orange"];
+    "c" -> "d"[label=""][color="green"];
+    "x" -> "a"[label=""][color="red"];
+}
+"#);
+}
+
+#[test]
+fn diff_undirected() {
+    let mut old = Graph::new("G", Kind::Graph);
+    old.add_edge(Edge::new("a", "b", ""));
+    let mut new = Graph::new("G", Kind::Graph);
+    new.add_edge(Edge::new("b", "a", ""));
+    assert!(old.diff(&new).is_empty());
+
+    let mut directed = Graph::new("G", Kind::Digraph);
+    directed.add_edge(Edge::new("b", "a", ""));
+    assert_eq!(old.diff(&directed).to_string(), "~ graph kind: graph -> digraph\n- edge a -> b\n+ edge b -> a\n");
+}
+
+#[test]
+fn diff_undeclared_names() {
+    let mut old = Graph::new("G", Kind::Digraph);
+    old.add_edge(Edge::new("1", "x y", ""));
+    let mut new = Graph::new("G", Kind::Digraph);
+    new.add_edge(Edge::new("1", "2", ""));
+    let diff = old.diff(&new);
+    assert_eq!(diff.to_string(), "- node x y\n+ node 2\n- edge 1 -> x y\n+ edge 1 -> 2\n");
+    assert_eq!(diff.to_dot_string().unwrap(),
+r#"digraph G {
+    "2"[label="2"][color="green"];
+    "x y"[label="x y"][color="red"];
+    "1" -> "2"[label=""][color="green"];
+    "1" -> "x y"[label=""][color="red"];
+}
+"#);
+}
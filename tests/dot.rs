@@ -0,0 +1,112 @@
+use dot_graph::{dot, Arrow, ArrowShape, Edge, Fill, Graph, Kind, Node, Style, Subgraph};
+
+#[test]
+fn dot_round_trip() {
+    let mut graph = Graph::new("di", Kind::Digraph).url(String::from("https://example.com"));
+    let mut c0 = Subgraph::new("cluster_0").label("zero").style(Style::Filled).color(Some("lightgrey"));
+    c0.add_node(Node::new("b").shape(Some("box")));
+    c0.add_node(Node::new("c"));
+    c0.add_edge(Edge::new("b", "c", "inner"));
+    graph.add_node(Node::new("a").label("say \\\"hi\\\"").style(Style::Bold).color(Some("red")));
+    graph.add_edge(Edge::chain(&["a", "b", "d"], "chain").style(Style::Dashed).penwidth(Some(2.5)));
+    graph.add_edge(Edge::new("a", &c0, "").url(String::from("https://example.com/a")));
+    graph.add_edge(Edge::new("d", "c", "").lhead(&c0).start_arrow(Arrow::from_arrow(ArrowShape::Dot(Fill::Open))).end_arrow(Arrow::none()));
+    graph.add_subgraph(c0);
+    graph.same_rank(&["a", "d"]);
+
+    let dot = graph.to_dot_string().unwrap();
+    let read = dot::read(&dot).unwrap();
+    assert!(read.warnings.is_empty(), "{:?}", read.warnings);
+    assert_eq!(read.graph.to_dot_string().unwrap(), dot);
+
+    let undirected = "graph G {\n    \"a\" -- \"b\"[label=\"\"];\n}\n";
+    assert_eq!(Graph::from_dot_string(undirected).unwrap().to_dot_string().unwrap(), undirected);
+}
+
+#[test]
+fn dot_language() {
+    let read = dot::read(r#"
+/* a comment */
+strict digraph "my graph" {
+    // defaults apply to what follows
+    edge [color=blue]
+    a -> b -> c [label="x" + "y"];
+    subgraph cluster_x {
+        label = "X";
+        c -> d;
+        e;
+    }
+    node [shape=box]
+    f
+    { a; f } -> g
+    d -> e [style=dotted]
+}
+"#).unwrap();
+    let warnings: Vec<String> = read.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(warnings, ["line 3: strict is not supported, duplicate edges are kept"]);
+    assert_eq!(read.graph.to_dot_string().unwrap(),
+r#"digraph "my graph" {
+    subgraph cluster_x {
+        label="X";
+        "c"[label="c"];
+        "d"[label="d"];
+        "e"[label="e"];
+        "c" -> "d"[label=""][color="blue"];
+    }
+    "f"[label="f"][shape="box"];
+    "g"[label="g"][shape="box"];
+    "a" -> "b" -> "c"[label="xy"][color="blue"];
+    {"a" "f"} -> "g"[label=""][color="blue"];
+    "d" -> "e"[label=""][style="dotted"][color="blue"];
+}
+"#);
+
+    // the quotes of a name are escaped, whether it was quoted or HTML
+    for name in [r#""say \"hi\"""#, r#"<say "hi">"#] {
+        let graph = Graph::from_dot_string(&format!("digraph {} {{ a }}", name)).unwrap();
+        let dot = graph.to_dot_string().unwrap();
+        assert!(dot.starts_with(r#"digraph "say \"hi\"" {"#), "{}", dot);
+        assert_eq!(Graph::from_dot_string(&dot).unwrap().to_dot_string().unwrap(), dot);
+    }
+}
+
+#[test]
+fn dot_warnings() {
+    let read = dot::read(r#"digraph G {
+    rankdir=LR;
+    a [fontname="Helvetica", label=<<b>A</b>>];
+    a:n -> b [dir=back, arrowhead=bogus];
+    subgraph s { b }
+    subgraph cluster_a {
+        subgraph cluster_b { c }
+    }
+    {rank=min; a}
+    b -> c [lhead=cluster_z, style="filled,bold"];
+}"#).unwrap();
+    let warnings: Vec<String> = read.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(warnings, vec![
+        "line 2: attribute rankdir of the graph is not supported",
+        "line 3: attribute fontname of node a is not supported",
+        "line 3: the HTML label of node a is read as text",
+        "line 4: the port of node a is not supported",
+        "line 4: arrowhead \"bogus\" of edge a -> b is not a valid arrow",
+        "line 4: attribute dir of edge a -> b is not supported",
+        "line 5: subgraph s is not a cluster, its statements are read into the enclosing graph",
+        "line 7: cluster cluster_b is nested, it is read next to its parent",
+        "line 9: rank=min is not supported",
+        "line 10: style \"filled,bold\" of edge b -> c is not supported",
+        "line 10: edge b -> c is clipped at cluster_z, which is not a cluster of the graph",
+    ]);
+    assert!(read.graph.to_dot_string().unwrap().contains("subgraph cluster_b {\n        label=\"\";\n        \"c\"[label=\"c\"];"));
+}
+
+#[test]
+fn dot_errors() {
+    let error = |dot: &str| Graph::from_dot_string(dot).err().unwrap().to_string();
+    assert_eq!(error("digraph {\n  a -> ;\n}"), "line 2: expected an id");
+    assert_eq!(error("digraph {\n  a [label=\"x];\n}"), "line 2: unterminated string");
+    assert_eq!(error("digraph { a -> b"), "line 1: expected '}' at the end of the document");
+    assert_eq!(error("node a"), "line 1: expected graph or digraph");
+    assert!(error("graph {\n\n  1 -- 2\n}").starts_with("line 3: node name \"1\" is not supported"));
+    assert_eq!(error("graph { a } b"), "line 1: unexpected text after the graph");
+}
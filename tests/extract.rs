@@ -0,0 +1,85 @@
+use dot_graph::{extract::Direction, Edge, Graph, Kind, Node, Style, Subgraph};
+
+fn crates() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut libs = Subgraph::new("cluster_libs").label("libs");
+    libs.add_node(Node::new("serde").shape(Some("box")));
+    libs.add_node(Node::new("core"));
+    libs.add_edge(Edge::new("serde", "core", "uses"));
+    graph.add_subgraph(libs);
+    let mut apps = Subgraph::new("cluster_apps");
+    apps.add_node(Node::new("cli"));
+    graph.add_edge(Edge::new("docs", "cli", "").lhead(&apps));
+    graph.add_subgraph(apps);
+    graph.add_edge(Edge::chain(&["cli", "http", "serde"], "").style(Style::Dashed));
+    graph.same_rank(&["cli", "docs"]);
+    graph
+}
+
+#[test]
+fn extract_neighborhood() {
+    let graph = crates();
+    assert_eq!(graph.neighborhood("serde", 1, Direction::Both).to_dot_string().unwrap(),
+r#"digraph G {
+    compound=true
+    subgraph cluster_libs {
+        label="libs";
+        "serde"[label="serde"][shape="box"];
+        "core"[label="core"];
+        "serde" -> "core"[label="uses"];
+    }
+    "http" -> "serde"[label=""][style="dashed"];
+}
+"#);
+    let ancestors = graph.neighborhood("http", usize::MAX, Direction::Incoming);
+    assert_eq!(ancestors.node_names(), ["cli", "docs", "http"]);
+    assert_eq!(ancestors.to_dot_string().unwrap(),
+r#"digraph G {
+    compound=true
+    subgraph cluster_apps {
+        label="";
+        "cli"[label="cli"];
+    }
+    {rank=same; "cli"; "docs"; }
+    "docs" -> "cli"[label=""][lhead="cluster_apps"];
+    "cli" -> "http"[label=""][style="dashed"];
+}
+"#);
+    assert_eq!(graph.neighborhood("core", 0, Direction::Outgoing).node_names(), ["core"]);
+    assert!(graph.neighborhood("missing", 3, Direction::Both).node_names().is_empty());
+}
+
+#[test]
+fn extract_filter() {
+    let graph = crates();
+    let induced = graph.induced(&["docs", "http", "serde"]);
+    assert_eq!(induced.to_dot_string().unwrap(),
+r#"digraph G {
+    compound=true
+    subgraph cluster_libs {
+        label="libs";
+        "serde"[label="serde"][shape="box"];
+    }
+    {rank=same; "docs"; }
+    "docs"[label="docs"];
+    "http" -> "serde"[label=""][style="dashed"];
+}
+"#);
+
+    let filtered = graph.filter(|n| n.name != "core", |e| e.to_dot_string("->").contains("dashed"));
+    assert_eq!(filtered.node_names(), ["serde", "cli", "docs", "http"]);
+    assert!(filtered.all_edges().iter().all(|e| e.to_dot_string("->").contains("dashed")));
+}
+
+#[test]
+fn extract_undeclared_names() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::chain(&["a", "1", "x y"], ""));
+    assert_eq!(graph.neighborhood("a", 1, Direction::Outgoing).node_names(), ["a", "1"]);
+    assert_eq!(graph.induced(&["1"]).to_dot_string().unwrap(), "digraph G {\n    \"1\"[label=\"1\"];\n}\n");
+    assert_eq!(graph.filter(|n| n.name != "a", |_| true).to_dot_string().unwrap(),
+r#"digraph G {
+    "1" -> "x y"[label=""];
+}
+"#);
+}
@@ -0,0 +1,73 @@
+use dot_graph::{Edge, Graph, Kind, Node, Style, Subgraph};
+
+#[test]
+fn gexf_document() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").label("a & b").color(Some("lightgrey"));
+    c0.add_node(Node::new("a0").shape(Some("box")));
+    c0.add_node(Node::new("a1").color(Some("#FF8000")));
+    graph.add_subgraph(c0.clone());
+    graph.add_node(Node::new("start").label("<start>").shape(Some("Mdiamond")).color(Some("unknowncolor")));
+    graph.add_edge(Edge::chain(&["start", "a0", "a1"], "go").color(Some("0.0 1.0 1.0")).style(Style::Dashed));
+    graph.add_edge(Edge::new("end", &c0, ""));
+    assert_eq!(graph.to_gexf(),
+r#"<?xml version="1.0" encoding="UTF-8"?>
+<gexf xmlns="http://gexf.net/1.3" xmlns:viz="http://gexf.net/1.3/viz" version="1.3">
+  <graph mode="static" defaultedgetype="directed">
+    <nodes>
+      <node id="cluster_0" label="a &amp; b">
+        <viz:color r="211" g="211" b="211"/>
+      </node>
+      <node id="a0" label="a0" pid="cluster_0">
+        <viz:shape value="square"/>
+      </node>
+      <node id="a1" label="a1" pid="cluster_0">
+        <viz:color r="255" g="128" b="0"/>
+      </node>
+      <node id="start" label="&lt;start&gt;">
+        <viz:shape value="diamond"/>
+      </node>
+      <node id="end" label="end"/>
+    </nodes>
+    <edges>
+      <edge id="0" source="start" target="a0" label="go">
+        <viz:color r="255" g="0" b="0"/>
+        <viz:shape value="dashed"/>
+      </edge>
+      <edge id="1" source="a0" target="a1" label="go">
+        <viz:color r="255" g="0" b="0"/>
+        <viz:shape value="dashed"/>
+      </edge>
+      <edge id="2" source="end" target="cluster_0"/>
+    </edges>
+  </graph>
+</gexf>
+"#);
+}
+
+#[test]
+fn gexf_undirected() {
+    let mut graph = Graph::new("g", Kind::Graph);
+    graph.add_edge(Edge::new("a", "b", ""));
+    assert!(graph.to_gexf().contains(r#"<graph mode="static" defaultedgetype="undirected">"#));
+    assert!(graph.to_gexf().contains(r#"<edge id="0" source="a" target="b"/>"#));
+}
+
+#[test]
+fn gexf_undeclared_names() {
+    let mut graph = Graph::new("g", Kind::Graph);
+    graph.add_edge(Edge::new("a", "1", ""));
+    let gexf = graph.to_gexf();
+    assert!(gexf.contains(r#"<node id="1" label="1"/>"#), "{}", gexf);
+    assert!(gexf.contains(r#"<edge id="0" source="a" target="1"/>"#));
+}
+
+#[test]
+fn gexf_labels() {
+    let mut graph = Graph::new("g", Kind::Digraph);
+    graph.add_node(Node::new("a").label(r#"say \"hi\"\nbye"#));
+    graph.add_edge(Edge::new("a", "b", r"x\\y"));
+    let gexf = graph.to_gexf();
+    assert!(gexf.contains("<node id=\"a\" label=\"say &quot;hi&quot;\nbye\"/>"), "{}", gexf);
+    assert!(gexf.contains(r#"label="x\y""#));
+}
@@ -0,0 +1,194 @@
+use dot_graph::{Arrow, ArrowShape, Edge, Graph, Kind, Node, Style, Subgraph};
+
+fn example() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").label("a & b").style(Style::Filled).color(Some("lightgrey"));
+    c0.add_node(Node::new("a0"));
+    c0.add_node(Node::new("a1").shape(Some("box")));
+    c0.add_edge(Edge::new("a0", "a1", ""));
+    graph.add_subgraph(c0.clone());
+    graph.add_node(Node::new("start").label("<start>").url("https://example.com/".into()));
+    graph.add_edge(Edge::new("start", "a0", "go").color(Some("red")).style(Style::Dashed)
+        .end_arrow(Arrow::from_arrow(ArrowShape::diamond())));
+    graph.add_edge(Edge::new("a1", "end", "").lhead(&c0));
+    graph
+}
+
+#[test]
+fn graphml_document() {
+    assert_eq!(example().to_graphml(),
+r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">
+  <key id="graph_compound" for="graph" attr.name="compound" attr.type="boolean"/>
+  <key id="node_label" for="node" attr.name="label" attr.type="string"/>
+  <key id="node_style" for="node" attr.name="style" attr.type="string"/>
+  <key id="node_color" for="node" attr.name="color" attr.type="string"/>
+  <key id="node_shape" for="node" attr.name="shape" attr.type="string"/>
+  <key id="node_URL" for="node" attr.name="URL" attr.type="string"/>
+  <key id="edge_label" for="edge" attr.name="label" attr.type="string"/>
+  <key id="edge_style" for="edge" attr.name="style" attr.type="string"/>
+  <key id="edge_color" for="edge" attr.name="color" attr.type="string"/>
+  <key id="edge_arrowhead" for="edge" attr.name="arrowhead" attr.type="string"/>
+  <key id="edge_lhead" for="edge" attr.name="lhead" attr.type="string"/>
+  <graph id="G" edgedefault="directed">
+    <data key="graph_compound">true</data>
+    <node id="cluster_0">
+      <data key="node_label">a &amp; b</data>
+      <data key="node_style">filled</data>
+      <data key="node_color">lightgrey</data>
+      <graph id="cluster_0:" edgedefault="directed">
+        <node id="a0">
+          <data key="node_label">a0</data>
+        </node>
+        <node id="a1">
+          <data key="node_label">a1</data>
+          <data key="node_shape">box</data>
+        </node>
+        <edge id="e0" source="a0" target="a1">
+          <data key="edge_label"></data>
+        </edge>
+      </graph>
+    </node>
+    <node id="start">
+      <data key="node_label">&lt;start&gt;</data>
+      <data key="node_URL">https://example.com/</data>
+    </node>
+    <node id="end">
+      <data key="node_label">end</data>
+    </node>
+    <edge id="e1" source="start" target="a0">
+      <data key="edge_label">go</data>
+      <data key="edge_style">dashed</data>
+      <data key="edge_color">red</data>
+      <data key="edge_arrowhead">diamond</data>
+    </edge>
+    <edge id="e2" source="a1" target="end">
+      <data key="edge_label"></data>
+      <data key="edge_lhead">cluster_0</data>
+    </edge>
+  </graph>
+</graphml>
+"#);
+}
+
+#[test]
+fn graphml_round_trip() {
+    let graph = example();
+    let read = Graph::from_graphml(&graph.to_graphml()).unwrap();
+    // the implicit node "end" is declared after the round trip
+    let mut expected = graph.clone();
+    expected.add_node(Node::new("end"));
+    assert_eq!(read.to_dot_string().unwrap(), expected.to_dot_string().unwrap());
+}
+
+#[test]
+fn graphml_undirected() {
+    let mut graph = Graph::new("g", Kind::Graph);
+    graph.add_node(Node::new("a"));
+    graph.add_node(Node::new("b"));
+    graph.add_edge(Edge::new("a", "b", "").start_arrow(Arrow::normal()).end_arrow(Arrow::normal()));
+    let graphml = graph.to_graphml();
+    assert!(graphml.contains(r#"<graph id="g" edgedefault="undirected">"#));
+    assert!(graphml.contains(r#"<data key="edge_dir">both</data>"#));
+
+    let read = Graph::from_graphml(&graphml).unwrap();
+    assert_eq!(read.to_dot_string().unwrap(), graph.to_dot_string().unwrap());
+}
+
+#[test]
+fn graphml_foreign_document() {
+    let graphml = r#"<?xml version="1.0"?>
+<!-- written by another tool -->
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="color" attr.type="string"><default>blue</default></key>
+  <key id="d1" for="edge" attr.name="weight" attr.type="double"/>
+  <key id="d2" for="node" attr.name="label" attr.type="string"/>
+  <graph edgedefault="undirected">
+    <node id="n0"><data key="d2"><![CDATA[<first>]]></data></node>
+    <node id="group">
+      <graph id="group:">
+        <node id="n1"><data key="d0">red</data></node>
+      </graph>
+    </node>
+    <edge source="n0" target="n1"><data key="d1">1.5</data></edge>
+  </graph>
+</graphml>
+"#;
+    let graph = Graph::from_graphml(graphml).unwrap();
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"graph G {
+    "n0"[label="<first>"][color="blue"];
+    "n1"[label="n1"][color="red"];
+    "n0" -- "n1"[label=""];
+}
+"#);
+}
+
+#[test]
+fn graphml_numeric_ids() {
+    // as written by Gephi
+    let graphml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key attr.name="label" attr.type="string" for="node" id="label"/>
+  <graph edgedefault="directed">
+    <node id="0">
+      <data key="label">
+        Paris
+      </data>
+    </node>
+    <node id="1"/>
+    <node id="n0"/>
+    <edge id="0" source="0" target="1"/>
+    <edge id="1" source="1" target="n0"/>
+    <edge id="2" source="1" target="a b"/>
+  </graph>
+</graphml>
+"#;
+    let graph = Graph::from_graphml(graphml).unwrap();
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    "n01"[label="Paris"];
+    "n1"[label="1"];
+    "n0"[label="n0"];
+    "n01" -> "n1"[label=""];
+    "n1" -> "n0"[label=""];
+    "n1" -> "a_b"[label=""];
+}
+"#);
+
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::new("a", "1", ""));
+    assert!(Graph::from_graphml(&graph.to_graphml()).is_ok());
+}
+
+#[test]
+fn graphml_labels() {
+    let graphml = r#"<graphml>
+  <key id="label" for="node" attr.name="label" attr.type="string"/>
+  <graph edgedefault="directed">
+    <node id="a"><data key="label">say "hi" \ bye</data></node>
+  </graph>
+</graphml>
+"#;
+    let graph = Graph::from_graphml(graphml).unwrap();
+    let dot = graph.to_dot_string().unwrap();
+    assert_eq!(dot, "digraph G {\n    \"a\"[label=\"say \\\"hi\\\" \\\\ bye\"];\n}\n");
+    let back = Graph::from_dot_string(&dot).unwrap();
+    assert_eq!(back.to_dot_string().unwrap(), dot);
+    assert!(back.to_graphml().contains("<data key=\"node_label\">say &quot;hi&quot; \\ bye</data>"));
+
+    // the escapes of DOT become line breaks
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_node(Node::new("b").label(r"one\ntwo\l"));
+    assert!(graph.to_graphml().contains("<data key=\"node_label\">one\ntwo</data>"));
+}
+
+#[test]
+fn graphml_errors() {
+    assert!(Graph::from_graphml("").is_err());
+    assert!(Graph::from_graphml("<graph/>").is_err());
+    assert!(Graph::from_graphml("<graphml><graph>").is_err());
+    assert!(Graph::from_graphml("<graphml></graphml>").is_err());
+    assert!(Graph::from_graphml(r#"<graphml><graph><node/></graph></graphml>"#).is_err());
+    assert!(Graph::from_graphml(r#"<graphml><graph><edge source="a"/></graph></graphml>"#).is_err());
+}
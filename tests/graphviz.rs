@@ -0,0 +1,78 @@
+#![cfg(feature = "graphviz")]
+
+use std::{cell::RefCell, io, time::Duration};
+
+use dot_graph::{
+    graphviz::{Engine, Executable, Format, Rendered, Runner},
+    Edge, Graph, Kind,
+};
+
+fn graph() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::new("a", "b", ""));
+    graph
+}
+
+struct Fake {
+    calls: RefCell<Vec<(Engine, Format, String)>>,
+}
+
+impl Runner for Fake {
+    fn run(&self, engine: Engine, format: Format, dot: &[u8]) -> io::Result<Rendered> {
+        self.calls.borrow_mut().push((engine, format, String::from_utf8(dot.to_vec()).unwrap()));
+        Ok(Rendered { output: b"<svg/>".to_vec(), stderr: String::new() })
+    }
+}
+
+#[test]
+fn runner() {
+    let fake = Fake { calls: RefCell::new(vec![]) };
+    let rendered = graph().render_with(&fake, Format::Svg, Engine::Neato).unwrap();
+    assert_eq!(rendered.output, b"<svg/>");
+    assert_eq!(fake.calls.borrow().as_slice(), &[(Engine::Neato, Format::Svg, graph().to_dot_string().unwrap())]);
+}
+
+#[test]
+fn missing_executable() {
+    let exe = Executable::new().program("/nonexistent/dot");
+    let error = graph().render_with(&exe, Format::Svg, Engine::Dot).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::NotFound);
+}
+
+#[cfg(unix)]
+fn script(name: &str, body: &str) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = format!("{}/{}", env!("CARGO_TARGET_TMPDIR"), name);
+    std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[cfg(unix)]
+#[test]
+fn executable() {
+    let exe = Executable::new().program(&script("fake_dot", r#"echo "warning: $@" >&2; cat"#));
+    let rendered = graph().render_with(&exe, Format::Plain, Engine::Fdp).unwrap();
+    assert_eq!(String::from_utf8(rendered.output).unwrap(), graph().to_dot_string().unwrap());
+    assert_eq!(rendered.stderr, "warning: -Kfdp -Tplain\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn failure_and_timeout() {
+    let exe = Executable::new().program(&script("failing_dot", "echo 'syntax error in line 1' >&2; exit 1"));
+    let error = graph().render_with(&exe, Format::Svg, Engine::Dot).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::Other);
+    assert!(error.to_string().contains("syntax error in line 1"));
+
+    let exe = Executable::new().program(&script("slow_dot", "exec sleep 10")).timeout(Duration::from_millis(100));
+    let error = graph().render_with(&exe, Format::Svg, Engine::Dot).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+    assert_eq!(error.to_string(), "dot did not finish within 100ms");
+
+    let exe = Executable::new().program(&script("stuck_dot", "echo 'laying out 1000 nodes' >&2; exec sleep 10")).timeout(Duration::from_millis(500));
+    let error = graph().render_with(&exe, Format::Svg, Engine::Dot).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+    assert_eq!(error.to_string(), "dot did not finish within 500ms: laying out 1000 nodes");
+}
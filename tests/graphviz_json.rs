@@ -0,0 +1,147 @@
+use dot_graph::{Arrow, ArrowShape, Edge, Fill, Graph, Kind, Node, Side, Style, Subgraph};
+
+#[test]
+fn to_graphviz_json() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").label("process #1").color(Some("blue"));
+    c0.add_node(Node::new("a0"));
+    c0.add_node(Node::new("a1").style(Style::Filled));
+    c0.add_edge(Edge::new("a0", "a1", ""));
+    graph.add_subgraph(c0);
+    graph.add_node(Node::new("start").shape(Some("Mdiamond")));
+    graph.add_edge(Edge::chain(&["start", "a0", "end"], "go").end_arrow(Arrow::from_arrow(ArrowShape::Diamond(Fill::Open, Side::Left))));
+    assert_eq!(graph.to_graphviz_json(),
+r#"{
+  "name": "G",
+  "directed": true,
+  "strict": false,
+  "_subgraph_cnt": 1,
+  "objects": [
+    {
+      "_gvid": 0,
+      "name": "cluster_0",
+      "label": "process #1",
+      "color": "blue",
+      "edges": [0],
+      "nodes": [1, 2]
+    },
+    {
+      "_gvid": 1,
+      "name": "a0",
+      "label": "a0"
+    },
+    {
+      "_gvid": 2,
+      "name": "a1",
+      "label": "a1",
+      "style": "filled"
+    },
+    {
+      "_gvid": 3,
+      "name": "start",
+      "label": "start",
+      "shape": "Mdiamond"
+    },
+    {
+      "_gvid": 4,
+      "name": "end",
+      "label": "end"
+    }
+  ],
+  "edges": [
+    {
+      "_gvid": 0,
+      "tail": 1,
+      "head": 2,
+      "label": ""
+    },
+    {
+      "_gvid": 1,
+      "tail": 3,
+      "head": 1,
+      "label": "go",
+      "arrowhead": "oldiamond"
+    },
+    {
+      "_gvid": 2,
+      "tail": 1,
+      "head": 4,
+      "label": "go",
+      "arrowhead": "oldiamond"
+    }
+  ]
+}
+"#);
+}
+
+#[test]
+fn graphviz_json_round_trip() {
+    let mut graph = Graph::new("g", Kind::Graph).url("https://example.com/".into());
+    let mut c0 = Subgraph::new("cluster_0").label(r#"\"quoted\""#).style(Style::Filled);
+    c0.add_node(Node::new("a").color(Some("red")));
+    c0.add_node(Node::new("b"));
+    c0.add_edge(Edge::new("a", "b", "inner"));
+    graph.add_subgraph(c0.clone());
+    graph.add_node(Node::new("c").url("https://example.com/c".into()));
+    graph.add_edge(Edge::new("c", "a", "").ltail(&c0)
+        .start_arrow(Arrow::from_arrow(ArrowShape::tee()))
+        .end_arrow([ArrowShape::Inv(Fill::Filled, Side::Both), ArrowShape::Dot(Fill::Open)].into()));
+
+    let back = Graph::from_graphviz_json(&graph.to_graphviz_json()).unwrap();
+    assert_eq!(back.to_dot_string().unwrap(), graph.to_dot_string().unwrap());
+}
+
+#[test]
+fn from_graphviz_json_output() {
+    // as printed by `echo 'digraph G { subgraph s { x } x -> y }' | dot -Tjson0`
+    let json = r#"{
+  "name": "G",
+  "directed": true,
+  "strict": false,
+  "_subgraph_cnt": 1,
+  "objects": [
+    {"_gvid": 0, "name": "s", "nodes": [1]},
+    {"_gvid": 1, "name": "x", "label": "\\N"},
+    {"_gvid": 2, "name": "y", "label": "\\N"}
+  ],
+  "edges": [
+    {"_gvid": 0, "tail": 1, "head": 2}
+  ]
+}"#;
+    let graph = Graph::from_graphviz_json(json).unwrap();
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    "x"[label="x"];
+    "y"[label="y"];
+    "x" -> "y"[label=""];
+}
+"#);
+}
+
+#[test]
+fn graphviz_json_quotes() {
+    let json = r#"{"name": "G", "directed": true, "_subgraph_cnt": 0, "objects": [{"_gvid": 0, "name": "a", "label": "say \"hi\"\\n"}]}"#;
+    let graph = Graph::from_graphviz_json(json).unwrap();
+    let dot = graph.to_dot_string().unwrap();
+    assert_eq!(dot, "digraph G {\n    \"a\"[label=\"say \\\"hi\\\"\\n\"];\n}\n");
+    let back = Graph::from_dot_string(&dot).unwrap();
+    assert_eq!(back.to_dot_string().unwrap(), dot);
+    assert!(back.to_graphviz_json().contains(r#""label": "say \"hi\"\\n""#));
+}
+
+#[test]
+fn from_graphviz_json_errors() {
+    assert!(Graph::from_graphviz_json("{").is_err());
+    assert!(Graph::from_graphviz_json(r#"{"directed": true}"#).is_err());
+    assert!(Graph::from_graphviz_json(r#"{"name": "G", "objects": [{"name": "not valid"}]}"#).is_err());
+    assert!(Graph::from_graphviz_json(r#"{"name": "G", "objects": [{"name": "a"}], "edges": [{"tail": 0, "head": 5}]}"#).is_err());
+    assert!(Graph::from_graphviz_json(r#"{"name": "G\ud800"}"#).is_err());
+    assert!(Graph::from_graphviz_json(r#"{"name": "G\ud800\u0041"}"#).is_err());
+    assert!(Graph::from_graphviz_json(r#"{"name": "G\ud800\ud800"}"#).is_err());
+    let error = |json: &str| Graph::from_graphviz_json(json).err().unwrap().to_string();
+    assert!(error(r#"{"name": "G\u+041"}"#).ends_with("invalid escape"));
+    let nested = format!("{{\"name\": \"G\", \"objects\": {}{}}}", "[".repeat(100_000), "]".repeat(100_000));
+    assert!(error(&nested).ends_with("nested too deeply"));
+    let graph = Graph::from_graphviz_json(r#"{"name": "G", "objects": [{"name": "a", "label": "\ud83d\ude00"}]}"#).unwrap();
+    assert!(graph.to_dot_string().unwrap().contains("[label=\"\u{1F600}\"]"));
+}
@@ -0,0 +1,124 @@
+use dot_graph::{algo, highlight::Highlight, Edge, Graph, Kind, Node, Style, Subgraph};
+
+#[test]
+fn highlight_path() {
+    let mut graph = Graph::new("G", Kind::Graph);
+    let mut c0 = Subgraph::new("cluster_0");
+    c0.add_node(Node::new("a"));
+    c0.add_edge(Edge::chain(&["a", "b", "c"], ""));
+    graph.add_subgraph(c0);
+    graph.add_edge(Edge::new("d", "c", ""));
+    let path = algo::shortest_path(&graph, "b", "d").unwrap();
+    graph.highlight_path(&path, &Highlight { style: Style::Dashed, color: None });
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"graph G {
+    subgraph cluster_0 {
+        label="";
+        "a"[label="a"];
+        "a" -- "b"[label=""];
+        "b" -- "c"[label=""][style="dashed"];
+    }
+    "b"[label="b"][style="dashed"];
+    "c"[label="c"][style="dashed"];
+    "d"[label="d"][style="dashed"];
+    "d" -- "c"[label=""][style="dashed"];
+}
+"#);
+}
+
+#[test]
+fn highlight_cycles() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_node(Node::new("a"));
+    graph.add_edge(Edge::chain(&["a", "b", "c", "b"], ""));
+    graph.add_edge(Edge::new("d", "d", ""));
+    assert!(graph.highlight_cycles(&Highlight::default()));
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    "a"[label="a"];
+    "b"[label="b"][style="bold"][color="red"];
+    "c"[label="c"][style="bold"][color="red"];
+    "d"[label="d"][style="bold"][color="red"];
+    "a" -> "b"[label=""];
+    "b" -> "c"[label=""][style="bold"][color="red"];
+    "c" -> "b"[label=""][style="bold"][color="red"];
+    "d" -> "d"[label=""][style="bold"][color="red"];
+}
+"#);
+
+    let mut tree = Graph::new("G", Kind::Graph);
+    tree.add_edge(Edge::chain(&["a", "b", "c"], ""));
+    assert!(!tree.highlight_cycles(&Highlight::default()));
+    tree.add_edge(Edge::new("a", "c", ""));
+    tree.add_edge(Edge::new("c", "x", ""));
+    assert!(tree.highlight_cycles(&Highlight::default()));
+    assert!(tree.to_dot_string().unwrap().contains("\"c\" -- \"x\"[label=\"\"];"));
+    assert!(tree.to_dot_string().unwrap().contains("\"a\" -- \"c\"[label=\"\"][style=\"bold\"][color=\"red\"];"));
+
+
+    // two cycles joined by a bridge, parallel edges and a loop
+    let mut graph = Graph::new("G", Kind::Graph);
+    graph.add_edge(Edge::chain(&["a", "b", "c", "a"], ""));
+    graph.add_edge(Edge::new("c", "d", ""));
+    graph.add_edge(Edge::chain(&["d", "e", "f", "d"], ""));
+    graph.add_edge(Edge::new("f", "g", ""));
+    graph.add_edge(Edge::new("g", "h", ""));
+    graph.add_edge(Edge::new("h", "g", ""));
+    graph.add_edge(Edge::new("i", "i", ""));
+    graph.add_edge(Edge::new("i", "j", ""));
+    assert!(graph.highlight_cycles(&Highlight { style: Style::Bold, color: None }));
+    let dot = graph.to_dot_string().unwrap();
+    let bold: Vec<&str> = dot.lines().filter(|l| l.contains(" -- ") && l.contains("bold")).map(|l| l.trim().split('[').next().unwrap()).collect();
+    assert_eq!(bold, [r#""a" -- "b" -- "c" -- "a""#, r#""d" -- "e" -- "f" -- "d""#, r#""g" -- "h""#, r#""h" -- "g""#, r#""i" -- "i""#]);
+}
+
+#[test]
+fn cluster_by_scc() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0");
+    c0.add_node(Node::new("b").shape(Some("box")));
+    c0.add_node(Node::new("x"));
+    c0.add_edge(Edge::new("b", "c", ""));
+    graph.add_subgraph(c0);
+    graph.add_edge(Edge::new("a", "b", ""));
+    graph.add_edge(Edge::new("c", "b", ""));
+    graph.add_edge(Edge::new("e", "f", ""));
+    graph.add_edge(Edge::new("f", "e", ""));
+    assert_eq!(graph.cluster_by_scc(), ["cluster_scc_0", "cluster_scc_1"]);
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    subgraph cluster_0 {
+        label="";
+        "x"[label="x"];
+    }
+    subgraph cluster_scc_0 {
+        label="";
+        "b"[label="b"][shape="box"];
+        "c"[label="c"];
+    }
+    subgraph cluster_scc_1 {
+        label="";
+        "e"[label="e"];
+        "f"[label="f"];
+    }
+    "a" -> "b"[label=""];
+    "c" -> "b"[label=""];
+    "e" -> "f"[label=""];
+    "f" -> "e"[label=""];
+    "b" -> "c"[label=""];
+}
+"#);
+}
+
+#[test]
+fn highlight_undeclared_names() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::chain(&["1", "x y", "1"], ""));
+    graph.highlight_path(&["1", "x y"], &Highlight { style: Style::Bold, color: None });
+    assert!(graph.to_dot_string().unwrap().contains("\"x y\"[label=\"x y\"][style=\"bold\"];"));
+
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::chain(&["1", "x y", "1"], ""));
+    assert_eq!(graph.cluster_by_scc(), ["cluster_scc_0"]);
+    assert!(graph.to_dot_string().unwrap().contains("subgraph cluster_scc_0 {\n        label=\"\";\n        \"1\"[label=\"1\"];\n        \"x y\"[label=\"x y\"];"));
+}
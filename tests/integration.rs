@@ -1,7 +1,7 @@
 
 #[cfg(test)]
 mod tests {
-    use dot_graph::{Graph, Kind, Node, Edge, Style, Arrow, ArrowShape, Side, Subgraph};
+    use dot_graph::{Graph, Kind, Node, Edge, Endpoint, Style, Arrow, ArrowShape, Side, Subgraph};
 
     // All of the tests use raw-strings as the format for the expected outputs,
     // so that you can cut-and-paste the content into a .dot file yourself to
@@ -482,4 +482,88 @@ r#"digraph single_edge {
 }
 "#);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn edge_chain() {
+        let mut graph = Graph::new("di", Kind::Digraph);
+        graph.add_edge(Edge::chain(&["a", "b", "c"], "").color(Some("red")));
+        assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph di {
+    "a" -> "b" -> "c"[label=""][color="red"];
+}
+"#);
+    }
+
+    #[test]
+    #[should_panic]
+    fn edge_chain_too_short() {
+        Edge::chain(&["a"], "");
+    }
+
+    #[test]
+    fn edge_to_subgraph() {
+        let mut graph = Graph::new("g", Kind::Graph);
+        let mut c0 = Subgraph::new("cluster_0").label("");
+        c0.add_nodes(vec![Node::new("b"), Node::new("c"), Node::new("d")]);
+        graph.add_edge(Edge::new("a", &c0, ""));
+        graph.add_edge(Edge::chain(&[Endpoint::from(&c0), Endpoint::from("e")], ""));
+        graph.add_subgraph(c0);
+        assert_eq!(graph.to_dot_string().unwrap(),
+r#"graph g {
+    subgraph cluster_0 {
+        label="";
+        "b"[label="b"];
+        "c"[label="c"];
+        "d"[label="d"];
+    }
+    "a" -- {"b" "c" "d"}[label=""];
+    {"b" "c" "d"} -- "e"[label=""];
+}
+"#);
+    }
+
+    #[test]
+    fn edge_to_subgraph_keeps_its_nodes() {
+        let mut c0 = Subgraph::new("cluster_0");
+        c0.add_node(Node::new("b"));
+        let edge = Edge::new("a", &c0, "");
+        c0.add_node(Node::new("c"));
+        assert_eq!(edge.node_pairs(), [(String::from("a"), String::from("b"))]);
+    }
+
+    #[test]
+    fn edge_lhead_ltail() {
+        let mut graph = Graph::new("di", Kind::Digraph);
+        let mut c0 = Subgraph::new("cluster_0").label("");
+        c0.add_node(Node::new("a"));
+        let mut c1 = Subgraph::new("cluster_1").label("");
+        c1.add_node(Node::new("b"));
+        graph.add_edge(Edge::new("a", "b", "").ltail(&c0).lhead(&c1));
+        graph.add_subgraph(c0);
+        graph.add_subgraph(c1);
+        assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph di {
+    compound=true
+    subgraph cluster_0 {
+        label="";
+        "a"[label="a"];
+    }
+    subgraph cluster_1 {
+        label="";
+        "b"[label="b"];
+    }
+    "a" -> "b"[label=""][lhead="cluster_1"][ltail="cluster_0"];
+}
+"#);
+    }
+
+    #[test]
+    fn subgraph_edge_lhead_sets_compound() {
+        let mut graph = Graph::new("di", Kind::Digraph);
+        let mut c0 = Subgraph::new("cluster_0").label("");
+        c0.add_node(Node::new("a"));
+        c0.add_edge(Edge::new("a", "a", "").lhead(&c0.clone()));
+        graph.add_subgraph(c0);
+        assert!(graph.to_dot_string().unwrap().contains("    compound=true\n"));
+    }
+}
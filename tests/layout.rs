@@ -0,0 +1,185 @@
+use dot_graph::{layout::{ForceOptions, LayoutOptions, LayoutResult}, Edge, Graph, Kind, Node, Subgraph};
+
+fn polyline() -> LayoutOptions {
+    LayoutOptions { splines: false, ..Default::default() }
+}
+
+fn overlap(layout: &LayoutResult, a: &str, b: &str) -> bool {
+    let (a, b) = (layout.node(a).unwrap(), layout.node(b).unwrap());
+    (a.x - b.x).abs() < (a.width + b.width) / 2.0 && (a.y - b.y).abs() < (a.height + b.height) / 2.0
+}
+
+#[test]
+fn layered_ranks() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::chain(&["a", "b", "c", "d"], ""));
+    graph.add_edge(Edge::new("a", "d", ""));
+    graph.add_edge(Edge::new("x", "d", ""));
+    let layout = graph.layered_layout(&polyline());
+
+    let y = |n: &str| layout.node(n).unwrap().y;
+    assert!(y("a") < y("b") && y("b") < y("c") && y("c") < y("d"));
+    // sources are pulled down to their successors
+    assert_eq!(y("x"), y("c"));
+    assert_eq!(layout.nodes.len(), 5);
+    assert_eq!(layout.edges.len(), 5);
+
+    // the long edge a -> d goes through a point on every rank
+    let long = layout.edges.iter().find(|e| e.from == "a" && e.to == "d").unwrap();
+    assert_eq!(long.points.len(), 4);
+    assert_eq!(long.edge, 1);
+    for (a, b) in [("a", "b"), ("b", "x"), ("a", "x"), ("c", "x")] {
+        assert!(!overlap(&layout, a, b), "{} and {} overlap", a, b);
+    }
+    for n in layout.nodes.iter() {
+        assert!(n.x - n.width / 2.0 >= 0.0 && n.x + n.width / 2.0 <= layout.width);
+        assert!(n.y - n.height / 2.0 >= 0.0 && n.y + n.height / 2.0 <= layout.height);
+    }
+}
+
+#[test]
+fn layered_cycles_and_loops() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::chain(&["a", "b", "c", "a"], "back"));
+    graph.add_edge(Edge::new("b", "b", ""));
+    let layout = graph.layered_layout(&polyline());
+
+    let (a, c) = (layout.node("a").unwrap(), layout.node("c").unwrap());
+    assert!(a.y < c.y);
+    // the reversed edge still starts at c and ends at a
+    let back = layout.edges.iter().find(|e| e.from == "c" && e.to == "a").unwrap();
+    let (start, end) = (back.points[0], back.points[back.points.len() - 1]);
+    assert!((start.y - c.y).abs() <= c.height / 2.0 + 1e-9);
+    assert!((end.y - a.y).abs() <= a.height / 2.0 + 1e-9);
+    assert!(back.label_pos.is_some());
+
+    let b = layout.node("b").unwrap();
+    let own = layout.edges.iter().find(|e| e.from == "b" && e.to == "b").unwrap();
+    assert!(own.points.iter().all(|p| p.x >= b.x));
+    assert!(own.label_pos.is_none());
+}
+
+#[test]
+fn layered_same_rank() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::chain(&["a", "b", "c"], ""));
+    graph.add_edge(Edge::new("a", "d", ""));
+    graph.same_rank(&["c", "d"]);
+    assert!(graph.to_dot_string().unwrap().contains("    {rank=same; \"c\"; \"d\"; }\n"));
+
+    let layout = graph.layered_layout(&polyline());
+    assert_eq!(layout.node("c").unwrap().y, layout.node("d").unwrap().y);
+    assert!(!overlap(&layout, "c", "d"));
+}
+
+#[test]
+fn layered_clusters() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").label("zero");
+    c0.add_node(Node::new("a0"));
+    c0.add_node(Node::new("a1"));
+    c0.add_node(Node::new("a2"));
+    c0.add_edge(Edge::chain(&["a0", "a1", "a2"], ""));
+    let mut c1 = Subgraph::new("cluster_1");
+    c1.add_node(Node::new("b0"));
+    c1.add_node(Node::new("b1"));
+    c1.add_edge(Edge::new("b0", "b1", ""));
+    graph.add_subgraph(c0);
+    graph.add_subgraph(c1);
+    graph.add_edge(Edge::new("start", "a0", ""));
+    graph.add_edge(Edge::new("start", "b0", ""));
+    graph.add_edge(Edge::new("a1", "b1", ""));
+    graph.add_edge(Edge::new("b1", "end", ""));
+    graph.add_edge(Edge::new("a2", "end", ""));
+    let layout = graph.layered_layout(&LayoutOptions::default());
+
+    for (cluster, members) in [("cluster_0", vec!["a0", "a1", "a2"]), ("cluster_1", vec!["b0", "b1"])] {
+        let c = layout.cluster(cluster).unwrap();
+        for n in layout.nodes.iter() {
+            let inside = (n.x - c.x).abs() < (n.width + c.width) / 2.0 && (n.y - c.y).abs() < (n.height + c.height) / 2.0;
+            assert_eq!(inside, members.contains(&n.name.as_str()), "{} in {}", n.name, cluster);
+        }
+    }
+    let (c0, c1) = (layout.cluster("cluster_0").unwrap(), layout.cluster("cluster_1").unwrap());
+    assert!((c0.x - c1.x).abs() >= (c0.width + c1.width) / 2.0);
+
+    // splines have a start point and three points per curve
+    for e in layout.edges.iter() {
+        assert!(e.spline);
+        assert_eq!(e.points.len() % 3, 1);
+    }
+}
+
+#[test]
+fn layered_empty() {
+    let layout = Graph::new("G", Kind::Digraph).layered_layout(&LayoutOptions::default());
+    assert!(layout.nodes.is_empty() && layout.edges.is_empty());
+}
+
+fn mesh() -> Graph {
+    let mut graph = Graph::new("mesh", Kind::Graph);
+    graph.add_edge(Edge::chain(&["a", "b", "c", "d", "e", "f", "a"], ""));
+    graph.add_edge(Edge::new("hub", "a", ""));
+    graph.add_edge(Edge::new("hub", "c", ""));
+    graph.add_edge(Edge::new("hub", "e", ""));
+    graph.add_edge(Edge::new("x", "y", "alone"));
+    graph.add_edge(Edge::new("y", "y", ""));
+    graph
+}
+
+#[test]
+fn force_layout() {
+    let graph = mesh();
+    let layout = graph.force_layout(&ForceOptions::default());
+    assert_eq!(layout.nodes.len(), 9);
+    assert_eq!(layout.edges.len(), 11);
+
+    let names: Vec<&str> = layout.nodes.iter().map(|n| n.name.as_str()).collect();
+    for (i, a) in names.iter().enumerate() {
+        for b in names[i + 1..].iter() {
+            assert!(!overlap(&layout, a, b), "{} and {} overlap", a, b);
+        }
+    }
+    for n in layout.nodes.iter() {
+        assert!(n.x - n.width / 2.0 >= 0.0 && n.x + n.width / 2.0 <= layout.width);
+        assert!(n.y - n.height / 2.0 >= 0.0 && n.y + n.height / 2.0 <= layout.height);
+    }
+
+    // connected nodes are closer than unconnected ones
+    let distance = |a: &str, b: &str| {
+        let (a, b) = (layout.node(a).unwrap(), layout.node(b).unwrap());
+        ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+    };
+    assert!(distance("a", "b") < distance("a", "d"));
+    assert!(distance("hub", "c") < distance("hub", "d"));
+    assert!(layout.edges.iter().all(|e| !e.spline));
+    assert!(layout.edges.iter().find(|e| e.from == "x").unwrap().label_pos.is_some());
+}
+
+#[test]
+fn force_layout_is_deterministic() {
+    let graph = mesh();
+    let options = ForceOptions::default();
+    assert_eq!(graph.force_layout(&options), graph.force_layout(&options));
+    let other = graph.force_layout(&ForceOptions { seed: 7, ..Default::default() });
+    assert_ne!(graph.force_layout(&options), other);
+}
+
+#[test]
+fn force_layout_clusters() {
+    let mut graph = Graph::new("G", Kind::Graph);
+    let mut c0 = Subgraph::new("cluster_0").label("services");
+    c0.add_node(Node::new("api"));
+    c0.add_node(Node::new("db"));
+    graph.add_subgraph(c0);
+    graph.add_edge(Edge::new("api", "db", ""));
+    graph.add_edge(Edge::new("api", "web", ""));
+    let layout = graph.force_layout(&ForceOptions { iterations: 50, ..Default::default() });
+
+    let c = layout.cluster("cluster_0").unwrap();
+    for name in ["api", "db"] {
+        let n = layout.node(name).unwrap();
+        assert!((n.x - c.x).abs() + n.width / 2.0 <= c.width / 2.0);
+        assert!((n.y - c.y).abs() + n.height / 2.0 <= c.height / 2.0);
+    }
+}
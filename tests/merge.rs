@@ -0,0 +1,112 @@
+use dot_graph::{merge::{Conflict, MergeError, MergePolicy}, Edge, Graph, Kind, Node, Style, Subgraph};
+
+fn left() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").label("zero");
+    c0.add_node(Node::new("a").color(Some("blue")));
+    graph.add_subgraph(c0);
+    graph.add_node(Node::new("b"));
+    graph.add_edge(Edge::new("a", "b", "ab"));
+    graph
+}
+
+fn right() -> Graph {
+    let mut graph = Graph::new("H", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").style(Style::Dashed);
+    c0.add_node(Node::new("b"));
+    graph.add_subgraph(c0);
+    let mut c1 = Subgraph::new("cluster_1");
+    c1.add_node(Node::new("c"));
+    c1.add_edge(Edge::new("c", "d", ""));
+    graph.add_subgraph(c1);
+    graph.add_node(Node::new("a").color(Some("red")).shape(Some("box")));
+    graph.add_edge(Edge::new("a", "b", "").color(Some("green")));
+    graph.add_edge(Edge::new("a", "b", "again"));
+    graph
+}
+
+#[test]
+fn merge_union() {
+    let mut graph = left();
+    graph.merge(&right(), &MergePolicy::default()).unwrap();
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    subgraph cluster_0 {
+        label="zero";
+        style="dashed";
+        "a"[label="a"][color="blue"][shape="box"];
+        "b"[label="b"];
+    }
+    subgraph cluster_1 {
+        label="";
+        "c"[label="c"];
+        "c" -> "d"[label=""];
+    }
+    "a" -> "b"[label="ab"][color="green"];
+    "a" -> "b"[label="again"];
+}
+"#);
+}
+
+#[test]
+fn merge_conflicts() {
+    let mut graph = left();
+    graph.merge(&right(), &MergePolicy { on_conflict: Conflict::KeepRight, ..Default::default() }).unwrap();
+    assert!(graph.to_dot_string().unwrap().contains(r#""a"[label="a"][color="red"][shape="box"];"#));
+
+    let mut graph = left();
+    let policy = MergePolicy { on_conflict: Conflict::Error, ..Default::default() };
+    let e = graph.merge(&right(), &policy).unwrap_err();
+    assert_eq!(e.to_string(), r#"color of node a: Some("blue") conflicts with Some("red")"#);
+    assert!(matches!(e, MergeError::Conflict { ref what, .. } if what == "color of node a"));
+    assert_eq!(graph.to_dot_string().unwrap(), left().to_dot_string().unwrap());
+
+    // a node in two clusters
+    let mut other = Graph::new("H", Kind::Digraph);
+    let mut c2 = Subgraph::new("cluster_2");
+    c2.add_node(Node::new("a"));
+    other.add_subgraph(c2);
+    let e = left().merge(&other, &policy).unwrap_err();
+    assert_eq!(e.to_string(), r#"cluster of node a: Some("cluster_0") conflicts with Some("cluster_2")"#);
+    let mut graph = left();
+    graph.merge(&other, &MergePolicy { on_conflict: Conflict::KeepRight, ..Default::default() }).unwrap();
+    assert!(graph.to_dot_string().unwrap().contains("subgraph cluster_2 {\n        label=\"\";\n        \"a\"[label=\"a\"][color=\"blue\"];"));
+
+    let e = left().merge(&Graph::new("H", Kind::Graph), &MergePolicy::default()).unwrap_err();
+    assert_eq!(e, MergeError::KindMismatch { left: Kind::Digraph, right: Kind::Graph });
+    assert_eq!(e.to_string(), "cannot merge a graph into a digraph");
+}
+
+#[test]
+fn merge_prefix() {
+    let mut graph = left();
+    let policy = MergePolicy { prefix: Some(String::from("r.")), ..Default::default() };
+    graph.merge(&right(), &policy).unwrap();
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    subgraph cluster_0 {
+        label="zero";
+        "a"[label="a"][color="blue"];
+    }
+    subgraph cluster_r_0 {
+        label="";
+        style="dashed";
+        "r.b"[label="b"];
+    }
+    subgraph cluster_r_1 {
+        label="";
+        "r.c"[label="c"];
+        "r.c" -> "r.d"[label=""];
+    }
+    "b"[label="b"];
+    "r.a"[label="a"][color="red"][shape="box"];
+    "r.d"[label="d"];
+    "a" -> "b"[label="ab"];
+    "r.a" -> "r.b"[label=""][color="green"];
+    "r.a" -> "r.b"[label="again"];
+}
+"#);
+
+    let policy = MergePolicy { prefix: Some(String::from("1")), ..Default::default() };
+    assert_eq!(left().merge(&right(), &policy).unwrap_err(), MergeError::InvalidPrefix { name: String::from("1b") });
+}
@@ -0,0 +1,122 @@
+use dot_graph::{
+    mermaid::{Direction, Untranslated},
+    Arrow, ArrowShape, Edge, Fill, Graph, Kind, Node, Style, Subgraph,
+};
+
+#[test]
+fn mermaid_flowchart() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").label("process #1").style(Style::Filled).color(Some("lightgrey"));
+    c0.add_node(Node::new("a0").style(Style::Filled).color(Some("white")));
+    c0.add_node(Node::new("a1").style(Style::Filled).color(Some("white")));
+    c0.add_edge(Edge::new("a0", "a1", ""));
+    graph.add_subgraph(c0.clone());
+    graph.add_node(Node::new("start").shape(Some("Mdiamond")).url("https://example.com/".into()));
+    graph.add_node(Node::new("end").shape(Some("Msquare")).style(Style::Dotted));
+    graph.add_edge(Edge::chain(&["start", "a0"], "go").style(Style::Bold).color(Some("red")));
+    graph.add_edge(Edge::new("a1", "end", "\"done\"").end_arrow(Arrow::from_arrow(ArrowShape::Dot(Fill::Filled))));
+    graph.add_edge(Edge::new("start", &c0, "").style(Style::Invisible));
+    graph.add_edge(Edge::chain(&["end", "x.y", "start"], "").style(Style::Dashed).color(Some("blue")));
+
+    let flowchart = graph.to_mermaid(Direction::TopDown);
+    assert_eq!(flowchart.text,
+r##"flowchart TD
+    subgraph cluster_0 ["process #1"]
+        a0["a0"]
+        a1["a1"]
+        a0 --> a1
+    end
+    start{"start"}
+    end_[["end"]]
+    x_y["x.y"]
+    start ==>|"go"| a0
+    a1 --o|"#quot;done#quot;"| end_
+    start ~~~ cluster_0
+    end_ -.-> x_y -.-> start
+    classDef class0 stroke:white,fill:white
+    class a0,a1 class0
+    classDef class1 stroke-dasharray:2 2
+    class end_ class1
+    style cluster_0 fill:lightgrey
+    linkStyle 1 stroke:red
+    linkStyle 4,5 stroke:blue
+    click start href "https://example.com/"
+"##);
+    assert!(flowchart.untranslated.is_empty());
+}
+
+#[test]
+fn mermaid_undirected() {
+    let mut graph = Graph::new("g", Kind::Graph);
+    graph.add_node(Node::new("a").shape(Some("circle")));
+    graph.add_edge(Edge::new("a", "b", "").style(Style::Dotted));
+    graph.add_edge(Edge::new("a", "b", "").style(Style::Bold));
+    graph.add_edge(Edge::new("a", "b", ""));
+    assert_eq!(graph.to_mermaid(Direction::LeftRight).text,
+r#"flowchart LR
+    a(("a"))
+    b["b"]
+    a -.- b
+    a === b
+    a --- b
+"#);
+}
+
+#[test]
+fn mermaid_undeclared_names() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::chain(&["a", "1", "x y"], ""));
+    assert_eq!(graph.to_mermaid(Direction::TopDown).text,
+r#"flowchart TD
+    a["a"]
+    1["1"]
+    x_y["x y"]
+    a --> 1 --> x_y
+"#);
+}
+
+#[test]
+fn mermaid_subgraph_endpoints() {
+    let graph = Graph::from_dot_string("digraph { a -> {b c} }").unwrap();
+    assert_eq!(graph.to_mermaid(Direction::TopDown).text,
+r#"flowchart TD
+    b["b"]
+    c["c"]
+    a["a"]
+    a --> b
+    a --> c
+"#);
+
+    // a cluster is linked to as a whole
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0");
+    c0.add_node(Node::new("b"));
+    graph.add_edge(Edge::new("a", &c0, "").color(Some("red")));
+    graph.add_subgraph(c0);
+    let text = graph.to_mermaid(Direction::TopDown).text;
+    assert!(text.contains("    a --> cluster_0\n"), "{}", text);
+    assert!(text.contains("linkStyle 0 stroke:red"), "{}", text);
+}
+
+#[test]
+fn mermaid_untranslated() {
+    let mut graph = Graph::new("G", Kind::Digraph).url("https://example.com/".into());
+    let c0 = Subgraph::new("cluster_0");
+    graph.add_node(Node::new("a").shape(Some("star")).style(Style::Wedged));
+    graph.add_edge(Edge::new("a", "b", "").end_arrow(Arrow::from_arrow(ArrowShape::crow())).lhead(&c0));
+    graph.add_subgraph(c0);
+
+    let flowchart = graph.to_mermaid(Direction::TopDown);
+    let report: Vec<(&str, &str, &str)> = flowchart.untranslated
+        .iter()
+        .map(|Untranslated { element, attribute, value }| (element.as_str(), attribute.as_str(), value.as_str()))
+        .collect();
+    assert_eq!(report, vec![
+        ("graph", "URL", "https://example.com/"),
+        ("graph", "compound", "true"),
+        ("a", "shape", "star"),
+        ("a", "style", "wedged"),
+        ("a -> b", "arrowhead", "crow"),
+        ("a -> b", "lhead", "cluster_0"),
+    ]);
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "petgraph")]
+
+use dot_graph::{Edge, Graph, Kind, Node, Subgraph};
+use petgraph::{graphmap::GraphMap, stable_graph::StableGraph, Directed, Undirected};
+
+#[test]
+fn from_petgraph_with_display() {
+    let mut pg = petgraph::Graph::<&str, u32>::new();
+    let a = pg.add_node("a");
+    let b = pg.add_node("b");
+    pg.add_edge(a, b, 7);
+    let graph = Graph::from(&pg);
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    "N0"[label="a"];
+    "N1"[label="b"];
+    "N0" -> "N1"[label="7"];
+}
+"#);
+}
+
+#[test]
+fn from_petgraph_with_closures() {
+    let mut pg = petgraph::Graph::<u32, &str, Undirected>::new_undirected();
+    let a = pg.add_node(1);
+    let b = pg.add_node(2);
+    pg.add_edge(a, b, "heavy");
+    let graph = Graph::from_petgraph("g", &pg,
+        |w, node| node.label(&format!("v{}", w)).color(Some("red")),
+        |w, mut edge| edge.label(w).color(Some("blue")));
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"graph g {
+    "N0"[label="v1"][color="red"];
+    "N1"[label="v2"][color="red"];
+    "N0" -- "N1"[label="heavy"][color="blue"];
+}
+"#);
+}
+
+#[test]
+fn from_stable_graph_keeps_indices() {
+    let mut pg = StableGraph::<&str, &str>::new();
+    let a = pg.add_node("a");
+    let b = pg.add_node("b");
+    let c = pg.add_node("c");
+    pg.add_edge(a, c, "");
+    pg.remove_node(b);
+    let graph = Graph::from(&pg);
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    "N0"[label="a"];
+    "N2"[label="c"];
+    "N0" -> "N2"[label=""];
+}
+"#);
+}
+
+#[test]
+fn from_graph_map() {
+    let mut pg = GraphMap::<u32, &str, Directed>::new();
+    pg.add_edge(10, 20, "x");
+    let graph = Graph::from(&pg);
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    "N0"[label="10"];
+    "N1"[label="20"];
+    "N0" -> "N1"[label="x"];
+}
+"#);
+}
+
+#[test]
+fn into_petgraph() {
+    let mut graph = Graph::new("di", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0");
+    c0.add_node(Node::new("b").label("B"));
+    c0.add_node(Node::new("c"));
+    graph.add_node(Node::new("a"));
+    graph.add_edge(Edge::new("a", &c0, "fan"));
+    graph.add_edge(Edge::chain(&["c", "d", "a"], ""));
+    graph.add_subgraph(c0);
+
+    let pg: petgraph::Graph<Node, Edge> = (&graph).into();
+    let names: Vec<&str> = pg.node_weights().map(|n| n.name.as_str()).collect();
+    assert_eq!(names, vec!["b", "c", "a", "d"]);
+    assert_eq!(pg.edge_count(), 4);
+
+    let map: GraphMap<&str, Edge, Directed> = (&graph).into();
+    assert!(map.contains_edge("a", "b"));
+    assert!(map.contains_edge("a", "c"));
+    assert!(map.contains_edge("c", "d"));
+    assert!(map.contains_edge("d", "a"));
+    assert!(!map.contains_edge("b", "a"));
+}
+
+#[test]
+fn into_petgraph_with_undeclared_names() {
+    let mut graph = Graph::new("g", Kind::Graph);
+    graph.add_edge(Edge::new("a", "1", ""));
+    graph.add_edge(Edge::new("1", "x y", ""));
+
+    let pg: petgraph::Graph<Node, Edge, Undirected> = (&graph).into();
+    let names: Vec<&str> = pg.node_weights().map(|n| n.name.as_str()).collect();
+    assert_eq!(names, vec!["a", "1", "x y"]);
+    assert_eq!(pg.edge_count(), 2);
+}
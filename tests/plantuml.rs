@@ -0,0 +1,85 @@
+use dot_graph::{Arrow, ArrowShape, Edge, Endpoint, Fill, Graph, Kind, Node, Side, Style, Subgraph};
+
+#[test]
+fn plantuml_document() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").label("process #1").color(Some("lightgrey"));
+    c0.add_node(Node::new("a0"));
+    c0.add_node(Node::new("a1").shape(Some("cylinder")));
+    c0.add_edge(Edge::new("a0", "a1", ""));
+    let mut c1 = Subgraph::new("cluster_1");
+    c1.add_node(Node::new("b0").color(Some("#FF0000")));
+    graph.add_subgraph(c0.clone());
+    graph.add_subgraph(c1);
+    graph.add_node(Node::new("start").url("https://example.com/".into()));
+    graph.add_edge(Edge::new("start", "a0", "go").color(Some("red")).style(Style::Bold));
+    graph.add_edge(Edge::new("b0", "a1", "").end_arrow(Arrow::from_arrow(ArrowShape::Diamond(Fill::Filled, Side::Both))));
+    graph.add_edge(Edge::new("b0", "a0", "").end_arrow(Arrow::from_arrow(ArrowShape::Diamond(Fill::Open, Side::Both))));
+    graph.add_edge(Edge::new("a1", "b0", "extends").end_arrow(Arrow::from_arrow(ArrowShape::Normal(Fill::Open, Side::Both))));
+    graph.add_edge(Edge::new("a1", "start", "").style(Style::Dashed)
+        .start_arrow(Arrow::from_arrow(ArrowShape::normal())).end_arrow(Arrow::none()));
+    graph.add_edge(Edge::chain(&[Endpoint::from("x.y"), Endpoint::from(&c0)], "\"all\"").style(Style::Dotted));
+
+    assert_eq!(graph.to_plantuml(),
+r#"@startuml G
+package "process #1" as cluster_0 #lightgrey {
+  rectangle "a0" as a0
+  database "a1" as a1
+}
+rectangle "" as cluster_1 {
+  rectangle "b0" as b0 #FF0000
+}
+rectangle "start" as start [[https://example.com/]]
+rectangle "x.y" as x_y
+a0 --> a1
+start -[#red,bold]-> a0 : go
+b0 --* a1
+b0 --o a0
+a1 --|> b0 : extends
+a1 <-[dashed]- start
+x_y -[dotted]-> cluster_0 : "all"
+@enduml
+"#);
+}
+
+#[test]
+fn plantuml_undirected() {
+    let mut graph = Graph::new("g", Kind::Graph);
+    graph.add_edge(Edge::chain(&["a", "b", "c"], ""));
+    assert_eq!(graph.to_plantuml(),
+r#"@startuml g
+rectangle "a" as a
+rectangle "b" as b
+rectangle "c" as c
+a -- b
+b -- c
+@enduml
+"#);
+}
+
+#[test]
+fn plantuml_undeclared_names() {
+    let mut graph = Graph::new("G", Kind::Graph);
+    graph.add_edge(Edge::new("a", "x y", ""));
+    assert_eq!(graph.to_plantuml(),
+r#"@startuml G
+rectangle "a" as a
+rectangle "x y" as x_y
+a -- x_y
+@enduml
+"#);
+}
+
+#[test]
+fn plantuml_subgraph_endpoints() {
+    let graph = Graph::from_dot_string("digraph G { a -> {b c} }").unwrap();
+    assert_eq!(graph.to_plantuml(),
+r#"@startuml G
+rectangle "b" as b
+rectangle "c" as c
+rectangle "a" as a
+a --> b
+a --> c
+@enduml
+"#);
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "serde")]
+
+use dot_graph::{Arrow, ArrowShape, Edge, Fill, Graph, Kind, Node, Side, Style, Subgraph};
+
+fn sample() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph).url("https://example.com/".into());
+    let mut c0 = Subgraph::new("cluster_0").label("process #1").style(Style::Filled).color(Some("lightgrey"));
+    c0.add_node(Node::new("a0").style(Style::Filled).color(Some("white")));
+    c0.add_node(Node::new("a1"));
+    c0.add_edge(Edge::new("a0", "a1", "inner"));
+    graph.add_node(Node::new("start").shape(Some("Mdiamond")).url("https://example.com/start".into()));
+    graph.add_edge(Edge::new("start", &c0, "fan").style(Style::Invisible));
+    graph.add_edge(Edge::chain(&["start", "a0", "a1"], "")
+        .end_arrow(Arrow::from_arrow(ArrowShape::Diamond(Fill::Open, Side::Left)))
+        .start_arrow([ArrowShape::none(), ArrowShape::Crow(Side::Right)].into()));
+    graph.add_edge(Edge::new("start", "a1", "").lhead(&c0));
+    graph.add_subgraph(c0);
+    graph
+}
+
+#[test]
+fn json_round_trip() {
+    let graph = sample();
+    let json = serde_json::to_string(&graph).unwrap();
+    let back: Graph = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.to_dot_string().unwrap(), graph.to_dot_string().unwrap());
+    assert_eq!(serde_json::to_string(&back).unwrap(), json);
+}
+
+#[test]
+fn json_schema() {
+    let mut graph = Graph::new("g", Kind::Graph);
+    graph.add_node(Node::new("a").style(Style::Invisible));
+    graph.add_edge(Edge::new("a", "b", "E").end_arrow(Arrow::from_arrow(ArrowShape::Normal(Fill::Open, Side::Both))));
+    let value = serde_json::to_value(&graph).unwrap();
+    assert_eq!(value, serde_json::json!({
+        "name": "g",
+        "kind": "graph",
+        "url": "",
+        "nodes": [
+            {"name": "a", "label": "a", "style": "invis", "color": null, "shape": null, "url": "", "pos": null}
+        ],
+        "edges": [
+            {
+                "endpoints": [{"node": "a"}, {"node": "b"}],
+                "label": "E",
+                "label_url": "",
+                "url": "",
+                "style": "none",
+                "start_arrow": {"arrows": []},
+                "end_arrow": {"arrows": [{"normal": ["open", "both"]}]},
+                "color": null,
+                "penwidth": null,
+                "lhead": null,
+                "ltail": null,
+                "pos": null
+            }
+        ],
+        "subgraphs": [],
+        "compound": false,
+        "same_rank": [],
+        "bb": null
+    }));
+}
+
+#[test]
+fn json_compound_from_edges() {
+    let graph: Graph = serde_json::from_str(r#"{
+        "name": "di",
+        "kind": "digraph",
+        "compound": false,
+        "edges": [{"endpoints": [{"node": "a"}, {"node": "b"}], "label": "", "lhead": "cluster_0"}],
+        "subgraphs": [{"name": "cluster_0", "nodes": [{"name": "b", "label": "b"}]}]
+    }"#).unwrap();
+    assert!(graph.to_dot_string().unwrap().contains("    compound=true\n"));
+}
+
+#[test]
+fn json_subgraph_edges_follow_kind() {
+    let graph: Graph = serde_json::from_str(r#"{
+        "name": "g",
+        "kind": "graph",
+        "subgraphs": [{"name": "cluster_0", "edges": [{"endpoints": [{"node": "a"}, {"node": "b"}], "label": ""}]}]
+    }"#).unwrap();
+    let dot = graph.to_dot_string().unwrap();
+    assert!(dot.contains("        \"a\" -- \"b\"[label=\"\"];\n"), "{}", dot);
+    assert!(Graph::from_dot_string(&dot).is_ok());
+    assert!(!serde_json::to_string(&graph).unwrap().contains("edgeop"));
+}
+
+#[test]
+fn json_optional_fields() {
+    let graph: Graph = serde_json::from_str(r#"{
+        "name": "di",
+        "kind": "digraph",
+        "nodes": [{"name": "N0", "label": "zero", "color": "red"}],
+        "edges": [{"endpoints": [{"node": "N0"}, {"subgraph": {"name": "cluster_0", "nodes": ["N1"]}}], "label": ""}],
+        "subgraphs": [{"name": "cluster_0", "nodes": [{"name": "N1", "label": "one"}]}]
+    }"#).unwrap();
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph di {
+    subgraph cluster_0 {
+        label="";
+        "N1"[label="one"];
+    }
+    "N0"[label="zero"][color="red"];
+    "N0" -> {"N1"}[label=""];
+}
+"#);
+}
@@ -0,0 +1,93 @@
+use dot_graph::{simplify::Multiplicity, Edge, Graph, Kind, Node, Subgraph};
+
+#[test]
+fn transitive_reduction() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0");
+    c0.add_node(Node::new("a"));
+    c0.add_edge(Edge::new("a", "c", "direct"));
+    graph.add_subgraph(c0);
+    graph.add_edge(Edge::chain(&["a", "b", "c", "d"], ""));
+    graph.add_edge(Edge::chain(&["b", "d", "e"], ""));
+    graph.transitive_reduction().unwrap();
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    subgraph cluster_0 {
+        label="";
+        "a"[label="a"];
+    }
+    "a" -> "b" -> "c" -> "d"[label=""];
+    "d" -> "e"[label=""];
+}
+"#);
+
+    graph.add_edge(Edge::new("e", "a", ""));
+    assert_eq!(graph.transitive_reduction().unwrap_err(), ["a", "b", "c", "d", "e", "a"]);
+}
+
+#[test]
+fn transitive_reduction_long_chain() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::new("n0", "n199", ""));
+    graph.add_edge(Edge::new("n70", "n130", ""));
+    let names: Vec<String> = (0..200).map(|i| format!("n{i}")).collect();
+    graph.add_edge(Edge::chain(&names.iter().map(String::as_str).collect::<Vec<_>>(), ""));
+    graph.add_edge(Edge::new("n199", "tail", ""));
+    graph.add_edge(Edge::new("n64", "tail", ""));
+    graph.transitive_reduction().unwrap();
+    let dot = graph.to_dot_string().unwrap();
+    assert!(!dot.contains(r#""n0" -> "n199""#));
+    assert!(!dot.contains(r#""n70" -> "n130""#));
+    assert!(!dot.contains(r#""n64" -> "tail""#));
+    assert!(dot.contains(r#""n199" -> "tail""#));
+}
+
+#[test]
+fn transitive_reduction_condensed() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::chain(&["a", "b", "c", "a"], ""));
+    graph.add_edge(Edge::chain(&["c", "d", "e"], ""));
+    graph.add_edge(Edge::new("a", "e", ""));
+    graph.add_edge(Edge::new("b", "d", ""));
+    graph.transitive_reduction_condensed();
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    "a" -> "b" -> "c" -> "a"[label=""];
+    "c" -> "d" -> "e"[label=""];
+    "b" -> "d"[label=""];
+}
+"#);
+}
+
+#[test]
+fn merge_parallel_edges() {
+    let build = || {
+        let mut graph = Graph::new("G", Kind::Graph);
+        let mut c0 = Subgraph::new("cluster_0");
+        c0.add_edge(Edge::new("a", "b", "x"));
+        graph.add_subgraph(c0);
+        graph.add_edge(Edge::chain(&["b", "a", "c"], ""));
+        graph.add_edge(Edge::new("a", "b", "y").penwidth(Some(1.5)));
+        graph.add_edge(Edge::new("a", "b", "x"));
+        graph.add_edge(Edge::new("c", "d", ""));
+        graph.add_edge(Edge::new("d", "c", ""));
+        graph
+    };
+    let mut graph = build();
+    graph.merge_parallel_edges(Multiplicity::Label);
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"graph G {
+    subgraph cluster_0 {
+        label="";
+        "a" -- "b"[label="x, y"];
+    }
+    "a" -- "c"[label=""];
+    "c" -- "d"[label="2"];
+}
+"#);
+
+    let mut graph = build();
+    graph.merge_parallel_edges(Multiplicity::Penwidth);
+    assert!(graph.to_dot_string().unwrap().contains(r#""a" -- "b"[label="x"][penwidth=4.5];"#));
+    assert!(graph.to_dot_string().unwrap().contains(r#""c" -- "d"[label=""][penwidth=2];"#));
+}
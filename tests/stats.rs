@@ -0,0 +1,96 @@
+use dot_graph::{Edge, Graph, Kind, Node, Subgraph};
+
+fn services() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut net = Subgraph::new("cluster_net");
+    net.add_node(Node::new("http"));
+    net.add_node(Node::new("tls"));
+    net.add_edge(Edge::new("http", "tls", ""));
+    graph.add_subgraph(net);
+    graph.add_node(Node::new("docs"));
+    graph.add_edge(Edge::chain(&["app", "http", "db"], ""));
+    graph.add_edge(Edge::new("cli", "http", ""));
+    graph
+}
+
+#[test]
+fn stats() {
+    let stats = services().stats();
+    assert_eq!(stats.nodes, 6);
+    assert_eq!(stats.edges, 4);
+    assert_eq!(stats.clusters, 1);
+    assert_eq!(stats.degrees, vec![(0, 1), (1, 4), (4, 1)]);
+    assert_eq!(stats.nesting_depth, 1);
+    assert_eq!(stats.isolated, vec!["docs"]);
+    assert_eq!(stats.fan_in, vec![(String::from("http"), 2), (String::from("tls"), 1), (String::from("db"), 1)]);
+    assert_eq!(stats.fan_out[0], (String::from("http"), 2));
+    assert_eq!(stats.to_string(),
+"nodes: 6
+edges: 4
+clusters: 1
+degrees: 0: 1, 1: 4, 4: 1
+density: 0.133
+nesting depth: 1
+longest path: app -> http -> tls (2 edges)
+isolated: docs
+fan-in: http 2, tls 1, db 1
+fan-out: http 2, app 1, cli 1
+");
+}
+
+#[test]
+fn stats_cyclic_and_undirected() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::chain(&["a", "b", "a"], ""));
+    let stats = graph.stats();
+    assert_eq!(stats.longest_path, None);
+    assert!(stats.to_string().contains("longest path: (cyclic)\n"));
+
+    let mut graph = Graph::new("G", Kind::Graph);
+    graph.add_edge(Edge::chain(&["a", "b", "c"], ""));
+    let stats = graph.stats();
+    assert_eq!(stats.density, 2.0 / 3.0);
+    assert_eq!(stats.fan_in, stats.fan_out);
+    assert_eq!(stats.fan_in[0], (String::from("b"), 2));
+    assert_eq!(stats.nesting_depth, 0);
+
+    // sparse graphs keep their density, and fewer top nodes can be asked for
+    let mut graph = Graph::new("G", Kind::Digraph);
+    for i in 0..40 {
+        graph.add_node(Node::new(&format!("n{}", i)));
+    }
+    graph.add_edge(Edge::chain(&["n0", "n1", "n2"], ""));
+    assert!(graph.stats().to_string().contains("density: 0.00128\n"));
+    assert_eq!(graph.stats_with(1).fan_out, vec![(String::from("n0"), 1)]);
+    assert_eq!(graph.stats_with(0).fan_in, vec![]);
+
+    let stats = Graph::new("G", Kind::Digraph).stats();
+    assert_eq!(stats.longest_path, Some(vec![]));
+    assert!(stats.to_string().contains("longest path: (none)\n"));
+}
+
+#[test]
+fn stats_to_json() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::new("a", "b", ""));
+    assert_eq!(graph.stats().to_json(),
+r#"{
+  "nodes": 2,
+  "edges": 1,
+  "clusters": 0,
+  "degrees": [
+    [1, 2]
+  ],
+  "density": 0.5,
+  "nesting_depth": 0,
+  "longest_path": ["a", "b"],
+  "isolated": [],
+  "fan_in": [
+    ["b", 1]
+  ],
+  "fan_out": [
+    ["a", 1]
+  ]
+}
+"#);
+}
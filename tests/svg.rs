@@ -0,0 +1,151 @@
+use dot_graph::{
+    layout::{ClusterLayout, EdgeLayout, LayoutOptions, LayoutResult, NodeLayout, Point},
+    Arrow, ArrowShape, Edge, Fill, Graph, Kind, Node, Side, Style, Subgraph,
+};
+
+fn node(name: &str, x: f64, y: f64) -> NodeLayout {
+    NodeLayout { name: String::from(name), x, y, width: 54.0, height: 36.0 }
+}
+
+#[test]
+fn document() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c = Subgraph::new("cluster_0").label("group");
+    c.add_node(Node::new("a").shape(Some("box")).style(Style::Filled).color(Some("red")).url(String::from("http://a")));
+    graph.add_subgraph(c);
+    graph.add_edge(Edge::new("a", "b", "x->y").end_arrow(Arrow::from_arrow(ArrowShape::Diamond(Fill::Open, Side::Both))));
+    let layout = LayoutResult {
+        width: 100.0,
+        height: 150.0,
+        nodes: vec![node("a", 50.0, 40.0), node("b", 50.0, 120.0)],
+        edges: vec![EdgeLayout {
+            edge: 0,
+            from: String::from("a"),
+            to: String::from("b"),
+            points: vec![Point::new(50.0, 58.0), Point::new(50.0, 102.0)],
+            spline: false,
+            label_pos: Some(Point::new(50.0, 80.0)),
+        }],
+        clusters: vec![ClusterLayout { name: String::from("cluster_0"), x: 50.0, y: 34.0, width: 70.0, height: 60.0 }],
+    };
+
+    assert_eq!(graph.to_svg(&layout), r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN"
+ "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd">
+<svg width="100pt" height="150pt"
+ viewBox="0.00 0.00 100.00 150.00" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+<g id="graph0" class="graph">
+<title>G</title>
+<polygon fill="white" stroke="none" points="0,0 100,0 100,150 0,150 0,0"/>
+<!-- cluster_0 -->
+<g id="clust1" class="cluster">
+<title>cluster_0</title>
+<polygon fill="none" stroke="black" points="15,4 85,4 85,64 15,64 15,4"/>
+<text text-anchor="middle" x="50" y="20.2" font-family="Times,serif" font-size="14.00">group</text>
+</g>
+<!-- a -->
+<g id="node1" class="node">
+<title>a</title>
+<g id="a_node1"><a xlink:href="http://a" xlink:title="a">
+<polygon fill="red" stroke="red" points="23,22 77,22 77,58 23,58 23,22"/>
+<text text-anchor="middle" x="50" y="44.2" font-family="Times,serif" font-size="14.00">a</text>
+</a>
+</g>
+</g>
+<!-- b -->
+<g id="node2" class="node">
+<title>b</title>
+<ellipse fill="none" stroke="black" cx="50" cy="120" rx="27" ry="18"/>
+<text text-anchor="middle" x="50" y="124.2" font-family="Times,serif" font-size="14.00">b</text>
+</g>
+<!-- a&#45;&gt;b -->
+<g id="edge1" class="edge">
+<title>a&#45;&gt;b</title>
+<path fill="none" stroke="black" d="M50,58L50,90"/>
+<polygon fill="none" stroke="black" points="50,102 53.5,96 50,90 46.5,96 50,102"/>
+<text text-anchor="middle" x="50" y="84.2" font-family="Times,serif" font-size="14.00">x-&gt;y</text>
+</g>
+</g>
+</svg>
+"#);
+}
+
+#[test]
+fn arrows() {
+    let shapes = [
+        ArrowShape::Normal(Fill::Open, Side::Left),
+        ArrowShape::Inv(Fill::Filled, Side::Right),
+        ArrowShape::Box(Fill::Open, Side::Both),
+        ArrowShape::Diamond(Fill::Filled, Side::Both),
+        ArrowShape::Dot(Fill::Open),
+        ArrowShape::Vee(Side::Both),
+        ArrowShape::Crow(Side::Left),
+        ArrowShape::Tee(Side::Both),
+        ArrowShape::Curve(Side::Both),
+        ArrowShape::ICurve(Fill::Filled, Side::Right),
+    ];
+    let mut graph = Graph::new("G", Kind::Digraph);
+    for (i, shape) in shapes.iter().enumerate() {
+        graph.add_edge(Edge::new("a", format!("n{}", i).as_str(), "").end_arrow(Arrow::from_arrow(*shape)));
+    }
+    graph.add_edge(Edge::new("a", "z", "").end_arrow(Arrow::none()));
+    let svg = graph.to_svg(&graph.layered_layout(&LayoutOptions::default()));
+
+    assert_eq!(svg.matches("class=\"edge\"").count(), 11);
+    assert_eq!(svg.matches("<ellipse fill=\"none\" stroke=\"black\" cx=").count(), 12 + 1);
+    // the edge without an arrow ends on the outline of z
+    let last = svg.rsplit("<g id=\"edge11\" class=\"edge\">").next().unwrap();
+    assert_eq!(last.matches("<path").count(), 1);
+    assert!(!last.contains("<polygon"));
+    assert!(svg.contains("fill=\"none\" stroke=\"black\" points="));
+    assert!(svg.contains("<path fill=\"none\" stroke=\"black\" d=\"M"));
+}
+
+#[test]
+fn styles_and_links() {
+    let mut graph = Graph::new("G", Kind::Graph);
+    graph.add_node(Node::new("a").label("left\\lright\\r").style(Style::Rounded).shape(Some("box")));
+    graph.add_node(Node::new("b").style(Style::Invisible));
+    graph.add_node(Node::new("c").shape(Some("doublecircle")).style(Style::Dashed));
+    graph.add_edge(Edge::new("a", "c", "").style(Style::Dotted).url(String::from("http://e?a&b")));
+    graph.add_edge(Edge::new("a", "b", "l").label_url(String::from("http://l")));
+    graph.add_edge(
+        Edge::new("b", "c", "").start_arrow(Arrow::from_arrow(ArrowShape::tee())).end_arrow(Arrow::from_arrow(ArrowShape::vee())),
+    );
+    let svg = graph.to_svg(&graph.layered_layout(&LayoutOptions::default()));
+
+    assert!(svg.contains("<title>a&#45;&#45;c</title>"));
+    assert!(svg.contains("text-anchor=\"start\""));
+    assert!(svg.contains("text-anchor=\"end\""));
+    assert!(svg.contains("<path fill=\"none\" stroke=\"black\" d=\"M"));
+    assert!(svg.contains("stroke-dasharray=\"5,2\""));
+    assert!(svg.contains("stroke-dasharray=\"1,5\""));
+    assert!(svg.contains("<g id=\"a_edge1\"><a xlink:href=\"http://e?a&amp;b\" xlink:title=\"\">"));
+    assert!(!svg.contains("a_edge2\""));
+    assert!(svg.contains("<g id=\"a_edge2-label\"><a xlink:href=\"http://l\" xlink:title=\"l\">"));
+
+    // the invisible node keeps its group but draws nothing
+    let b = svg.split("<g id=\"node2\" class=\"node\">").nth(1).unwrap();
+    assert!(b.starts_with("\n<title>b</title>\n</g>"));
+    // an undirected edge only has arrows when both ends are set
+    assert_eq!(svg.matches("<polygon fill=\"black\"").count(), 2);
+    assert_eq!(svg.matches("<polyline").count(), 1);
+}
+
+#[test]
+fn stale_layout_and_undeclared_names() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::new("a", "1", ""));
+    let layout = graph.layered_layout(&LayoutOptions::default());
+    assert!(graph.to_svg(&layout).contains("<title>a&#45;&gt;1</title>"));
+
+    // a layout of another graph draws its nodes, but not the edges the
+    // graph does not have
+    let other = Graph::new("G", Kind::Digraph);
+    let svg = other.to_svg(&layout);
+    assert!(svg.contains("<title>1</title>"));
+    assert!(!svg.contains("class=\"edge\""));
+    let mut edited = Graph::new("G", Kind::Digraph);
+    edited.add_edge(Edge::new("b", "c", ""));
+    assert!(!edited.to_svg(&layout).contains("class=\"edge\""));
+}
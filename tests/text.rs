@@ -0,0 +1,122 @@
+use dot_graph::{text::TextOptions, Arrow, Edge, Graph, Kind, Node, Style, Subgraph};
+
+fn steps() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_node(Node::new("a").shape(Some("box")).label("start"));
+    graph.add_edge(Edge::new("a", "b", "go"));
+    graph.add_edge(Edge::new("b", "b", "loop"));
+    graph.add_edge(Edge::new("a", "c", ""));
+    graph.add_edge(Edge::new("b", "c", "").end_arrow(Arrow::none()));
+    graph
+}
+
+#[test]
+fn unicode() {
+    assert_eq!(steps().to_text(&TextOptions::default()),
+r#"  ┌───────┐
+  │ start │
+  └─┬───┬─┘
+  ┌─┘   │
+  ▼ go  │
+╭───╮◄┐ │
+│ b ├─┘ │
+╰─┬─╯   │
+  └─┐ ┌─┘
+    │ ▼
+   ╭┴──╮
+   │ c │
+   ╰───╯
+"#);
+}
+
+#[test]
+fn ascii() {
+    assert_eq!(steps().to_text(&TextOptions { ascii: true, ..Default::default() }),
+r#"  +-------+
+  | start |
+  +-+---+-+
+  +-+   |
+  v go  |
++---+<+ |
+| b +-+ |
++-+-+   |
+  +-+ +-+
+    | v
+   ++--+
+   | c |
+   +---+
+"#);
+}
+
+#[test]
+fn clusters() {
+    let mut graph = Graph::new("G", Kind::Graph);
+    let mut db = Subgraph::new("cluster_0").label("db");
+    db.add_node(Node::new("primary"));
+    db.add_node(Node::new("replica").style(Style::Dashed));
+    db.add_edge(Edge::new("primary", "replica", ""));
+    graph.add_subgraph(db);
+    graph.add_edge(Edge::new("app", "primary", ""));
+    graph.add_edge(Edge::new("app", "cache", ""));
+
+    assert_eq!(graph.to_text(&TextOptions::default()),
+r#"             ╭─────╮
+             │ app │
+             ╰─┬─┬─╯
+       ┌───────┘ └────┐
+┌─ db ─│──────┐       │
+│      │      │       │
+│ ╭────┴────╮ │   ╭───┴───╮
+│ │ primary │ │   │ cache │
+│ ╰────┬────╯ │   ╰───────╯
+│      │      │
+│ ┌╌╌╌╌┴╌╌╌╌┐ │
+│ ╎ replica ╎ │
+│ └╌╌╌╌╌╌╌╌╌┘ │
+└─────────────┘
+"#);
+}
+
+#[test]
+fn width_limit() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    for n in ["alpha_service", "beta_service", "gamma_service", "delta_service"] {
+        graph.add_edge(Edge::new("root", n, ""));
+    }
+    let wide = graph.to_text(&TextOptions::default());
+    assert!(wide.contains("│ alpha_service │"));
+    assert!(wide.lines().all(|l| l.chars().count() <= 80));
+
+    assert_eq!(graph.to_text(&TextOptions { max_width: 50, ascii: false }),
+r#"                     ╭──────╮
+                     │ root │
+                     ╰┬─┬┬─┬╯
+     ┌────────────────┘ ││ └─────────────────┐
+     │            ┌─────┘│                   │
+     │            │      └─────┐             │
+     ▼            ▼            ▼             ▼
+╭────────╮   ╭────────╮   ╭────────╮    ╭────────╮
+│ alpha… │   │ beta_… │   │ gamma… │    │ delta… │
+╰────────╯   ╰────────╯   ╰────────╯    ╰────────╯
+"#);
+
+    // labels are kept when cutting them does not help
+    let narrow = graph.to_text(&TextOptions { max_width: 10, ascii: false });
+    assert_eq!(narrow, wide);
+}
+
+#[test]
+fn same_rank() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_node(Node::new("a"));
+    graph.add_node(Node::new("b"));
+    graph.same_rank(&["a", "b"]);
+    graph.add_edge(Edge::new("b", "a", "back"));
+
+    assert_eq!(graph.to_text(&TextOptions::default()),
+r#"╭───╮   ╭───╮
+│ a │◄──┤ b │
+╰───╯   ╰───╯
+"#);
+    assert_eq!(Graph::new("G", Kind::Digraph).to_text(&TextOptions::default()), "");
+}
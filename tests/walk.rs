@@ -0,0 +1,127 @@
+use std::borrow::Cow;
+
+use dot_graph::{render_walk, Edges, GraphWalk, Kind, Labeller, Nodes, Style};
+
+// A crate dependency graph kept in its own data structure.
+struct Deps {
+    crates: Vec<(&'static str, Option<&'static str>)>,
+    deps: Vec<(usize, usize)>,
+}
+
+impl Labeller<usize, (usize, usize)> for Deps {
+    fn graph_id(&self) -> String {
+        String::from("deps")
+    }
+
+    fn node_id(&self, n: &usize) -> String {
+        format!("N{}", n)
+    }
+
+    fn node_label(&self, n: &usize) -> String {
+        String::from(self.crates[*n].0)
+    }
+
+    fn node_cluster(&self, n: &usize) -> Option<String> {
+        self.crates[*n].1.map(String::from)
+    }
+
+    fn cluster_label(&self, cluster: &str) -> String {
+        cluster.trim_start_matches("cluster_").to_string()
+    }
+
+    fn edge_style(&self, e: &(usize, usize)) -> Style {
+        if e.0 == 0 { Style::Bold } else { Style::None }
+    }
+}
+
+impl<'a> GraphWalk<'a, usize, (usize, usize)> for Deps {
+    fn nodes(&'a self) -> Nodes<'a, usize> {
+        (0..self.crates.len()).collect()
+    }
+
+    fn edges(&'a self) -> Edges<'a, (usize, usize)> {
+        Cow::Borrowed(&self.deps)
+    }
+
+    fn source(&'a self, edge: &(usize, usize)) -> usize {
+        edge.0
+    }
+
+    fn target(&'a self, edge: &(usize, usize)) -> usize {
+        edge.1
+    }
+}
+
+#[test]
+fn render_user_structure() {
+    let deps = Deps {
+        crates: vec![("app", None), ("serde", Some("cluster_std")), ("log", Some("cluster_std")), ("cli", None)],
+        deps: vec![(0, 1), (0, 2), (3, 2)],
+    };
+    let mut writer = Vec::new();
+    render_walk(&deps, &mut writer).unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(),
+r#"digraph deps {
+    subgraph cluster_std {
+        label="std";
+        "N1"[label="serde"];
+        "N2"[label="log"];
+    }
+    "N0"[label="app"];
+    "N3"[label="cli"];
+    "N0" -> "N1"[label=""][style="bold"];
+    "N0" -> "N2"[label=""][style="bold"];
+    "N3" -> "N2"[label=""];
+}
+"#);
+}
+
+struct Ring(usize);
+
+impl Labeller<usize, usize> for Ring {
+    fn graph_id(&self) -> String {
+        String::from("ring")
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::Graph
+    }
+
+    fn node_id(&self, n: &usize) -> String {
+        format!("r{}", n)
+    }
+}
+
+impl<'a> GraphWalk<'a, usize, usize> for Ring {
+    fn nodes(&'a self) -> Nodes<'a, usize> {
+        (0..self.0).collect()
+    }
+
+    fn edges(&'a self) -> Edges<'a, usize> {
+        (0..self.0).collect()
+    }
+
+    fn source(&'a self, edge: &usize) -> usize {
+        *edge
+    }
+
+    fn target(&'a self, edge: &usize) -> usize {
+        (*edge + 1) % self.0
+    }
+}
+
+#[test]
+fn render_undirected_with_defaults() {
+    let mut writer = Vec::new();
+    render_walk(&Ring(3), &mut writer).unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(),
+r#"graph ring {
+    "r0"[label="r0"];
+    "r1"[label="r1"];
+    "r2"[label="r2"];
+    "r0" -- "r1"[label=""];
+    "r1" -- "r2"[label=""];
+    "r2" -- "r0"[label=""];
+}
+"#);
+}
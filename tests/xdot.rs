@@ -0,0 +1,140 @@
+use dot_graph::{layout::{EdgeLayout, NodeLayout, Point}, Edge, Graph, Kind, Node, Subgraph};
+
+fn graph() -> Graph {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    let mut c0 = Subgraph::new("cluster_0").label("");
+    c0.add_node(Node::new("b"));
+    graph.add_subgraph(c0);
+    graph.add_node(Node::new("a"));
+    graph.add_edge(Edge::new("a", "b", "x"));
+    graph.add_edge(Edge::new("a", "c", ""));
+    graph
+}
+
+const PLAIN: &str = r#"graph 1 1.5 2.5
+node b 0.375 0.25 0.75 0.5 b solid ellipse black lightgrey
+node a 0.75 2.25 0.75 0.5 a solid ellipse black lightgrey
+node c 1.125 0.25 0.75 0.5 "label of c" solid box black lightgrey
+edge a c 4 0.875 2 1 1.5 1 1 1.125 0.5 solid black
+edge a b 4 0.625 2 0.5 1.5 0.5 1 0.375 0.5 x 0.75 1.25 solid black
+stop
+"#;
+
+const XDOT: &str = r#"digraph G {
+	graph [bb="0,0,108,180",
+		_draw_="c 9 -#fffffe00 C 7 -#ffffff P 4 0 0 0 180 108 180 108 0 ",
+		compound=true
+	];
+	node [label="\N"];
+	subgraph cluster_0 {
+		graph [bb="8,8,100,64",
+			label=""];
+		b	[height=0.5,
+			pos="27,36",
+			width=0.75];
+	}
+	a	[height=0.5,
+		label=<<b>a</b>>,
+		pos="54,162",
+		width=0.75];
+	a -> b	[label=x,
+		lp="48,99",
+		pos="e,31.5,54 49.5,144 45,126 40.5,108 36,90 33.75,81 31.5,72 31.5,\
+63"];
+	a -> c	[pos="e,81,54 58.5,144 63,126 72,99 81,72"];
+	c	[height=0.5,
+		pos="81,36",
+		width=0.75];
+}
+"#;
+
+#[test]
+fn plain() {
+    let layout = graph().layout_from_plain(PLAIN).unwrap();
+    assert_eq!((layout.width, layout.height), (108.0, 180.0));
+    assert_eq!(layout.nodes.len(), 3);
+    assert_eq!(layout.node("a"), Some(&NodeLayout { name: String::from("a"), x: 54.0, y: 18.0, width: 54.0, height: 36.0 }));
+    assert_eq!(layout.node("c").unwrap().x, 81.0);
+
+    // edges keep their index in all_edges, whatever the order of the lines
+    let ab = &layout.edges[1];
+    assert_eq!(ab, &EdgeLayout {
+        edge: 0,
+        from: String::from("a"),
+        to: String::from("b"),
+        points: vec![Point::new(45.0, 36.0), Point::new(36.0, 72.0), Point::new(36.0, 108.0), Point::new(27.0, 144.0)],
+        spline: true,
+        label_pos: Some(Point::new(54.0, 90.0)),
+    });
+    assert_eq!(layout.edges[0].edge, 1);
+    assert_eq!(layout.edges[0].label_pos, None);
+    assert!(layout.clusters.is_empty());
+
+    assert!(graph().layout_from_plain("graph 1 1 1\nedge b a 4 0 0 0 0 0 0 0 0 solid black\n").is_err());
+    assert!(graph().layout_from_plain("graph 1 x 1\n").is_err());
+    assert!(graph().layout_from_plain("graph 1 1 1\nnode\n").is_err());
+}
+
+#[test]
+fn xdot() {
+    let layout = graph().layout_from_xdot(XDOT).unwrap();
+    assert_eq!((layout.width, layout.height), (108.0, 180.0));
+    assert_eq!(layout.node("a"), Some(&NodeLayout { name: String::from("a"), x: 54.0, y: 18.0, width: 54.0, height: 36.0 }));
+    assert_eq!(layout.node("b").unwrap().y, 144.0);
+
+    // straight segments out to the arrow tip
+    let ab = &layout.edges[0];
+    assert_eq!((ab.edge, ab.points.len()), (0, 10));
+    assert_eq!(ab.points[0], Point::new(49.5, 36.0));
+    assert_eq!(&ab.points[7..], &[Point::new(31.5, 117.0), Point::new(31.5, 126.0), Point::new(31.5, 126.0)]);
+    assert_eq!(ab.label_pos, Some(Point::new(48.0, 81.0)));
+
+    let cluster = layout.cluster("cluster_0").unwrap();
+    assert_eq!((cluster.x, cluster.y, cluster.width, cluster.height), (54.0, 144.0, 92.0, 56.0));
+
+    assert!(graph().layout_from_xdot("digraph G { b -> a [pos=\"1,2\"] }").is_err());
+    assert!(graph().layout_from_xdot("digraph G { a [pos=\"1,2").is_err());
+}
+
+#[test]
+fn apply_layout() {
+    let mut graph = graph();
+    let layout = graph.layout_from_xdot(XDOT).unwrap();
+    graph.apply_layout(&layout);
+    assert_eq!(graph.to_dot_string().unwrap(),
+r#"digraph G {
+    bb="0,0,108,180"
+    subgraph cluster_0 {
+        label="";
+        bb="8,8,100,64";
+        "b"[label="b"][pos="27,36"];
+    }
+    "a"[label="a"][pos="54,162"];
+    "c"[label="c"][pos="81,36"];
+    "a" -> "b"[label="x"][pos="49.5,144 45,126 40.5,108 36,90 33.75,81 31.5,72 31.5,63 31.5,63 31.5,54 31.5,54"];
+    "a" -> "c"[label=""][pos="58.5,144 63,126 72,99 81,72 81,72 81,54 81,54"];
+}
+"#);
+
+    // polylines become straight cubic segments
+    let mut graph = Graph::new("G", Kind::Graph);
+    graph.add_edge(Edge::new("a", "b", ""));
+    let layout = graph.layered_layout(&dot_graph::layout::LayoutOptions { splines: false, ..Default::default() });
+    graph.apply_layout(&layout);
+    let dot = graph.to_dot_string().unwrap();
+    let edge = dot.lines().find(|l| l.contains("--")).unwrap();
+    let pos = edge.split("pos=\"").nth(1).unwrap().trim_end_matches("\"];");
+    assert_eq!(pos.split(' ').count(), 4);
+    assert!(dot.contains("\"a\"[label=\"a\"][pos="));
+}
+
+#[test]
+fn apply_layout_with_undeclared_names() {
+    let mut graph = Graph::new("G", Kind::Digraph);
+    graph.add_edge(Edge::new("1", "x y", ""));
+    let layout = graph.layered_layout(&Default::default());
+    graph.apply_layout(&layout);
+    let dot = graph.to_dot_string().unwrap();
+    assert!(dot.contains("\"1\"[label=\"1\"][pos="));
+    assert!(dot.contains("\"x y\"[label=\"x y\"][pos="));
+}